@@ -0,0 +1,165 @@
+//! Types for OpenWeather's air pollution API
+
+use crate::GeodeticCoords;
+use serde::{Deserialize, Serialize};
+use strum::Display;
+use thiserror::Error;
+
+/// OpenWeather response from the air pollution API, more details
+/// [here](https://openweathermap.org/api/air-pollution).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAirPollutionResponse {
+    pub coord: GeodeticCoords,
+
+    /// Air quality readings in chronological order, a single entry for current conditions or one per hour
+    /// for forecasts and history
+    pub list: Vec<OWAirPollutionEntry>,
+}
+
+/// Air quality at a single moment
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAirPollutionEntry {
+    /// Time of the reading, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    pub main: OWAirQuality,
+
+    pub components: OWPollutants,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAirQuality {
+    /// Air Quality Index
+    #[serde(deserialize_with = "from_raw_aqi", serialize_with = "to_raw_aqi")]
+    pub aqi: AirQualityIndex,
+}
+
+/// Pollutant concentrations, all in μg/m3
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWPollutants {
+    /// Carbon monoxide
+    pub co: f32,
+
+    /// Nitrogen monoxide
+    pub no: f32,
+
+    /// Nitrogen dioxide
+    pub no2: f32,
+
+    /// Ozone
+    pub o3: f32,
+
+    /// Sulphur dioxide
+    pub so2: f32,
+
+    /// Fine particulate matter
+    pub pm2_5: f32,
+
+    /// Coarse particulate matter
+    pub pm10: f32,
+
+    /// Ammonia
+    pub nh3: f32,
+}
+
+/// OpenWeather's Air Quality Index, from 1 (good) to 5 (very poor)
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AirQualityIndex {
+    Good = 1,
+    Fair,
+    Moderate,
+    Poor,
+    #[strum(serialize = "Very Poor")]
+    VeryPoor,
+}
+
+#[derive(Error, Debug)]
+pub enum AirQualityIndexError {
+    #[error("provided air quality index of `{0}` is outside the valid range [1, 5]")]
+    InvalidIndex(u8),
+}
+
+impl AirQualityIndex {
+    /// Validates the index falls inside OpenWeather's 1 to 5 scale.
+    pub fn new_checked(index: u8) -> Result<Self, AirQualityIndexError> {
+        match index {
+            1 => Ok(AirQualityIndex::Good),
+            2 => Ok(AirQualityIndex::Fair),
+            3 => Ok(AirQualityIndex::Moderate),
+            4 => Ok(AirQualityIndex::Poor),
+            5 => Ok(AirQualityIndex::VeryPoor),
+            _ => Err(AirQualityIndexError::InvalidIndex(index)),
+        }
+    }
+
+    /// The numeric index, from 1 to 5
+    pub fn value(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// Custom deserializer for the Air Quality Index
+fn from_raw_aqi<'de, D>(deserializer: D) -> Result<AirQualityIndex, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let index = u8::deserialize(deserializer)?;
+
+    AirQualityIndex::new_checked(index).map_err(serde::de::Error::custom)
+}
+
+/// Custom serializer for the Air Quality Index, writing it back as OpenWeather's bare number
+fn to_raw_aqi<S>(index: &AirQualityIndex, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_u8(index.value())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const AIR_POLLUTION_RESPONSE: &str = r#"
+        {
+        "coord": { "lon": 50, "lat": 50 },
+        "list": [
+            {
+            "dt": 1605182400,
+            "main": { "aqi": 3 },
+            "components": {
+                "co": 201.94053649902344,
+                "no": 0.01877197064459324,
+                "no2": 0.7711350917816162,
+                "o3": 68.66455078125,
+                "so2": 0.6407499313354492,
+                "pm2_5": 40.5,
+                "pm10": 0.540438711643219,
+                "nh3": 0.12369127571582794
+            }
+            }
+        ]
+        }
+        "#;
+
+    #[test]
+    fn parse_air_pollution() {
+        let response: OWAirPollutionResponse =
+            serde_json::from_str(AIR_POLLUTION_RESPONSE).unwrap();
+
+        let entry = &response.list[0];
+        assert_eq!(entry.main.aqi, AirQualityIndex::Moderate);
+        assert_eq!(entry.main.aqi.value(), 3);
+        assert_eq!(entry.components.pm2_5, 40.5);
+    }
+
+    #[test]
+    fn invalid_aqi() {
+        assert!(AirQualityIndex::new_checked(0).is_err());
+        assert!(AirQualityIndex::new_checked(6).is_err());
+        assert_eq!(AirQualityIndex::VeryPoor.to_string(), "Very Poor");
+
+        let json = AIR_POLLUTION_RESPONSE.replace(r#""aqi": 3"#, r#""aqi": 9"#);
+        assert!(serde_json::from_str::<OWAirPollutionResponse>(&json).is_err());
+    }
+}
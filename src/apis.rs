@@ -1,7 +1,16 @@
 //! Interactions with the OpenWeather HTTP API
 
-use crate::{GeodeticCoords, OWCurrentWeatherResponse, WeatherUnits};
-use log::error;
+use crate::{
+    GeoLocation, GeodeticCoords, Language, OWAirPollutionResponse, OWClimateForecastResponse,
+    OWCurrentWeatherResponse, OWDailyForecastResponse, OWDaySummary, OWForecastResponse,
+    OWHourlyForecastResponse, OWOneCallResponse, OWRoadRiskEntry, OWSolarRadiationResponse,
+    OWTimeMachineResponse, OWWeatherOverview, OneCallBlock, OpenWeatherClient, ResponseMode,
+    RoadRiskWaypoint, WeatherUnits, ZipLocation,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Errors that occur at the API boundary with OpenWeather
@@ -21,37 +30,866 @@ pub enum OpenWxError {
 
     #[error("HTTP GET from OpenWeather failed")]
     HttpGetError(#[from] reqwest::Error),
+
+    #[error("no default coordinates were configured on the client")]
+    NoDefaultCoords,
+
+    #[error("failed to build the HTTP client")]
+    HttpClientError(#[source] reqwest::Error),
+
+    #[error("the HTTP transport doesn't support {0} requests")]
+    UnsupportedMethod(&'static str),
+
+    #[error("OpenWeather returned error {code}: {message}")]
+    ApiError {
+        code: u16,
+        message: String,
+
+        /// How long the server asked us to wait before retrying, from the `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+
+    #[error("a concurrent identical request this one was waiting on failed: {0}")]
+    CoalescedRequestFailed(String),
+
+    #[cfg(feature = "xml")]
+    #[error("the XML response from open weather could not be parsed")]
+    XmlParseError(#[from] quick_xml::DeError),
+}
+
+impl OpenWxError {
+    /// The API key is missing, invalid, or not subscribed to the endpoint
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self, OpenWxError::ApiError { code: 401, .. })
+    }
+
+    /// The requested location or resource doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, OpenWxError::ApiError { code: 404, .. })
+    }
+
+    /// The API key has exceeded its call quota
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, OpenWxError::ApiError { code: 429, .. })
+    }
+
+    /// Whether the request might succeed if tried again: network failures, rate limiting, and server errors
+    pub fn is_transient(&self) -> bool {
+        match self {
+            OpenWxError::HttpGetError(e) => e.is_timeout() || e.is_connect(),
+            OpenWxError::ApiError { code, .. } => *code == 429 || (500..600).contains(code),
+            _ => false,
+        }
+    }
+
+    /// How long OpenWeather asked us to wait before trying again, if it said
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            OpenWxError::ApiError { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+/// The body OpenWeather sends along with an unsuccessful status
+#[derive(Deserialize)]
+struct OWErrorBody {
+    /// Status code, which OpenWeather sends as either a string or a number
+    cod: serde_json::Value,
+    message: String,
 }
 
+/// Builds an [`OpenWxError::ApiError`] for an unsuccessful response, preferring OpenWeather's own code and message
+/// from the body and falling back to the HTTP status when the body isn't one of OpenWeather's error bodies (e.g. from
+/// a proxy or load balancer).
+pub(crate) fn api_error(status: u16, retry_after: Option<&str>, body: &str) -> OpenWxError {
+    let retry_after = retry_after
+        .and_then(|seconds| seconds.trim().parse().ok())
+        .map(Duration::from_secs);
+
+    let (code, message) = parse_error_body(body).unwrap_or_else(|| {
+        let reason = reqwest::StatusCode::from_u16(status)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or("unknown error");
+
+        (status, reason.to_string())
+    });
+
+    OpenWxError::ApiError {
+        code,
+        message,
+        retry_after,
+    }
+}
+
+/// The code and message from one of OpenWeather's error bodies
+fn parse_error_body(body: &str) -> Option<(u16, String)> {
+    let error: OWErrorBody = serde_json::from_str(body).ok()?;
+
+    let code = match &error.cod {
+        serde_json::Value::Number(n) => n.as_u64()?.try_into().ok()?,
+        serde_json::Value::String(s) => s.parse().ok()?,
+        _ => return None,
+    };
+
+    Some((code, error.message))
+}
+
+/// Default host for the OpenWeather API
+pub const DEFAULT_BASE_URL: &str = "https://api.openweathermap.org";
+
+/// Host for OpenWeather's paid "pro" endpoints, such as the hourly and climate forecasts
+pub const PRO_BASE_URL: &str = "https://pro.openweathermap.org";
+
+/// Path of OpenWeather's current weather endpoint
+const CURRENT_WEATHER_PATH: &str = "/data/2.5/weather";
+
+/// Path of OpenWeather's 5 day / 3 hour forecast endpoint
+const FORECAST_PATH: &str = "/data/2.5/forecast";
+
+/// Path of OpenWeather's pro 4 day hourly forecast endpoint
+const HOURLY_FORECAST_PATH: &str = "/data/2.5/forecast/hourly";
+
+/// Path of OpenWeather's 16 day daily forecast endpoint
+const DAILY_FORECAST_PATH: &str = "/data/2.5/forecast/daily";
+
+/// Path of OpenWeather's 30 day climate forecast endpoint
+const CLIMATE_FORECAST_PATH: &str = "/data/2.5/forecast/climate";
+
+/// Path of OpenWeather's current air pollution endpoint
+const AIR_POLLUTION_PATH: &str = "/data/2.5/air_pollution";
+
+/// Path of OpenWeather's air pollution forecast endpoint
+const AIR_POLLUTION_FORECAST_PATH: &str = "/data/2.5/air_pollution/forecast";
+
+/// Path of OpenWeather's historical air pollution endpoint
+const AIR_POLLUTION_HISTORY_PATH: &str = "/data/2.5/air_pollution/history";
+
+/// Path of OpenWeather's direct geocoding endpoint
+const DIRECT_GEOCODING_PATH: &str = "/geo/1.0/direct";
+
+/// Path of OpenWeather's reverse geocoding endpoint
+const REVERSE_GEOCODING_PATH: &str = "/geo/1.0/reverse";
+
+/// Path of OpenWeather's zip code geocoding endpoint
+const ZIP_GEOCODING_PATH: &str = "/geo/1.0/zip";
+
+/// Path of OpenWeather's Road Risk endpoint
+pub(crate) const ROAD_RISK_PATH: &str = "/data/2.5/roadrisk";
+
+/// Path of OpenWeather's current solar radiation endpoint
+const SOLAR_RADIATION_PATH: &str = "/data/2.5/solar_radiation";
+
+/// Path of OpenWeather's solar radiation forecast endpoint
+const SOLAR_RADIATION_FORECAST_PATH: &str = "/data/2.5/solar_radiation/forecast";
+
+/// Path of OpenWeather's historical solar radiation endpoint
+const SOLAR_RADIATION_HISTORY_PATH: &str = "/data/2.5/solar_radiation/history";
+
+/// Path of OpenWeather's Weather Triggers collection
+pub(crate) const TRIGGERS_PATH: &str = "/data/3.0/triggers";
+
+/// Path of OpenWeather's Weather Stations collection
+pub(crate) const STATIONS_PATH: &str = "/data/3.0/stations";
+
+/// Path of OpenWeather's station measurements collection
+pub(crate) const MEASUREMENTS_PATH: &str = "/data/3.0/measurements";
+
+/// Path of OpenWeather's One Call 3.0 endpoint
+const ONE_CALL_PATH: &str = "/data/3.0/onecall";
+
+/// Path of One Call's daily aggregation route
+const DAY_SUMMARY_PATH: &str = "/data/3.0/onecall/day_summary";
+
+/// Path of One Call's weather overview route
+const OVERVIEW_PATH: &str = "/data/3.0/onecall/overview";
+
+/// Path of One Call's historical weather route
+const TIME_MACHINE_PATH: &str = "/data/3.0/onecall/timemachine";
+
 /// Request the current weather from OpenWeather, this is a blocking HTTP request.
 pub fn open_weather_request(
     coords: GeodeticCoords,
     units: WeatherUnits,
     api_key: String,
 ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
-    let lat_str = coords.lat.to_string();
-    let lon_str = coords.lon.to_string();
+    OpenWeatherClient::new(api_key, units).current_weather(coords)
+}
 
-    let url = format!(
-        "https://api.openweathermap.org/data/2.5/weather?lat={lat_str}&lon={lon_str}&mode=json&units={units}&appid={api_key}"
+/// Per-call settings for the one-shot request functions like [`open_weather_request_with_options`]. Timeouts left
+/// unset keep `reqwest`'s default, which for blocking requests is no connect timeout and 30 seconds overall.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RequestOptions {
+    /// Scheme and host to send the request to instead of [`DEFAULT_BASE_URL`], e.g. a mock server or proxy
+    pub base_url: Option<String>,
+
+    /// Language to localize descriptions into
+    pub language: Option<Language>,
+
+    /// Timeout for establishing a connection to OpenWeather
+    pub connect_timeout: Option<Duration>,
+
+    /// Timeout for the entire request, from connecting through reading the response body
+    pub timeout: Option<Duration>,
+}
+
+impl RequestOptions {
+    /// Sets the scheme and host to send the request to.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Sets the language to localize descriptions into.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Sets the connect timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the timeout for the entire request.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Builds a client for a single call with these options
+    fn client(
+        &self,
+        api_key: String,
+        units: WeatherUnits,
+    ) -> Result<OpenWeatherClient, OpenWxError> {
+        let mut builder = OpenWeatherClient::builder(api_key).units(units);
+        if let Some(base_url) = &self.base_url {
+            builder = builder.base_url(base_url.as_str());
+        }
+        if let Some(language) = self.language {
+            builder = builder.language(language);
+        }
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        builder.build()
+    }
+}
+
+/// Request the current weather from OpenWeather like [`open_weather_request`], with [`RequestOptions`] such as
+/// timeouts or a language for this call.
+pub fn open_weather_request_with_options(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    api_key: String,
+    options: &RequestOptions,
+) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+    options.client(api_key, units)?.current_weather(coords)
+}
+
+/// Request the current weather from OpenWeather for a city by its OpenWeather city ID, this is a blocking HTTP request.
+/// City IDs are the most stable way to refer to a location across runs and skip geocoding entirely.
+pub fn open_weather_request_by_id(
+    city_id: u32,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).current_weather_by_id(city_id)
+}
+
+/// Request the current weather from OpenWeather in any of the supported [`ResponseMode`]s and return the body
+/// without parsing it, this is a blocking HTTP request. Prefer [`open_weather_request`] for typed JSON responses.
+pub fn raw_weather(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    mode: ResponseMode,
+    api_key: String,
+) -> Result<String, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).raw_current_weather(coords, mode)
+}
+
+/// Request the 5 day / 3 hour forecast from OpenWeather, this is a blocking HTTP request.
+pub fn open_weather_forecast(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWForecastResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).forecast(coords)
+}
+
+/// Forecast path and query for a query position
+pub(crate) fn forecast_path(coords: &GeodeticCoords, units: &WeatherUnits) -> String {
+    format!("{FORECAST_PATH}?{}&units={units}", lat_lon_params(coords))
+}
+
+/// Request the 4 day hourly forecast from OpenWeather's [`PRO_BASE_URL`], this is a blocking HTTP request. The hourly
+/// forecast requires a paid subscription.
+pub fn open_weather_hourly_forecast(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWHourlyForecastResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).hourly_forecast(coords)
+}
+
+/// Hourly forecast path and query for a query position
+pub(crate) fn hourly_forecast_path(coords: &GeodeticCoords, units: &WeatherUnits) -> String {
+    format!(
+        "{HOURLY_FORECAST_PATH}?{}&units={units}",
+        lat_lon_params(coords)
+    )
+}
+
+/// Request the daily forecast from OpenWeather for up to 16 days, this is a blocking HTTP request. `days` limits the
+/// number of days returned, OpenWeather's default is 7. The daily forecast requires a paid subscription.
+pub fn open_weather_daily_forecast(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    days: Option<u8>,
+    api_key: String,
+) -> Result<OWDailyForecastResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).daily_forecast(coords, days)
+}
+
+/// Daily forecast path and query for a query position, limited to `days` entries if provided
+pub(crate) fn daily_forecast_path(
+    coords: &GeodeticCoords,
+    units: &WeatherUnits,
+    days: Option<u8>,
+) -> String {
+    let mut path = format!(
+        "{DAILY_FORECAST_PATH}?{}&units={units}",
+        lat_lon_params(coords)
     );
 
-    // This makes a new Client on each GET, but we're making requests so infrequently this is totally fine.
-    let response_text = reqwest::blocking::get(url)?
-        .error_for_status()
-        .map_err(OpenWxError::HttpGetError)?
-        .text()?;
+    if let Some(days) = days {
+        path.push_str(&format!("&cnt={days}"));
+    }
 
-    // First get the untyped JSON blob so we log it in the event of a parsing failure
-    let response_json: serde_json::Value = serde_json::from_str(&response_text)?;
+    path
+}
+
+/// Request the 30 day climate forecast from OpenWeather's [`PRO_BASE_URL`], this is a blocking HTTP request. The
+/// climate forecast requires a paid subscription.
+pub fn open_weather_climate_forecast(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWClimateForecastResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).climate_forecast(coords)
+}
+
+/// Climate forecast path and query for a query position
+pub(crate) fn climate_forecast_path(coords: &GeodeticCoords, units: &WeatherUnits) -> String {
+    format!(
+        "{CLIMATE_FORECAST_PATH}?{}&units={units}",
+        lat_lon_params(coords)
+    )
+}
+
+/// Request current air pollution from OpenWeather, this is a blocking HTTP request.
+pub fn open_weather_air_pollution(
+    coords: GeodeticCoords,
+    api_key: String,
+) -> Result<OWAirPollutionResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).air_pollution(coords)
+}
+
+/// Current air pollution path and query for a query position, air pollution has no notion of units
+pub(crate) fn air_pollution_path(coords: &GeodeticCoords) -> String {
+    format!("{AIR_POLLUTION_PATH}?{}", lat_lon_params(coords))
+}
+
+/// Request the hourly air pollution forecast for the next 4 days from OpenWeather, this is a blocking HTTP request.
+pub fn open_weather_air_pollution_forecast(
+    coords: GeodeticCoords,
+    api_key: String,
+) -> Result<OWAirPollutionResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).air_pollution_forecast(coords)
+}
+
+/// Air pollution forecast path and query for a query position
+pub(crate) fn air_pollution_forecast_path(coords: &GeodeticCoords) -> String {
+    format!("{AIR_POLLUTION_FORECAST_PATH}?{}", lat_lon_params(coords))
+}
+
+/// Request hourly air pollution readings between `start` and `end` from OpenWeather, this is a blocking HTTP request.
+/// Data is available from November 27th, 2020.
+pub fn open_weather_air_pollution_history(
+    coords: GeodeticCoords,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    api_key: String,
+) -> Result<OWAirPollutionResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard)
+        .air_pollution_history(coords, start, end)
+}
+
+/// Historical air pollution path and query for a query position over a time range
+pub(crate) fn air_pollution_history_path(
+    coords: &GeodeticCoords,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> String {
+    format!(
+        "{AIR_POLLUTION_HISTORY_PATH}?{}&start={}&end={}",
+        lat_lon_params(coords),
+        start.timestamp(),
+        end.timestamp()
+    )
+}
+
+/// Resolve a place name like "San Diego,CA,US" into matching locations, this is a blocking HTTP request. The query is
+/// a city name optionally followed by a state code (US only) and an ISO 3166 country code, separated by commas.
+/// `limit` caps the number of results, up to OpenWeather's maximum of 5.
+pub fn open_weather_geocode(
+    query: &str,
+    limit: Option<u8>,
+    api_key: String,
+) -> Result<Vec<GeoLocation>, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).geocode(query, limit)
+}
+
+/// Direct geocoding path and query for a place name, limited to `limit` results if provided
+pub(crate) fn direct_geocoding_path(query: &str, limit: Option<u8>) -> String {
+    let mut path = format!("{DIRECT_GEOCODING_PATH}?q={}", encode_query_value(query));
+
+    if let Some(limit) = limit {
+        path.push_str(&format!("&limit={limit}"));
+    }
+
+    path
+}
+
+/// Find the names of places near a position, this is a blocking HTTP request. `limit` caps the number of results, up
+/// to OpenWeather's maximum of 5.
+pub fn open_weather_reverse_geocode(
+    coords: GeodeticCoords,
+    limit: Option<u8>,
+    api_key: String,
+) -> Result<Vec<GeoLocation>, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).reverse_geocode(coords, limit)
+}
+
+/// Reverse geocoding path and query for a query position, limited to `limit` results if provided
+pub(crate) fn reverse_geocoding_path(coords: &GeodeticCoords, limit: Option<u8>) -> String {
+    let mut path = format!("{REVERSE_GEOCODING_PATH}?{}", lat_lon_params(coords));
+
+    if let Some(limit) = limit {
+        path.push_str(&format!("&limit={limit}"));
+    }
 
-    let parsed: OWCurrentWeatherResponse =
-        serde_json::from_value(response_json.clone()).map_err(|err| {
-            OpenWxError::ResponseParseError {
-                input_json: response_json,
-                parse_error: err,
+    path
+}
+
+/// Resolve a zip or postal code in the country with ISO 3166 code `country` into a location, this is a blocking HTTP
+/// request.
+pub fn open_weather_geocode_zip(
+    zip: &str,
+    country: &str,
+    api_key: String,
+) -> Result<ZipLocation, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).geocode_zip(zip, country)
+}
+
+/// Zip code geocoding path and query for a zip code in a country
+pub(crate) fn zip_geocoding_path(zip: &str, country: &str) -> String {
+    format!(
+        "{ZIP_GEOCODING_PATH}?zip={},{}",
+        encode_query_value(zip),
+        encode_query_value(country)
+    )
+}
+
+/// Percent-encodes everything except unreserved characters so free text can go in a query string
+pub(crate) fn encode_query_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
             }
-        })?;
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    encoded
+}
+
+/// Request weather, alerts, and road surface conditions along a route from OpenWeather's Road Risk API, this is a
+/// blocking HTTP request. Road Risk requires a separate subscription.
+pub fn open_weather_road_risk(
+    track: &[RoadRiskWaypoint],
+    api_key: String,
+) -> Result<Vec<OWRoadRiskEntry>, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).road_risk(track)
+}
+
+/// Request current solar radiation from OpenWeather, this is a blocking HTTP request. Solar radiation requires a
+/// separate subscription.
+pub fn open_weather_solar_radiation(
+    coords: GeodeticCoords,
+    api_key: String,
+) -> Result<OWSolarRadiationResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).solar_radiation(coords)
+}
+
+/// Request the hourly solar radiation forecast for the next 15 days from OpenWeather, this is a blocking HTTP
+/// request. Solar radiation requires a separate subscription.
+pub fn open_weather_solar_radiation_forecast(
+    coords: GeodeticCoords,
+    api_key: String,
+) -> Result<OWSolarRadiationResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).solar_radiation_forecast(coords)
+}
+
+/// Request hourly solar radiation readings between `start` and `end` from OpenWeather, this is a blocking HTTP
+/// request. Solar radiation requires a separate subscription.
+pub fn open_weather_solar_radiation_history(
+    coords: GeodeticCoords,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    api_key: String,
+) -> Result<OWSolarRadiationResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard)
+        .solar_radiation_history(coords, start, end)
+}
+
+/// Current solar radiation path and query for a query position
+pub(crate) fn solar_radiation_path(coords: &GeodeticCoords) -> String {
+    format!("{SOLAR_RADIATION_PATH}?{}", lat_lon_params(coords))
+}
+
+/// Solar radiation forecast path and query for a query position
+pub(crate) fn solar_radiation_forecast_path(coords: &GeodeticCoords) -> String {
+    format!("{SOLAR_RADIATION_FORECAST_PATH}?{}", lat_lon_params(coords))
+}
+
+/// Historical solar radiation path and query for a query position over a time range
+pub(crate) fn solar_radiation_history_path(
+    coords: &GeodeticCoords,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> String {
+    format!(
+        "{SOLAR_RADIATION_HISTORY_PATH}?{}&start={}&end={}",
+        lat_lon_params(coords),
+        start.timestamp(),
+        end.timestamp()
+    )
+}
+
+/// Request current weather, forecasts, and alerts from OpenWeather's One Call 3.0 API, this is a blocking HTTP
+/// request. One Call requires a separate subscription from the free tier.
+pub fn open_weather_one_call(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWOneCallResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).one_call(coords, &[])
+}
+
+/// One Call path and query for a query position, leaving out any `exclude`d blocks
+pub(crate) fn one_call_path(
+    coords: &GeodeticCoords,
+    units: &WeatherUnits,
+    exclude: &[OneCallBlock],
+) -> String {
+    let mut path = format!("{ONE_CALL_PATH}?{}&units={units}", lat_lon_params(coords));
+
+    if !exclude.is_empty() {
+        let blocks: Vec<String> = exclude.iter().map(|b| b.to_string()).collect();
+        path.push_str(&format!("&exclude={}", blocks.join(",")));
+    }
+
+    path
+}
+
+/// Request historical weather at `timestamp` from One Call's timemachine route, this is a blocking HTTP request.
+/// Data is available from January 1st, 1979 up to 4 days ahead.
+pub fn open_weather_timemachine(
+    coords: GeodeticCoords,
+    timestamp: DateTime<Utc>,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWTimeMachineResponse, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).timemachine(coords, timestamp)
+}
+
+/// Timemachine path and query for a query position at a moment in time
+pub(crate) fn timemachine_path(
+    coords: &GeodeticCoords,
+    timestamp: DateTime<Utc>,
+    units: &WeatherUnits,
+) -> String {
+    format!(
+        "{TIME_MACHINE_PATH}?{}&dt={}&units={units}",
+        lat_lon_params(coords),
+        timestamp.timestamp()
+    )
+}
+
+/// Request weather aggregated over `date` from One Call's day summary route, this is a blocking HTTP request. Data is
+/// available from January 2nd, 1979 up to 1.5 years ahead.
+pub fn open_weather_day_summary(
+    coords: GeodeticCoords,
+    date: NaiveDate,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWDaySummary, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).day_summary(coords, date)
+}
+
+/// Day summary path and query for a query position on a date
+pub(crate) fn day_summary_path(
+    coords: &GeodeticCoords,
+    date: NaiveDate,
+    units: &WeatherUnits,
+) -> String {
+    format!(
+        "{DAY_SUMMARY_PATH}?{}&date={}&units={units}",
+        lat_lon_params(coords),
+        date.format("%Y-%m-%d")
+    )
+}
+
+/// Request a human-readable summary of the weather from One Call's overview route, this is a blocking HTTP request.
+/// `date` may be today or tomorrow, and defaults to today.
+pub fn open_weather_overview(
+    coords: GeodeticCoords,
+    date: Option<NaiveDate>,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWWeatherOverview, OpenWxError> {
+    OpenWeatherClient::new(api_key, units).overview(coords, date)
+}
+
+/// Overview path and query for a query position, on `date` if provided
+pub(crate) fn overview_path(
+    coords: &GeodeticCoords,
+    date: Option<NaiveDate>,
+    units: &WeatherUnits,
+) -> String {
+    let mut path = format!("{OVERVIEW_PATH}?{}&units={units}", lat_lon_params(coords));
+
+    if let Some(date) = date {
+        path.push_str(&format!("&date={}", date.format("%Y-%m-%d")));
+    }
+
+    path
+}
+
+/// Current weather path and query for a query position
+pub(crate) fn coords_path(
+    coords: &GeodeticCoords,
+    units: &WeatherUnits,
+    mode: ResponseMode,
+) -> String {
+    current_weather_path(&lat_lon_params(coords), units, mode)
+}
+
+/// Current weather path and query for an OpenWeather city ID
+pub(crate) fn city_id_path(city_id: u32, units: &WeatherUnits) -> String {
+    current_weather_path(&format!("id={city_id}"), units, ResponseMode::Json)
+}
+
+/// Current weather path and query given the query parameters that pick out the location, the client adds the base URL
+/// and API key.
+fn current_weather_path(location_params: &str, units: &WeatherUnits, mode: ResponseMode) -> String {
+    format!("{CURRENT_WEATHER_PATH}?{location_params}&mode={mode}&units={units}")
+}
+
+/// Parse the body of a current weather response into strongly-typed data.
+pub fn parse_current_weather(response_text: &str) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+    parse_response(response_text)
+}
+
+/// Parse the body of any JSON response into strongly-typed data.
+pub(crate) fn parse_response<T: DeserializeOwned>(response_text: &str) -> Result<T, OpenWxError> {
+    // First get the untyped JSON blob so we log it in the event of a parsing failure
+    let response_json: serde_json::Value = serde_json::from_str(response_text)?;
+
+    let parsed: T = serde_json::from_value(response_json.clone()).map_err(|err| {
+        OpenWxError::ResponseParseError {
+            input_json: response_json,
+            parse_error: err,
+        }
+    })?;
 
     Ok(parsed)
 }
+
+/// Query parameters for a position, shared by most endpoints
+pub(crate) fn lat_lon_params(coords: &GeodeticCoords) -> String {
+    format!("lat={}&lon={}", coords.lat, coords.lon)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn city_id_path_has_no_coords() {
+        let url = city_id_path(3163858, &WeatherUnits::Metric);
+        assert!(url.contains("?id=3163858"));
+        assert!(url.contains("units=metric"));
+        assert!(!url.contains("lat="));
+        assert!(!url.contains("lon="));
+
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let url = coords_path(&coords, &WeatherUnits::Metric, ResponseMode::Json);
+        assert!(url.contains("lat=44.34&lon=10.99"));
+        assert!(!url.contains("?id="));
+    }
+
+    #[test]
+    fn one_call_exclusions() {
+        let coords = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+
+        let path = one_call_path(&coords, &WeatherUnits::Imperial, &[]);
+        assert_eq!(
+            path,
+            "/data/3.0/onecall?lat=33.545&lon=-117.771&units=imperial"
+        );
+
+        let exclude = [OneCallBlock::Minutely, OneCallBlock::Alerts];
+        let path = one_call_path(&coords, &WeatherUnits::Imperial, &exclude);
+        assert!(path.ends_with("&exclude=minutely,alerts"));
+    }
+
+    #[test]
+    fn daily_forecast_day_limit() {
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        let path = daily_forecast_path(&coords, &WeatherUnits::Metric, None);
+        assert!(!path.contains("cnt="));
+
+        let path = daily_forecast_path(&coords, &WeatherUnits::Metric, Some(16));
+        assert_eq!(
+            path,
+            "/data/2.5/forecast/daily?lat=44.34&lon=10.99&units=metric&cnt=16"
+        );
+    }
+
+    #[test]
+    fn day_summary_date() {
+        let coords = GeodeticCoords::new_checked(33.0, 35.0).unwrap();
+        let date = NaiveDate::from_ymd_opt(2020, 3, 4).unwrap();
+
+        let path = day_summary_path(&coords, date, &WeatherUnits::Standard);
+        assert_eq!(
+            path,
+            "/data/3.0/onecall/day_summary?lat=33&lon=35&date=2020-03-04&units=standard"
+        );
+    }
+
+    #[test]
+    fn air_pollution_paths() {
+        let coords = GeodeticCoords::new_checked(50.0, 50.0).unwrap();
+
+        assert_eq!(
+            air_pollution_forecast_path(&coords),
+            "/data/2.5/air_pollution/forecast?lat=50&lon=50"
+        );
+
+        let start = DateTime::from_timestamp(1606223802, 0).unwrap();
+        let end = DateTime::from_timestamp(1606482999, 0).unwrap();
+        assert_eq!(
+            air_pollution_history_path(&coords, start, end),
+            "/data/2.5/air_pollution/history?lat=50&lon=50&start=1606223802&end=1606482999"
+        );
+    }
+
+    #[test]
+    fn geocoding_paths() {
+        assert_eq!(
+            direct_geocoding_path("San Diego,CA,US", Some(5)),
+            "/geo/1.0/direct?q=San%20Diego%2CCA%2CUS&limit=5"
+        );
+        assert_eq!(encode_query_value("Zürich"), "Z%C3%BCrich");
+
+        let coords = GeodeticCoords::new_checked(51.5098, -0.1180).unwrap();
+        assert_eq!(
+            reverse_geocoding_path(&coords, Some(1)),
+            "/geo/1.0/reverse?lat=51.5098&lon=-0.118&limit=1"
+        );
+
+        assert_eq!(
+            zip_geocoding_path("E14 5AB", "GB"),
+            "/geo/1.0/zip?zip=E14%205AB,GB"
+        );
+    }
+
+    #[test]
+    fn solar_radiation_paths() {
+        let coords = GeodeticCoords::new_checked(32.7243, -114.6244).unwrap();
+
+        assert_eq!(
+            solar_radiation_forecast_path(&coords),
+            "/data/2.5/solar_radiation/forecast?lat=32.7243&lon=-114.6244"
+        );
+
+        let start = DateTime::from_timestamp(1618232400, 0).unwrap();
+        let end = DateTime::from_timestamp(1618318800, 0).unwrap();
+        assert!(
+            solar_radiation_history_path(&coords, start, end)
+                .ends_with("&start=1618232400&end=1618318800")
+        );
+    }
+
+    #[test]
+    fn request_options() {
+        let options = RequestOptions::default()
+            .connect_timeout(Duration::from_secs(2))
+            .timeout(Duration::from_secs(5));
+        assert_eq!(options.connect_timeout, Some(Duration::from_secs(2)));
+        assert_eq!(options.timeout, Some(Duration::from_secs(5)));
+
+        assert!(options.client("key".into(), WeatherUnits::Metric).is_ok());
+
+        let proxied = RequestOptions::default().base_url("http://localhost:8080");
+        assert_eq!(proxied.base_url.as_deref(), Some("http://localhost:8080"));
+    }
+
+    #[test]
+    fn api_error_bodies() {
+        let unauthorized = api_error(
+            401,
+            None,
+            r#"{"cod":401, "message": "Invalid API key. Please see https://openweathermap.org/faq#error401 for more info."}"#,
+        );
+        assert!(unauthorized.is_unauthorized());
+
+        let not_found = api_error(404, None, r#"{"cod":"404","message":"city not found"}"#);
+        assert!(not_found.is_not_found());
+        assert_eq!(
+            not_found.to_string(),
+            "OpenWeather returned error 404: city not found"
+        );
+
+        let bad_gateway = api_error(502, Some("120"), "<html>Bad Gateway</html>");
+        assert_eq!(
+            bad_gateway.to_string(),
+            "OpenWeather returned error 502: Bad Gateway"
+        );
+        assert_eq!(bad_gateway.retry_after(), Some(Duration::from_secs(120)));
+    }
+
+    #[test]
+    fn url_response_mode() {
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let url_for = |mode| coords_path(&coords, &WeatherUnits::Metric, mode);
+
+        assert!(url_for(ResponseMode::Json).contains("&mode=json&"));
+        assert!(url_for(ResponseMode::Xml).contains("&mode=xml&"));
+        assert!(url_for(ResponseMode::Html).contains("&mode=html&"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Offline astronomical calculations, these never touch the network
+
+use crate::GeodeticCoords;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use std::f64::consts::PI;
+
+/// Zenith angle of the sun's center at sunrise and sunset in degrees, accounting for atmospheric refraction and the
+/// apparent radius of the solar disc.
+const SUNRISE_ZENITH_DEG: f64 = 90.833;
+
+impl GeodeticCoords {
+    /// Computes the sunrise and sunset times in UTC for this position on `date` (a UTC calendar day), using NOAA's
+    /// general solar position approximation. Results are typically within a minute or two of the true times.
+    ///
+    /// Returns `None` during polar day or polar night, when the sun doesn't cross the horizon.
+    pub fn sun_times(&self, date: NaiveDate) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let lat = (self.lat as f64).to_radians();
+        let lon = self.lon as f64;
+
+        let days_in_year = if date.leap_year() { 366.0 } else { 365.0 };
+
+        // Fractional year in radians, evaluated at solar noon
+        let gamma = 2.0 * PI / days_in_year * (date.ordinal0() as f64);
+
+        // Equation of time in minutes
+        let eqtime = 229.18
+            * (0.000075 + 0.001868 * gamma.cos()
+                - 0.032077 * gamma.sin()
+                - 0.014615 * (2.0 * gamma).cos()
+                - 0.040849 * (2.0 * gamma).sin());
+
+        // Solar declination in radians
+        let decl = 0.006918 - 0.399912 * gamma.cos() + 0.070257 * gamma.sin()
+            - 0.006758 * (2.0 * gamma).cos()
+            + 0.000907 * (2.0 * gamma).sin()
+            - 0.002697 * (3.0 * gamma).cos()
+            + 0.00148 * (3.0 * gamma).sin();
+
+        let cos_hour_angle = SUNRISE_ZENITH_DEG.to_radians().cos() / (lat.cos() * decl.cos())
+            - lat.tan() * decl.tan();
+
+        if !(-1.0..=1.0).contains(&cos_hour_angle) {
+            return None;
+        }
+
+        let hour_angle_deg = cos_hour_angle.acos().to_degrees();
+
+        // Minutes past midnight UTC, these can fall outside of [0, 1440) far from the prime meridian
+        let sunrise_min = 720.0 - 4.0 * (lon + hour_angle_deg) - eqtime;
+        let sunset_min = 720.0 - 4.0 * (lon - hour_angle_deg) - eqtime;
+
+        let midnight = date.and_hms_opt(0, 0, 0)?.and_utc();
+        let at_minutes =
+            |minutes: f64| midnight + Duration::seconds((minutes * 60.0).round() as i64);
+
+        Some((at_minutes(sunrise_min), at_minutes(sunset_min)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+
+    #[test]
+    fn sun_times_match_open_weather() {
+        let response = zocca();
+        let date = response.sys.sunrise.date_naive();
+
+        let (sunrise, sunset) = response.coord.sun_times(date).unwrap();
+
+        let tolerance = Duration::minutes(2);
+        assert!((sunrise - response.sys.sunrise).abs() < tolerance);
+        assert!((sunset - response.sys.sunset).abs() < tolerance);
+    }
+
+    #[test]
+    fn polar_night() {
+        let svalbard = GeodeticCoords::new_checked(78.22, 15.65).unwrap();
+
+        let winter_solstice = NaiveDate::from_ymd_opt(2025, 12, 21).unwrap();
+        assert!(svalbard.sun_times(winter_solstice).is_none());
+
+        let summer_solstice = NaiveDate::from_ymd_opt(2025, 6, 21).unwrap();
+        assert!(svalbard.sun_times(summer_solstice).is_none());
+    }
+}
@@ -0,0 +1,160 @@
+//! A non-blocking client for the OpenWeather API, enabled with the `async` feature
+
+use crate::single_flight::SingleFlight;
+use crate::{
+    ApiKeyPlacement, Credentials, DEFAULT_BASE_URL, GeodeticCoords, HttpRequest, Language,
+    OWCurrentWeatherResponse, OpenWxError, ResponseMode, WeatherUnits, api_error,
+    append_query_param, city_id_path, coords_path, parse_current_weather,
+};
+
+/// Async counterpart to [`crate::OpenWeatherClient`], built on `reqwest`'s async client so it can be used from
+/// within an async runtime without spawning blocking tasks. Identical requests made concurrently, e.g. from several
+/// tasks asking for the weather at the same place, are sent once and the response shared between them.
+#[derive(Debug)]
+pub struct AsyncOpenWeatherClient {
+    credentials: Credentials,
+    units: WeatherUnits,
+    base_url: String,
+    language: Option<Language>,
+    client: reqwest::Client,
+    in_flight: SingleFlight,
+}
+
+impl AsyncOpenWeatherClient {
+    /// Creates a new async client.
+    pub fn new(api_key: String, units: WeatherUnits) -> Self {
+        AsyncOpenWeatherClient {
+            credentials: Credentials::new(api_key),
+            units,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            language: None,
+            client: reqwest::Client::new(),
+            in_flight: SingleFlight::default(),
+        }
+    }
+
+    /// Sets where the API key is placed on each request, by default it's sent as a query parameter.
+    pub fn with_api_key_placement(mut self, placement: ApiKeyPlacement) -> Self {
+        self.credentials.placement = placement;
+        self
+    }
+
+    /// Sets the scheme and host requests are sent to, by default [`DEFAULT_BASE_URL`]. Useful for pointing at a mock
+    /// server in tests or routing through a proxy.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the language descriptions in responses are localized into, by default OpenWeather responds in English.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// The units responses from this client are requested in
+    pub fn units(&self) -> WeatherUnits {
+        self.units
+    }
+
+    /// Request the current weather at a position.
+    pub async fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        let response_text = self.raw_current_weather(coords, ResponseMode::Json).await?;
+
+        Ok(parse_current_weather(&response_text)?.with_units(self.units))
+    }
+
+    /// Request the current weather at a position in the given [`ResponseMode`] and return the unparsed body.
+    pub async fn raw_current_weather(
+        &self,
+        coords: GeodeticCoords,
+        mode: ResponseMode,
+    ) -> Result<String, OpenWxError> {
+        self.get(coords_path(&coords, &self.units, mode)).await
+    }
+
+    /// Request the current weather for an OpenWeather city ID.
+    pub async fn current_weather_by_id(
+        &self,
+        city_id: u32,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        let response_text = self.get(city_id_path(city_id, &self.units)).await?;
+
+        Ok(parse_current_weather(&response_text)?.with_units(self.units))
+    }
+
+    /// Authenticate a request for the endpoint at `path_and_query` and send it, or wait on an identical request
+    /// which is already in flight
+    async fn get(&self, path_and_query: String) -> Result<String, OpenWxError> {
+        let mut url = format!("{}{path_and_query}", self.base_url);
+        if let Some(language) = self.language {
+            url = append_query_param(&url, "lang", &language.to_string());
+        }
+
+        self.in_flight.run(url.clone(), || self.send(url)).await
+    }
+
+    /// Authenticate a GET of `url` and send it
+    async fn send(&self, url: String) -> Result<String, OpenWxError> {
+        let HttpRequest { url, headers } = self.credentials.authenticate(url);
+
+        let mut builder = self.client.get(url);
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send().await?;
+
+        // Prefer OpenWeather's own explanation of the failure over the bare status
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let body = response.text().await.unwrap_or_default();
+            return Err(api_error(status.as_u16(), retry_after.as_deref(), &body));
+        }
+
+        Ok(response.text().await?)
+    }
+}
+
+/// Request the current weather from OpenWeather without blocking.
+pub async fn open_weather_request_async(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+    AsyncOpenWeatherClient::new(api_key, units)
+        .current_weather(coords)
+        .await
+}
+
+/// Request the current weather from OpenWeather for an OpenWeather city ID without blocking.
+pub async fn open_weather_request_by_id_async(
+    city_id: u32,
+    units: WeatherUnits,
+    api_key: String,
+) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+    AsyncOpenWeatherClient::new(api_key, units)
+        .current_weather_by_id(city_id)
+        .await
+}
+
+/// Request the current weather from OpenWeather in any of the supported [`ResponseMode`]s without blocking, and
+/// return the body without parsing it.
+pub async fn raw_weather_async(
+    coords: GeodeticCoords,
+    units: WeatherUnits,
+    mode: ResponseMode,
+    api_key: String,
+) -> Result<String, OpenWxError> {
+    AsyncOpenWeatherClient::new(api_key, units)
+        .raw_current_weather(coords, mode)
+        .await
+}
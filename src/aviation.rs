@@ -0,0 +1,369 @@
+//! Aviation weather helpers, for pilots checking conditions at a field before a flight
+
+use crate::{Altitude, OWCurrentWeatherResponse, OWMain, OWWind, WindDirection, WindSpeed};
+use chrono::DateTime;
+use strum::Display;
+
+/// Pressure of the International Standard Atmosphere at sea level, hPa
+const ISA_SEA_LEVEL_HPA: f32 = 1013.25;
+
+/// Scales station pressure in inHg over temperature in °R to the ratio of air density to the standard sea level
+/// density, from the NWS density altitude formula
+const NWS_DENSITY_INHG_RANKINE: f32 = 17.326;
+
+/// Meters in one statute mile, the unit flight category visibilities are defined in
+const METERS_PER_STATUTE_MILE: f32 = 1609.344;
+
+/// Feet the cloud base rises for every °C of spread between the temperature and dew point
+const CLOUD_BASE_FT_PER_C_SPREAD: f32 = 400.0;
+
+/// Cloud cover in % at or above which clouds are broken (5 oktas), the least coverage which counts as a ceiling
+const CEILING_COVER_PERCENT: f32 = 62.5;
+
+/// FAA flight categories, declared from least to most restrictive. The category is the more restrictive of what the
+/// ceiling and the visibility each allow.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum FlightCategory {
+    /// Visual flight rules, ceiling above 3000 ft and visibility above 5 statute miles
+    Vfr,
+    /// Marginal VFR, ceiling 1000 to 3000 ft and/or visibility 3 to 5 statute miles
+    Mvfr,
+    /// Instrument flight rules, ceiling 500 to below 1000 ft and/or visibility 1 to below 3 statute miles
+    Ifr,
+    /// Low IFR, ceiling below 500 ft and/or visibility below 1 statute mile
+    Lifr,
+}
+
+impl FlightCategory {
+    /// Classifies a ceiling, `None` meaning there isn't one, and a visibility in statute miles
+    pub fn from_conditions(ceiling: Option<Altitude>, visibility_sm: f32) -> Self {
+        let by_ceiling = match ceiling.map(|ceiling| ceiling.feet()) {
+            None => FlightCategory::Vfr,
+            Some(feet) if feet > 3000.0 => FlightCategory::Vfr,
+            Some(feet) if feet >= 1000.0 => FlightCategory::Mvfr,
+            Some(feet) if feet >= 500.0 => FlightCategory::Ifr,
+            Some(_) => FlightCategory::Lifr,
+        };
+
+        let by_visibility = match visibility_sm {
+            sm if sm > 5.0 => FlightCategory::Vfr,
+            sm if sm >= 3.0 => FlightCategory::Mvfr,
+            sm if sm >= 1.0 => FlightCategory::Ifr,
+            _ => FlightCategory::Lifr,
+        };
+
+        by_ceiling.max(by_visibility)
+    }
+}
+
+/// Wind resolved along and across a runway or course, in the units the wind was reported in
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindComponents {
+    /// Wind along the heading, positive for a headwind and negative for a tailwind
+    pub headwind: WindSpeed,
+
+    /// Wind across the heading, positive from the right and negative from the left
+    pub crosswind: WindSpeed,
+}
+
+impl WindDirection {
+    /// Signed angle in degrees from `heading_deg` to the direction the wind is blowing from, in (-180, 180].
+    /// Positive angles are wind from the right of the heading.
+    pub fn angle_from(&self, heading_deg: f32) -> f32 {
+        let angle = (self.value() - heading_deg).rem_euclid(360.0);
+
+        if angle > 180.0 { angle - 360.0 } else { angle }
+    }
+}
+
+impl OWWind {
+    /// Resolves the sustained wind into headwind and crosswind components for a runway or course of `heading_deg`.
+    /// OpenWeather reports wind directions relative to true north, runway numbers are usually magnetic so correct
+    /// for the local magnetic variation first.
+    pub fn components(&self, heading_deg: f32) -> WindComponents {
+        let angle = self.deg.angle_from(heading_deg).to_radians();
+        let (speed, units) = (self.speed.value(), self.speed.units());
+
+        WindComponents {
+            headwind: WindSpeed::new(speed * angle.cos(), units),
+            crosswind: WindSpeed::new(speed * angle.sin(), units),
+        }
+    }
+}
+
+impl OWMain {
+    /// Pressure altitude, the height in the standard atmosphere with the same pressure as `grnd_level`. This is what
+    /// an altimeter set to 29.92 inHg (1013.25 hPa) reads on the ground.
+    ///
+    /// Returns `None` if the ground level pressure wasn't reported, as is the case for XML responses.
+    pub fn pressure_altitude(&self) -> Option<Altitude> {
+        let station_hpa = self.grnd_level?.hpa();
+
+        let feet = 145_366.45 * (1.0 - (station_hpa / ISA_SEA_LEVEL_HPA).powf(0.190_284));
+
+        Some(Altitude::from_feet(feet))
+    }
+
+    /// Density altitude, the height in the standard atmosphere with the same air density as on the ground here,
+    /// which is what aircraft performance depends on. Hot days at high fields can put it thousands of feet above the
+    /// field elevation. Uses the US National Weather Service's formula for dry air from `grnd_level` and `temp`.
+    ///
+    /// Returns `None` if the ground level pressure wasn't reported, as is the case for XML responses.
+    pub fn density_altitude(&self) -> Option<Altitude> {
+        let station_inhg = self.grnd_level?.to_inhg();
+
+        let temp_rankine = self.temp.to_fahrenheit() + 459.67;
+        let feet = 145_442.16
+            * (1.0 - (NWS_DENSITY_INHG_RANKINE * station_inhg / temp_rankine).powf(0.235));
+
+        Some(Altitude::from_feet(feet))
+    }
+}
+
+impl OWCurrentWeatherResponse {
+    /// Estimated ceiling above ground level, the base of the lowest broken or overcast cloud layer, or `None` if
+    /// less than 5 oktas of the sky is covered. OpenWeather doesn't report cloud heights so the base is estimated
+    /// from the spread between the temperature and dew point, the height where rising air would cool to saturation.
+    /// Perfectly dry air has no dew point to estimate from, so there's no ceiling at 0% humidity either.
+    pub fn estimated_ceiling(&self) -> Option<Altitude> {
+        if self.clouds.all.value() < CEILING_COVER_PERCENT {
+            return None;
+        }
+
+        let dew_point = self.main.dew_point()?;
+        let spread_c = (self.main.temp.to_celsius() - dew_point.to_celsius()).max(0.0);
+
+        Some(Altitude::from_feet(spread_c * CLOUD_BASE_FT_PER_C_SPREAD))
+    }
+
+    /// The flight category given the reported visibility and the [`OWCurrentWeatherResponse::estimated_ceiling`].
+    /// OpenWeather caps visibility at 10 km, which is still above the 5 statute miles VFR requires.
+    pub fn flight_category(&self) -> FlightCategory {
+        FlightCategory::from_conditions(
+            self.estimated_ceiling(),
+            self.visibility / METERS_PER_STATUTE_MILE,
+        )
+    }
+
+    /// Renders the observation as a compact METAR-like line, e.g. `132345Z 20005G07KT 9999 BKN/// 08/07 A3024`, with
+    /// groups for the observation time, wind in knots, visibility in meters, cloud cover, temperature and dew point
+    /// in °C, and the altimeter setting in inHg. The dew point is `//` at 0% humidity, where it's undefined.
+    ///
+    /// This is for humans used to reading METARs, not a valid report. There's no station identifier, weather
+    /// phenomena aren't encoded, and OpenWeather doesn't report cloud heights so cloud layers end in `///`. Gusts are
+    /// included whenever they're reported rather than only when they're significant.
+    pub fn metar(&self) -> String {
+        let time = DateTime::from_timestamp(self.dt as i64, 0).map_or_else(
+            || "//////Z".to_string(),
+            |dt| dt.format("%d%H%MZ").to_string(),
+        );
+
+        let visibility = if self.visibility >= MAX_METAR_VISIBILITY_M {
+            "9999".to_string()
+        } else {
+            format!("{:04.0}", self.visibility)
+        };
+
+        format!(
+            "{time} {} {visibility} {} {}/{} A{:04.0}",
+            metar_wind(self.wind()),
+            metar_clouds(self.clouds.all.value()),
+            metar_temperature(self.main.temp.to_celsius()),
+            self.main.dew_point().map_or_else(
+                || "//".to_string(),
+                |dew_point| metar_temperature(dew_point.to_celsius())
+            ),
+            self.main.sea_level.to_inhg() * 100.0
+        )
+    }
+}
+
+/// Visibility in meters reported as `9999`, meaning 10 km or more
+const MAX_METAR_VISIBILITY_M: f32 = 9999.0;
+
+/// Wind group, `dddffGggKT`, with the direction rounded to the nearest 10 degrees and `/////KT` if wind is missing
+fn metar_wind(wind: Option<&OWWind>) -> String {
+    let Some(wind) = wind else {
+        return "/////KT".to_string();
+    };
+
+    let speed = wind.speed_knots().round();
+    if speed < 1.0 {
+        return "00000KT".to_string();
+    }
+
+    let direction = match (wind.deg.value() / 10.0).round() as u32 * 10 {
+        0 => 360,
+        direction => direction,
+    };
+
+    let gust = wind
+        .gust
+        .map(|gust| gust.to_knots().round())
+        .filter(|gust| *gust > speed)
+        .map_or_else(String::new, |gust| format!("G{gust:02}"));
+
+    format!("{direction:03}{speed:02}{gust}KT")
+}
+
+/// Cloud cover group from the fraction of sky covered, `SKC` when clear and otherwise the METAR coverage word for
+/// the number of eighths (oktas) covered
+fn metar_clouds(cover_percent: f32) -> &'static str {
+    match (cover_percent / 12.5).round() as u8 {
+        0 => "SKC",
+        1..=2 => "FEW///",
+        3..=4 => "SCT///",
+        5..=7 => "BKN///",
+        _ => "OVC///",
+    }
+}
+
+/// Temperature group in whole °C, negative temperatures are prefixed with `M`
+fn metar_temperature(temp_c: f32) -> String {
+    let rounded = temp_c.round() as i32;
+
+    if rounded < 0 {
+        format!("M{:02}", -rounded)
+    } else {
+        format!("{rounded:02}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+    use crate::{Percentage, Pressure, Temperature, WeatherUnits, WindSpeed};
+
+    #[test]
+    fn standard_atmosphere() {
+        let mut main = zocca().main;
+        main.grnd_level = Some(Pressure::from_hpa(1013.25));
+        main.temp = Temperature::new(15.0, WeatherUnits::Metric);
+
+        assert!(main.pressure_altitude().unwrap().feet().abs() < 1.0);
+        assert!(main.density_altitude().unwrap().feet().abs() < 50.0);
+    }
+
+    #[test]
+    fn hot_high_field() {
+        // Standard pressure at 5000 ft, on a 35°C day where standard would be 5°C
+        let mut main = zocca().main;
+        main.grnd_level = Some(Pressure::from_hpa(843.07));
+        main.temp = Temperature::new(35.0, WeatherUnits::Metric);
+
+        let pressure_altitude = main.pressure_altitude().unwrap();
+        assert!(
+            (pressure_altitude.feet() - 5000.0).abs() < 5.0,
+            "{pressure_altitude}"
+        );
+        assert!((pressure_altitude.meters() - 1524.0).abs() < 2.0);
+
+        let density_altitude = main.density_altitude().unwrap();
+        assert!(
+            (density_altitude.feet() - 8350.0).abs() < 50.0,
+            "{density_altitude}"
+        );
+    }
+
+    #[test]
+    fn missing_ground_pressure() {
+        let mut main = zocca().main;
+        main.grnd_level = None;
+
+        assert_eq!(main.pressure_altitude(), None);
+        assert_eq!(main.density_altitude(), None);
+    }
+
+    #[test]
+    fn metar_sample() {
+        assert_eq!(
+            zocca().metar(),
+            "132345Z 20005G07KT 9999 BKN/// 08/07 A3024"
+        );
+    }
+
+    #[test]
+    fn metar_groups() {
+        let mut response = zocca();
+        response.main.temp = Temperature::new(-4.6, WeatherUnits::Metric);
+        response.visibility = 800.0;
+        response.clouds.all = Percentage::new_checked(0.0).unwrap();
+
+        let wind = response.wind.as_mut().unwrap();
+        wind.speed = WindSpeed::new(0.2, WeatherUnits::Metric);
+
+        let metar = response.metar();
+        assert!(metar.contains(" 00000KT 0800 SKC M05/"), "{metar}");
+
+        response.wind = None;
+        assert!(response.metar().contains(" /////KT "));
+
+        response.main.humidity = Percentage::new_checked(0.0).unwrap();
+        assert!(response.metar().contains(" M05/// "));
+    }
+
+    #[test]
+    fn flight_categories() {
+        let category = FlightCategory::from_conditions;
+
+        assert_eq!(category(None, 10.0), FlightCategory::Vfr);
+        assert_eq!(
+            category(Some(Altitude::from_feet(2500.0)), 10.0),
+            FlightCategory::Mvfr
+        );
+        assert_eq!(
+            category(Some(Altitude::from_feet(5000.0)), 2.0),
+            FlightCategory::Ifr
+        );
+        assert_eq!(
+            category(Some(Altitude::from_feet(400.0)), 4.0),
+            FlightCategory::Lifr
+        );
+        assert_eq!(FlightCategory::Mvfr.to_string(), "MVFR");
+    }
+
+    #[test]
+    fn response_flight_category() {
+        // Nearly saturated under broken clouds, so the ceiling is low
+        let mut response = zocca();
+        let ceiling = response.estimated_ceiling().unwrap();
+        assert!((ceiling.feet() - 300.0).abs() < 50.0, "{ceiling}");
+        assert_eq!(response.flight_category(), FlightCategory::Lifr);
+
+        response.main.humidity = Percentage::new_checked(0.0).unwrap();
+        assert_eq!(response.estimated_ceiling(), None);
+        response.main.humidity = zocca().main.humidity;
+
+        response.clouds.all = Percentage::new_checked(20.0).unwrap();
+        assert_eq!(response.estimated_ceiling(), None);
+        assert_eq!(response.flight_category(), FlightCategory::Vfr);
+
+        response.visibility = 6000.0;
+        assert_eq!(response.flight_category(), FlightCategory::Mvfr);
+    }
+
+    #[test]
+    fn wind_components() {
+        let mut wind = zocca().wind.unwrap();
+        wind.deg = WindDirection::new_checked(300.0).unwrap();
+        wind.speed = WindSpeed::new(20.0, WeatherUnits::Imperial);
+
+        // Runway 27, the wind is 30 degrees off the nose from the right
+        let components = wind.components(270.0);
+        assert!((components.headwind.value() - 17.32).abs() < 0.01);
+        assert!((components.crosswind.value() - 10.0).abs() < 0.01);
+        assert_eq!(components.crosswind.units(), WeatherUnits::Imperial);
+
+        // Departing the other way it's a tailwind from the left
+        let components = wind.components(90.0);
+        assert!((components.headwind.value() + 17.32).abs() < 0.01);
+        assert!((components.crosswind.value() + 10.0).abs() < 0.01);
+
+        // Wrapping through north
+        wind.deg = WindDirection::new_checked(10.0).unwrap();
+        assert!((wind.deg.angle_from(350.0) - 20.0).abs() < 1e-3);
+        assert!((wind.deg.angle_from(30.0) + 20.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,434 @@
+//! Streaming parsers for OpenWeather's History Bulk archives, which are delivered as either one large JSON array or a
+//! CSV file. Records are parsed one at a time so archives far larger than memory can be processed.
+
+use crate::{
+    OWClouds, OWRain, OWSnow, OWWeather, OWWind, Percentage, PrecipRate, WeatherConditionId,
+    WeatherUnits, WindDirection, WindSpeed, from_raw_percentage, from_utc_shift, to_raw_percentage,
+    to_utc_shift,
+};
+use chrono::FixedOffset;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::BufRead;
+use thiserror::Error;
+
+/// A single hourly observation from a History Bulk archive, more details
+/// [here](https://openweathermap.org/history-bulk).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BulkRecord {
+    /// Time of the observation, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Time of the observation as an ISO-like string, e.g. "2020-01-01 00:00:00 +0000 UTC"
+    pub dt_iso: String,
+
+    /// Shift in seconds from UTC
+    #[serde(deserialize_with = "from_utc_shift", serialize_with = "to_utc_shift")]
+    pub timezone: FixedOffset,
+
+    pub city_name: Option<String>,
+
+    pub lat: f32,
+
+    pub lon: f32,
+
+    pub main: BulkMain,
+
+    /// Average visibility, meter
+    pub visibility: Option<f32>,
+
+    pub wind: OWWind,
+
+    pub clouds: OWClouds,
+
+    pub weather: Vec<OWWeather>,
+
+    pub rain: Option<OWRain>,
+
+    pub snow: Option<OWSnow>,
+}
+
+/// Temperatures, pressure, and humidity of a bulk record, in the units the archive was ordered in
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BulkMain {
+    pub temp: f32,
+
+    pub feels_like: f32,
+
+    pub temp_min: f32,
+
+    pub temp_max: f32,
+
+    /// Atmospheric pressure on the sea level, hPa
+    pub pressure: f32,
+
+    /// Humidity, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
+
+    pub dew_point: Option<f32>,
+}
+
+#[derive(Error, Debug)]
+pub enum BulkError {
+    #[error("failed to read the bulk archive")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid JSON record in the bulk archive")]
+    Json(#[from] serde_json::Error),
+
+    #[error("expected the bulk archive to be a JSON array")]
+    NotAnArray,
+
+    #[error("invalid CSV record on line {line}: {message}")]
+    Csv { line: usize, message: String },
+}
+
+/// Iterates over the records of a JSON History Bulk archive, see [`json_records`]
+pub struct JsonRecords<R> {
+    reader: R,
+    started: bool,
+    finished: bool,
+}
+
+/// Streams records out of a JSON History Bulk archive, which is a single top-level array of records. Only one record
+/// is held in memory at a time. Iteration stops after the first error.
+pub fn json_records<R: BufRead>(reader: R) -> JsonRecords<R> {
+    JsonRecords {
+        reader,
+        started: false,
+        finished: false,
+    }
+}
+
+impl<R: BufRead> JsonRecords<R> {
+    /// Consumes whitespace and returns the next byte without consuming it, `None` at the end of the input
+    fn peek_past_whitespace(&mut self) -> Result<Option<u8>, BulkError> {
+        loop {
+            let buf = self.reader.fill_buf()?;
+            let Some(&byte) = buf.first() else {
+                return Ok(None);
+            };
+
+            if !byte.is_ascii_whitespace() {
+                return Ok(Some(byte));
+            }
+            self.reader.consume(1);
+        }
+    }
+
+    /// Advances past the array punctuation to the start of the next record, returns false at the end of the array
+    fn advance_to_record(&mut self) -> Result<bool, BulkError> {
+        let expected = if self.started { b',' } else { b'[' };
+
+        match self.peek_past_whitespace()? {
+            Some(b']') if self.started => return Ok(false),
+            Some(byte) if byte == expected => self.reader.consume(1),
+            _ => return Err(BulkError::NotAnArray),
+        }
+
+        // An empty array has no records after the opening bracket
+        if !self.started {
+            self.started = true;
+            if self.peek_past_whitespace()? == Some(b']') {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+impl<R: BufRead> Iterator for JsonRecords<R> {
+    type Item = Result<BulkRecord, BulkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        let record = match self.advance_to_record() {
+            Ok(true) => {
+                // Records are objects, so the deserializer never reads past the closing brace
+                let mut deserializer = serde_json::Deserializer::from_reader(&mut self.reader);
+                BulkRecord::deserialize(&mut deserializer).map_err(BulkError::from)
+            }
+            Ok(false) => {
+                self.finished = true;
+                return None;
+            }
+            Err(e) => Err(e),
+        };
+
+        if record.is_err() {
+            self.finished = true;
+        }
+
+        Some(record)
+    }
+}
+
+/// Iterates over the records of a CSV History Bulk archive, see [`csv_records`]
+pub struct CsvRecords<R> {
+    lines: std::io::Lines<R>,
+    columns: HashMap<String, usize>,
+    line: usize,
+}
+
+/// Streams records out of a CSV History Bulk archive, reading the header row up front. Only one record is held in
+/// memory at a time.
+pub fn csv_records<R: BufRead>(reader: R) -> Result<CsvRecords<R>, BulkError> {
+    let mut lines = reader.lines();
+
+    let header = lines.next().ok_or(BulkError::Csv {
+        line: 1,
+        message: "missing header row".into(),
+    })??;
+    let columns = split_csv_line(&header)
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| (name, i))
+        .collect();
+
+    Ok(CsvRecords {
+        lines,
+        columns,
+        line: 1,
+    })
+}
+
+impl<R: BufRead> CsvRecords<R> {
+    fn parse_record(&self, line: &str) -> Result<BulkRecord, String> {
+        let fields = split_csv_line(line);
+        let row = CsvRow {
+            fields: &fields,
+            columns: &self.columns,
+        };
+
+        let wind_deg = row.required::<f32>("wind_deg")?;
+        let humidity = row.required::<f32>("humidity")?;
+        let clouds = row.required::<f32>("clouds_all")?;
+
+        let rain_1h = row.optional("rain_1h")?.map(PrecipRate::from_mm_per_hour);
+        let rain_3h = row.optional("rain_3h")?;
+        let snow_1h = row.optional("snow_1h")?.map(PrecipRate::from_mm_per_hour);
+        let snow_3h = row.optional("snow_3h")?;
+
+        Ok(BulkRecord {
+            dt: row.required("dt")?,
+            dt_iso: row.required("dt_iso")?,
+            timezone: FixedOffset::east_opt(row.required("timezone")?)
+                .ok_or("timezone offset out of range")?,
+            city_name: row.optional("city_name")?,
+            lat: row.required("lat")?,
+            lon: row.required("lon")?,
+            main: BulkMain {
+                temp: row.required("temp")?,
+                feels_like: row.required("feels_like")?,
+                temp_min: row.required("temp_min")?,
+                temp_max: row.required("temp_max")?,
+                pressure: row.required("pressure")?,
+                humidity: Percentage::new_checked(humidity).map_err(|e| e.to_string())?,
+                dew_point: row.optional("dew_point")?,
+            },
+            visibility: row.optional("visibility")?,
+            wind: OWWind {
+                speed: WindSpeed::new(row.required("wind_speed")?, WeatherUnits::Standard),
+                deg: WindDirection::new_checked(wind_deg).map_err(|e| e.to_string())?,
+                gust: row
+                    .optional("wind_gust")?
+                    .map(|gust| WindSpeed::new(gust, WeatherUnits::Standard)),
+            },
+            clouds: OWClouds {
+                all: Percentage::new_checked(clouds).map_err(|e| e.to_string())?,
+            },
+            weather: vec![OWWeather {
+                id: WeatherConditionId::from_code(row.required("weather_id")?),
+                main: row.required("weather_main")?,
+                description: row.required("weather_description")?,
+                icon: row.required("weather_icon")?,
+            }],
+            rain: (rain_1h.is_some() || rain_3h.is_some()).then_some(OWRain {
+                one_hour: rain_1h,
+                three_hours: rain_3h,
+            }),
+            snow: (snow_1h.is_some() || snow_3h.is_some()).then_some(OWSnow {
+                one_hour: snow_1h,
+                three_hours: snow_3h,
+            }),
+        })
+    }
+}
+
+impl<R: BufRead> Iterator for CsvRecords<R> {
+    type Item = Result<BulkRecord, BulkError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+            self.line += 1;
+
+            if !line.trim().is_empty() {
+                break line;
+            }
+        };
+
+        Some(self.parse_record(&line).map_err(|message| BulkError::Csv {
+            line: self.line,
+            message,
+        }))
+    }
+}
+
+/// A row of CSV fields looked up by column name
+struct CsvRow<'a> {
+    fields: &'a [String],
+    columns: &'a HashMap<String, usize>,
+}
+
+impl CsvRow<'_> {
+    /// The parsed value of a column, `None` if the column is absent or the field is empty
+    fn optional<T: std::str::FromStr>(&self, column: &str) -> Result<Option<T>, String> {
+        let field = self
+            .columns
+            .get(column)
+            .and_then(|&i| self.fields.get(i))
+            .filter(|field| !field.is_empty());
+
+        match field {
+            Some(field) => field
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid value `{field}` for `{column}`")),
+            None => Ok(None),
+        }
+    }
+
+    fn required<T: std::str::FromStr>(&self, column: &str) -> Result<T, String> {
+        self.optional(column)?
+            .ok_or_else(|| format!("missing value for `{column}`"))
+    }
+}
+
+/// Splits a line of CSV into fields, handling quoted fields with embedded commas and doubled quotes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const JSON_ARCHIVE: &str = r#"
+        [
+            {
+            "city_name": "Zocca", "lat": 44.34, "lon": 10.99,
+            "main": {
+                "temp": 274.5, "temp_min": 273.9, "temp_max": 275.2, "feels_like": 271.1,
+                "pressure": 1030, "humidity": 87, "dew_point": 272.6
+            },
+            "wind": { "speed": 2.1, "deg": 200 },
+            "clouds": { "all": 20 },
+            "weather": [{ "id": 801, "main": "Clouds", "description": "few clouds", "icon": "02n" }],
+            "dt": 1577836800, "dt_iso": "2020-01-01 00:00:00 +0000 UTC", "timezone": 3600
+            },
+            {
+            "city_name": "Zocca", "lat": 44.34, "lon": 10.99,
+            "main": {
+                "temp": 274.1, "temp_min": 273.5, "temp_max": 274.9, "feels_like": 270.8,
+                "pressure": 1030, "humidity": 89
+            },
+            "wind": { "speed": 1.8, "deg": 210, "gust": 3.2 },
+            "clouds": { "all": 75 },
+            "weather": [{ "id": 500, "main": "Rain", "description": "light rain", "icon": "10n" }],
+            "rain": { "1h": 0.3 },
+            "dt": 1577840400, "dt_iso": "2020-01-01 01:00:00 +0000 UTC", "timezone": 3600
+            }
+        ]
+        "#;
+
+    const CSV_ARCHIVE: &str = "\
+dt,dt_iso,timezone,city_name,lat,lon,temp,visibility,dew_point,feels_like,temp_min,temp_max,pressure,sea_level,\
+grnd_level,humidity,wind_speed,wind_deg,wind_gust,rain_1h,rain_3h,snow_1h,snow_3h,clouds_all,weather_id,\
+weather_main,weather_description,weather_icon
+1577836800,2020-01-01 00:00:00 +0000 UTC,3600,Zocca,44.34,10.99,274.5,10000,272.6,271.1,273.9,275.2,1030,,,87,2.1,\
+200,,,,,,20,801,Clouds,few clouds,02n
+1577840400,2020-01-01 01:00:00 +0000 UTC,3600,\"Zocca, IT\",44.34,10.99,274.1,,,270.8,273.5,274.9,1030,,,89,1.8,\
+210,3.2,0.3,,,,75,500,Rain,light rain,10n
+";
+
+    #[test]
+    fn stream_json_archive() {
+        let records: Vec<_> = json_records(Cursor::new(JSON_ARCHIVE))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].main.dew_point, Some(272.6));
+        assert_eq!(
+            records[1].rain.as_ref().unwrap().one_hour,
+            Some(PrecipRate::from_mm_per_hour(0.3))
+        );
+    }
+
+    #[test]
+    fn json_archive_edge_cases() {
+        assert_eq!(json_records(Cursor::new(" [ ] ")).count(), 0);
+
+        let mut records = json_records(Cursor::new(r#"{"not": "an array"}"#));
+        assert!(matches!(records.next(), Some(Err(BulkError::NotAnArray))));
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn stream_csv_archive() {
+        let records: Vec<_> = csv_records(Cursor::new(CSV_ARCHIVE))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].visibility, Some(10000.0));
+        assert!(records[0].rain.is_none());
+        assert_eq!(records[1].city_name.as_deref(), Some("Zocca, IT"));
+        assert_eq!(records[1].wind.gust.map(|gust| gust.value()), Some(3.2));
+        assert_eq!(records[1].weather[0].description, "light rain");
+    }
+
+    #[test]
+    fn csv_error_reports_line() {
+        let archive = CSV_ARCHIVE.replacen("274.5", "warm", 1);
+        let mut records = csv_records(Cursor::new(archive)).unwrap();
+
+        let err = records.next().unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid CSV record on line 2: invalid value `warm` for `temp`"
+        );
+    }
+}
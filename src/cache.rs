@@ -0,0 +1,253 @@
+//! Caching responses in memory or on disk to avoid repeating identical requests
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How often OpenWeather refreshes its current weather, caching for longer than this serves stale data
+const OPENWEATHER_REFRESH: Duration = Duration::from_secs(10 * 60);
+
+/// Extension of the files holding responses in an on-disk cache
+const CACHE_FILE_EXTENSION: &str = "owx";
+
+/// Cache of response bodies. Requests are keyed by everything which distinguishes their responses (endpoint,
+/// coordinates, units, language, and any other query parameters) so a repeated query within the TTL is answered
+/// without touching the network. Only weather data is cached, requests for account resources like triggers, stations,
+/// and station measurements are always sent since they change whenever they're edited.
+#[derive(Debug)]
+pub struct ResponseCache {
+    ttl: Duration,
+    store: Store,
+}
+
+#[derive(Debug)]
+enum Store {
+    Memory(Mutex<HashMap<String, CacheEntry>>),
+
+    /// One file per response, shared by every process pointed at the directory
+    Disk(PathBuf),
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    stored: SystemTime,
+    body: String,
+}
+
+impl ResponseCache {
+    /// Creates an empty in-memory cache which serves each response for `ttl` after it was fetched.
+    pub fn new(ttl: Duration) -> Self {
+        ResponseCache {
+            ttl,
+            store: Store::Memory(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// An in-memory cache matching OpenWeather's 10 minute refresh interval.
+    pub fn openweather_refresh() -> Self {
+        Self::new(OPENWEATHER_REFRESH)
+    }
+
+    /// Creates a cache persisted to files in `dir`, creating it if need be, so responses outlive the process. Each
+    /// response is served for `ttl` after it was fetched. Failures to read or write the cache are treated as misses
+    /// rather than failing the request.
+    pub fn on_disk(dir: impl Into<PathBuf>, ttl: Duration) -> std::io::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        Ok(ResponseCache {
+            ttl,
+            store: Store::Disk(dir),
+        })
+    }
+
+    /// How long responses are served from the cache
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Forgets every cached response.
+    pub fn clear(&self) {
+        match &self.store {
+            Store::Memory(entries) => lock(entries).clear(),
+            Store::Disk(dir) => {
+                for path in cache_files(dir) {
+                    let _ = std::fs::remove_file(path);
+                }
+            }
+        }
+    }
+
+    /// The cached response for `key`, if one was stored within the TTL of `now`
+    pub(crate) fn get(&self, key: &str, now: SystemTime) -> Option<String> {
+        let entry = match &self.store {
+            Store::Memory(entries) => lock(entries).get(key).map(|entry| CacheEntry {
+                stored: entry.stored,
+                body: entry.body.clone(),
+            }),
+            Store::Disk(dir) => {
+                read_entry(&std::fs::read_to_string(entry_path(dir, key)).ok()?, key)
+            }
+        }?;
+
+        self.is_fresh(entry.stored, now).then_some(entry.body)
+    }
+
+    /// Stores a response for `key` fetched at `now`, evicting any which have expired
+    pub(crate) fn insert(&self, key: String, body: String, now: SystemTime) {
+        match &self.store {
+            Store::Memory(entries) => {
+                let mut entries = lock(entries);
+                entries.retain(|_, entry| self.is_fresh(entry.stored, now));
+                entries.insert(key, CacheEntry { stored: now, body });
+            }
+            Store::Disk(dir) => {
+                for path in cache_files(dir) {
+                    let expired = std::fs::read_to_string(&path)
+                        .ok()
+                        .and_then(|contents| read_stored(&contents))
+                        .is_none_or(|stored| !self.is_fresh(stored, now));
+                    if expired {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+
+                let _ = write_entry(dir, &key, &body, now);
+            }
+        }
+    }
+
+    fn is_fresh(&self, stored: SystemTime, now: SystemTime) -> bool {
+        now.duration_since(stored).is_ok_and(|age| age < self.ttl)
+    }
+}
+
+fn lock(
+    entries: &Mutex<HashMap<String, CacheEntry>>,
+) -> std::sync::MutexGuard<'_, HashMap<String, CacheEntry>> {
+    entries.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Every response file in an on-disk cache
+fn cache_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == CACHE_FILE_EXTENSION)
+        })
+        .collect()
+}
+
+/// File holding the response for `key`. Named by a hash of the key since URLs don't make valid file names, the key
+/// is also stored in the file to tell apart colliding hashes.
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{:016x}.{CACHE_FILE_EXTENSION}", fnv1a(key)))
+}
+
+/// FNV-1a, which unlike the standard library's hashers is stable across Rust versions and processes
+fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Writes a response file: the unix time it was stored, the key, then the body, each on their own line. The file is
+/// written alongside and renamed into place so concurrent processes never read half a response.
+fn write_entry(dir: &Path, key: &str, body: &str, now: SystemTime) -> std::io::Result<()> {
+    let stored = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    let path = entry_path(dir, key);
+    let partial = path.with_extension(format!("{}.partial", std::process::id()));
+
+    std::fs::write(&partial, format!("{stored}\n{key}\n{body}"))?;
+    std::fs::rename(partial, path)
+}
+
+/// Reads the time a response file was stored
+fn read_stored(contents: &str) -> Option<SystemTime> {
+    let (stored, _) = contents.split_once('\n')?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(stored.parse().ok()?))
+}
+
+/// Reads a response file, `None` if it's malformed or holds the response for a different key
+fn read_entry(contents: &str, key: &str) -> Option<CacheEntry> {
+    let stored = read_stored(contents)?;
+    let (_, rest) = contents.split_once('\n')?;
+    let (stored_key, body) = rest.split_once('\n')?;
+
+    (stored_key == key).then(|| CacheEntry {
+        stored,
+        body: body.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_expiry(cache: &ResponseCache) {
+        let start = SystemTime::now();
+
+        cache.insert("/weather?lat=1&lon=2".into(), "body\nlines".into(), start);
+        assert_eq!(
+            cache.get("/weather?lat=1&lon=2", start + Duration::from_secs(59)),
+            Some("body\nlines".into())
+        );
+        assert_eq!(cache.get("/weather?lat=1&lon=3", start), None);
+        assert_eq!(
+            cache.get("/weather?lat=1&lon=2", start + Duration::from_secs(60)),
+            None
+        );
+
+        cache.insert(
+            "/forecast?lat=1&lon=2".into(),
+            "forecast".into(),
+            start + Duration::from_secs(61),
+        );
+        cache.clear();
+        assert_eq!(cache.get("/forecast?lat=1&lon=2", start), None);
+    }
+
+    #[test]
+    fn entries_expire() {
+        let cache = ResponseCache::new(Duration::from_secs(60));
+        check_expiry(&cache);
+
+        // Expired entries are evicted to make room for new ones
+        let start = SystemTime::now();
+        cache.insert("a".into(), "a".into(), start);
+        cache.insert("b".into(), "b".into(), start + Duration::from_secs(61));
+        let Store::Memory(entries) = &cache.store else {
+            unreachable!()
+        };
+        assert_eq!(lock(entries).len(), 1);
+    }
+
+    #[test]
+    fn disk_entries_expire() {
+        let dir = std::env::temp_dir().join(format!("openwx-cache-test-{}", std::process::id()));
+        let cache = ResponseCache::on_disk(&dir, Duration::from_secs(60)).unwrap();
+        check_expiry(&cache);
+
+        // Responses are visible to other caches sharing the directory, like later runs of a CLI
+        let now = SystemTime::now();
+        cache.insert("/weather?lat=1&lon=2".into(), "body".into(), now);
+        let reopened = ResponseCache::on_disk(&dir, Duration::from_secs(60)).unwrap();
+        assert_eq!(
+            reopened.get("/weather?lat=1&lon=2", now),
+            Some("body".into())
+        );
+
+        // Expired files are evicted
+        cache.insert("b".into(), "b".into(), now + Duration::from_secs(61));
+        assert_eq!(cache_files(&dir).len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,1488 @@
+//! A reusable client for the OpenWeather API
+
+use crate::{
+    AggregationPeriod, DEFAULT_BASE_URL, GeoLocation, GeodeticCoords, Language, MEASUREMENTS_PATH,
+    NewStation, NewTrigger, OWAggregatedMeasurement, OWAirPollutionResponse,
+    OWClimateForecastResponse, OWCurrentWeatherResponse, OWDailyForecastResponse, OWDaySummary,
+    OWForecastResponse, OWHourlyForecastResponse, OWOneCallResponse, OWRoadRiskEntry,
+    OWSolarRadiationResponse, OWStation, OWTimeMachineResponse, OWTrigger, OWWeatherOverview,
+    OneCallBlock, OpenWxError, PRO_BASE_URL, ROAD_RISK_PATH, RateLimiter, ResponseCache,
+    ResponseMode, RetryPolicy, RoadRiskWaypoint, STATIONS_PATH, StationMeasurement, TRIGGERS_PATH,
+    WeatherUnits, ZipLocation, air_pollution_forecast_path, air_pollution_history_path,
+    air_pollution_path, api_error, city_id_path, climate_forecast_path, coords_path,
+    daily_forecast_path, day_summary_path, direct_geocoding_path, encode_query_value,
+    forecast_path, hourly_forecast_path, measurements_body, one_call_path, overview_path,
+    parse_current_weather, parse_response, reverse_geocoding_path, road_risk_body,
+    solar_radiation_forecast_path, solar_radiation_history_path, solar_radiation_path,
+    timemachine_path, zip_geocoding_path,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Header OpenWeather accepts the API key in, when it isn't passed as the `appid` query parameter
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Where the client puts the API key on each request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiKeyPlacement {
+    /// The `appid` query parameter, this is the default and works on every plan
+    #[default]
+    QueryParam,
+
+    /// The [`API_KEY_HEADER`] header, which keeps the key out of URLs and therefore out of access and proxy logs
+    Header,
+}
+
+/// The API key along with where it goes on each request
+#[derive(Debug, Clone)]
+pub(crate) struct Credentials {
+    pub(crate) api_key: String,
+    pub(crate) placement: ApiKeyPlacement,
+}
+
+impl Credentials {
+    pub(crate) fn new(api_key: String) -> Self {
+        Credentials {
+            api_key,
+            placement: ApiKeyPlacement::default(),
+        }
+    }
+
+    /// Builds an authenticated request for `url`
+    pub(crate) fn authenticate(&self, url: String) -> HttpRequest {
+        match self.placement {
+            ApiKeyPlacement::QueryParam => HttpRequest {
+                url: append_query_param(&url, "appid", &self.api_key),
+                headers: Vec::new(),
+            },
+            ApiKeyPlacement::Header => HttpRequest {
+                url,
+                headers: vec![(API_KEY_HEADER.to_string(), self.api_key.clone())],
+            },
+        }
+    }
+}
+
+/// Appends `name=value` to the query string of `url`, starting one if there isn't already
+pub(crate) fn append_query_param(url: &str, name: &str, value: &str) -> String {
+    let separator = if url.contains('?') { '&' } else { '?' };
+
+    format!("{url}{separator}{name}={value}")
+}
+
+/// A GET request for an [`HttpTransport`] to perform
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+/// The HTTP layer underneath an [`OpenWeatherClient`]. The default is [`ReqwestTransport`], but anything that can
+/// perform a blocking GET can be swapped in, which is mostly useful for testing without a network.
+pub trait HttpTransport: Send + Sync {
+    /// Perform a blocking GET and return the body of a successful response.
+    fn get(&self, request: &HttpRequest) -> Result<String, OpenWxError>;
+
+    /// Perform a blocking POST of a JSON `body` and return the body of a successful response. Defaults to
+    /// [`OpenWxError::UnsupportedMethod`] for transports which only implement GET.
+    fn post_json(&self, request: &HttpRequest, body: &str) -> Result<String, OpenWxError> {
+        let _ = (request, body);
+        Err(OpenWxError::UnsupportedMethod("POST"))
+    }
+
+    /// Perform a blocking PUT of a JSON `body` and return the body of a successful response. Defaults to
+    /// [`OpenWxError::UnsupportedMethod`] for transports which only implement GET.
+    fn put_json(&self, request: &HttpRequest, body: &str) -> Result<String, OpenWxError> {
+        let _ = (request, body);
+        Err(OpenWxError::UnsupportedMethod("PUT"))
+    }
+
+    /// Perform a blocking DELETE. Defaults to [`OpenWxError::UnsupportedMethod`] for transports which only implement
+    /// GET.
+    fn delete(&self, request: &HttpRequest) -> Result<(), OpenWxError> {
+        let _ = request;
+        Err(OpenWxError::UnsupportedMethod("DELETE"))
+    }
+
+    /// Perform a blocking GET and return the raw bytes of a successful response, for binary bodies like map tiles.
+    /// Defaults to the bytes of [`HttpTransport::get`], which is only correct for UTF-8 bodies.
+    fn get_bytes(&self, request: &HttpRequest) -> Result<Vec<u8>, OpenWxError> {
+        self.get(request).map(String::into_bytes)
+    }
+}
+
+/// [`HttpTransport`] backed by a blocking `reqwest` client
+#[derive(Debug, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::blocking::Client,
+}
+
+impl ReqwestTransport {
+    /// Creates a transport from a preconfigured `reqwest` client.
+    pub fn from_client(client: reqwest::blocking::Client) -> Self {
+        ReqwestTransport { client }
+    }
+}
+
+impl ReqwestTransport {
+    /// Adds the request's headers to `builder`, sends it, and fails on an unsuccessful status
+    fn execute(
+        &self,
+        mut builder: reqwest::blocking::RequestBuilder,
+        request: &HttpRequest,
+    ) -> Result<reqwest::blocking::Response, OpenWxError> {
+        for (name, value) in &request.headers {
+            builder = builder.header(name, value);
+        }
+
+        let response = builder.send()?;
+
+        // Prefer OpenWeather's own explanation of the failure over the bare status
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = retry_after_header(&response);
+            let body = response.text().unwrap_or_default();
+            return Err(api_error(status.as_u16(), retry_after.as_deref(), &body));
+        }
+
+        Ok(response)
+    }
+
+    /// A request builder carrying a JSON body
+    fn with_json(
+        builder: reqwest::blocking::RequestBuilder,
+        body: &str,
+    ) -> reqwest::blocking::RequestBuilder {
+        builder
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string())
+    }
+}
+
+impl HttpTransport for ReqwestTransport {
+    fn get(&self, request: &HttpRequest) -> Result<String, OpenWxError> {
+        let response_text = self
+            .execute(self.client.get(&request.url), request)?
+            .text()?;
+
+        Ok(response_text)
+    }
+
+    fn get_bytes(&self, request: &HttpRequest) -> Result<Vec<u8>, OpenWxError> {
+        let response_bytes = self
+            .execute(self.client.get(&request.url), request)?
+            .bytes()?;
+
+        Ok(response_bytes.to_vec())
+    }
+
+    fn post_json(&self, request: &HttpRequest, body: &str) -> Result<String, OpenWxError> {
+        let builder = Self::with_json(self.client.post(&request.url), body);
+        let response_text = self.execute(builder, request)?.text()?;
+
+        Ok(response_text)
+    }
+
+    fn put_json(&self, request: &HttpRequest, body: &str) -> Result<String, OpenWxError> {
+        let builder = Self::with_json(self.client.put(&request.url), body);
+        let response_text = self.execute(builder, request)?.text()?;
+
+        Ok(response_text)
+    }
+
+    fn delete(&self, request: &HttpRequest) -> Result<(), OpenWxError> {
+        self.execute(self.client.delete(&request.url), request)?;
+
+        Ok(())
+    }
+}
+
+/// Path of a single Weather Trigger
+fn trigger_path(id: &str) -> String {
+    format!("{TRIGGERS_PATH}/{}", encode_query_value(id))
+}
+
+/// Value of the `Retry-After` header of a blocking response, if any
+fn retry_after_header(response: &reqwest::blocking::Response) -> Option<String> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// A value along with the wall-clock time it took to produce it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+/// Client for the OpenWeather API, holds onto the API key and units so they needn't be passed on every request.
+pub struct OpenWeatherClient {
+    credentials: Credentials,
+    units: WeatherUnits,
+    default_coords: Option<GeodeticCoords>,
+    base_url: String,
+    pro_base_url: String,
+    transport: Box<dyn HttpTransport>,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
+    cache: Option<ResponseCache>,
+    language: Option<Language>,
+}
+
+impl OpenWeatherClient {
+    /// Creates a new client which makes requests over HTTP with `reqwest`, use [`OpenWeatherClient::builder`] for
+    /// more configuration.
+    ///
+    /// # Panics
+    ///
+    /// Like `reqwest::blocking::Client::new`, this panics if the TLS backend can't be initialized.
+    pub fn new(api_key: String, units: WeatherUnits) -> Self {
+        Self::builder(api_key)
+            .units(units)
+            .build()
+            .expect("failed to build the HTTP client")
+    }
+
+    /// Starts building a client with more control over its configuration.
+    pub fn builder(api_key: String) -> OpenWeatherClientBuilder {
+        OpenWeatherClientBuilder::new(api_key)
+    }
+
+    /// Creates a new client which makes requests through the provided [`HttpTransport`].
+    pub fn with_transport(
+        api_key: String,
+        units: WeatherUnits,
+        transport: impl HttpTransport + 'static,
+    ) -> Self {
+        OpenWeatherClient {
+            credentials: Credentials::new(api_key),
+            units,
+            default_coords: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            pro_base_url: PRO_BASE_URL.to_string(),
+            transport: Box::new(transport),
+            rate_limiter: None,
+            retry_policy: None,
+            cache: None,
+            language: None,
+        }
+    }
+
+    /// Sets a default position for [`OpenWeatherClient::current_weather_default`], handy for fixed-location
+    /// deployments. Requests which take explicit coordinates are unaffected.
+    pub fn with_default_coords(mut self, coords: GeodeticCoords) -> Self {
+        self.default_coords = Some(coords);
+        self
+    }
+
+    /// Sets where the API key is placed on each request, by default it's sent as a query parameter.
+    pub fn with_api_key_placement(mut self, placement: ApiKeyPlacement) -> Self {
+        self.credentials.placement = placement;
+        self
+    }
+
+    /// The units responses from this client are requested in
+    pub fn units(&self) -> WeatherUnits {
+        self.units
+    }
+
+    /// Sets the language descriptions in responses are localized into, by default OpenWeather responds in English.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Request the current weather at a position, this is a blocking HTTP request.
+    pub fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        let response_text = self.raw_current_weather(coords, ResponseMode::Json)?;
+
+        Ok(parse_current_weather(&response_text)?.with_units(self.units))
+    }
+
+    /// Request the current weather at a position in the given [`ResponseMode`] and return the unparsed body, this
+    /// is a blocking HTTP request.
+    pub fn raw_current_weather(
+        &self,
+        coords: GeodeticCoords,
+        mode: ResponseMode,
+    ) -> Result<String, OpenWxError> {
+        self.get(self.localized(coords_path(&coords, &self.units, mode)))
+    }
+
+    /// Request the current weather at the client's default position, this is a blocking HTTP request. Fails with
+    /// [`OpenWxError::NoDefaultCoords`] if no default was configured.
+    pub fn current_weather_default(&self) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        let coords = self.default_coords.ok_or(OpenWxError::NoDefaultCoords)?;
+
+        self.current_weather(coords)
+    }
+
+    /// Request the current weather for an OpenWeather city ID, this is a blocking HTTP request.
+    pub fn current_weather_by_id(
+        &self,
+        city_id: u32,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        let response_text = self.get(self.localized(city_id_path(city_id, &self.units)))?;
+
+        Ok(parse_current_weather(&response_text)?.with_units(self.units))
+    }
+
+    /// Same as [`OpenWeatherClient::current_weather`] but also reports how long the request took, measured from just
+    /// before the GET until parsing completes.
+    pub fn current_weather_timed(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<Timed<OWCurrentWeatherResponse>, OpenWxError> {
+        let start = Instant::now();
+        let value = self.current_weather(coords)?;
+
+        Ok(Timed {
+            value,
+            elapsed: start.elapsed(),
+        })
+    }
+
+    /// Request the 5 day / 3 hour forecast for a position, this is a blocking HTTP request.
+    pub fn forecast(&self, coords: GeodeticCoords) -> Result<OWForecastResponse, OpenWxError> {
+        let response_text = self.get(self.localized(forecast_path(&coords, &self.units)))?;
+        let response: OWForecastResponse = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request the 4 day hourly forecast for a position, this is a blocking HTTP request. The hourly forecast requires a
+    /// paid subscription and is served from [`PRO_BASE_URL`], see [`OpenWeatherClientBuilder::pro_base_url`].
+    pub fn hourly_forecast(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWHourlyForecastResponse, OpenWxError> {
+        let path = self.localized(hourly_forecast_path(&coords, &self.units));
+        let response_text = self.get_from(&self.pro_base_url, path)?;
+        let response: OWHourlyForecastResponse = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request the daily forecast for a position for up to 16 days, this is a blocking HTTP request. `days` limits the
+    /// number of days returned, OpenWeather's default is 7. The daily forecast requires a paid subscription.
+    pub fn daily_forecast(
+        &self,
+        coords: GeodeticCoords,
+        days: Option<u8>,
+    ) -> Result<OWDailyForecastResponse, OpenWxError> {
+        let response_text =
+            self.get(self.localized(daily_forecast_path(&coords, &self.units, days)))?;
+
+        let response: OWDailyForecastResponse = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request the 30 day climate forecast for a position, this is a blocking HTTP request. The climate forecast
+    /// requires a paid subscription and is served from [`PRO_BASE_URL`], see [`OpenWeatherClientBuilder::pro_base_url`].
+    pub fn climate_forecast(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWClimateForecastResponse, OpenWxError> {
+        let path = self.localized(climate_forecast_path(&coords, &self.units));
+        let response_text = self.get_from(&self.pro_base_url, path)?;
+
+        let response: OWClimateForecastResponse = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request current air pollution for a position, this is a blocking HTTP request.
+    pub fn air_pollution(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWAirPollutionResponse, OpenWxError> {
+        let response_text = self.get(air_pollution_path(&coords))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request the hourly air pollution forecast for the next 4 days for a position, this is a blocking HTTP request.
+    pub fn air_pollution_forecast(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWAirPollutionResponse, OpenWxError> {
+        let response_text = self.get(air_pollution_forecast_path(&coords))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request hourly air pollution readings between `start` and `end` for a position, this is a blocking HTTP request.
+    /// Data is available from November 27th, 2020.
+    pub fn air_pollution_history(
+        &self,
+        coords: GeodeticCoords,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<OWAirPollutionResponse, OpenWxError> {
+        let response_text = self.get(air_pollution_history_path(&coords, start, end))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Resolve a place name like "San Diego,CA,US" into matching locations, this is a blocking HTTP request. `limit`
+    /// caps the number of results, up to OpenWeather's maximum of 5.
+    pub fn geocode(&self, query: &str, limit: Option<u8>) -> Result<Vec<GeoLocation>, OpenWxError> {
+        let response_text = self.get(direct_geocoding_path(query, limit))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Find the names of places near a position, this is a blocking HTTP request. `limit` caps the number of results,
+    /// up to OpenWeather's maximum of 5.
+    pub fn reverse_geocode(
+        &self,
+        coords: GeodeticCoords,
+        limit: Option<u8>,
+    ) -> Result<Vec<GeoLocation>, OpenWxError> {
+        let response_text = self.get(reverse_geocoding_path(&coords, limit))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Resolve a zip or postal code in the country with ISO 3166 code `country` into a location, this is a blocking HTTP
+    /// request.
+    pub fn geocode_zip(&self, zip: &str, country: &str) -> Result<ZipLocation, OpenWxError> {
+        let response_text = self.get(zip_geocoding_path(zip, country))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request weather, alerts, and road surface conditions along a route of waypoints from the Road Risk API, this is
+    /// a blocking HTTP request. Road Risk requires a separate subscription.
+    pub fn road_risk(
+        &self,
+        track: &[RoadRiskWaypoint],
+    ) -> Result<Vec<OWRoadRiskEntry>, OpenWxError> {
+        let response_text = self.post(ROAD_RISK_PATH, &road_risk_body(track))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request current solar radiation for a position, this is a blocking HTTP request. Solar radiation requires a
+    /// separate subscription.
+    pub fn solar_radiation(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWSolarRadiationResponse, OpenWxError> {
+        let response_text = self.get(solar_radiation_path(&coords))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request the hourly solar radiation forecast for the next 15 days for a position, this is a blocking HTTP
+    /// request. Solar radiation requires a separate subscription.
+    pub fn solar_radiation_forecast(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWSolarRadiationResponse, OpenWxError> {
+        let response_text = self.get(solar_radiation_forecast_path(&coords))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request hourly solar radiation readings between `start` and `end` for a position, this is a blocking HTTP
+    /// request. Solar radiation requires a separate subscription.
+    pub fn solar_radiation_history(
+        &self,
+        coords: GeodeticCoords,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<OWSolarRadiationResponse, OpenWxError> {
+        let response_text = self.get(solar_radiation_history_path(&coords, start, end))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Create a Weather Trigger which fires when all of its conditions are forecast in its area, this is a blocking HTTP
+    /// request. Returns the stored trigger including its ID.
+    pub fn create_trigger(&self, trigger: &NewTrigger) -> Result<OWTrigger, OpenWxError> {
+        let response_text = self.post(TRIGGERS_PATH, &trigger.to_body())?;
+
+        parse_response(&response_text)
+    }
+
+    /// List every Weather Trigger on the account, this is a blocking HTTP request.
+    pub fn triggers(&self) -> Result<Vec<OWTrigger>, OpenWxError> {
+        let response_text = self.get_uncached(TRIGGERS_PATH)?;
+
+        parse_response(&response_text)
+    }
+
+    /// Fetch a single Weather Trigger by ID, this is a blocking HTTP request.
+    pub fn trigger(&self, id: &str) -> Result<OWTrigger, OpenWxError> {
+        let response_text = self.get_uncached(&trigger_path(id))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Replace the Weather Trigger with ID `id`, this is a blocking HTTP request.
+    pub fn update_trigger(&self, id: &str, trigger: &NewTrigger) -> Result<OWTrigger, OpenWxError> {
+        let response_text = self.put(&trigger_path(id), &trigger.to_body())?;
+
+        parse_response(&response_text)
+    }
+
+    /// Delete the Weather Trigger with ID `id`, this is a blocking HTTP request.
+    pub fn delete_trigger(&self, id: &str) -> Result<(), OpenWxError> {
+        self.delete(&trigger_path(id))
+    }
+
+    /// Register a personal weather station, this is a blocking HTTP request. Returns the stored station including the
+    /// ID to push measurements under.
+    pub fn register_station(&self, station: &NewStation) -> Result<OWStation, OpenWxError> {
+        let response_text = self.post(STATIONS_PATH, &station.to_body())?;
+
+        parse_response(&response_text)
+    }
+
+    /// List every station registered on the account, this is a blocking HTTP request.
+    pub fn stations(&self) -> Result<Vec<OWStation>, OpenWxError> {
+        let response_text = self.get_uncached(STATIONS_PATH)?;
+
+        parse_response(&response_text)
+    }
+
+    /// Delete the station with ID `id` along with its measurements, this is a blocking HTTP request.
+    pub fn delete_station(&self, id: &str) -> Result<(), OpenWxError> {
+        self.delete(&format!("{STATIONS_PATH}/{}", encode_query_value(id)))
+    }
+
+    /// Push a batch of observations from registered stations, this is a blocking HTTP request.
+    pub fn push_measurements(
+        &self,
+        measurements: &[StationMeasurement],
+    ) -> Result<(), OpenWxError> {
+        self.post(MEASUREMENTS_PATH, &measurements_body(measurements))?;
+
+        Ok(())
+    }
+
+    /// Fetch up to `limit` measurements from a station between `from` and `to`, aggregated over `period`, this is a
+    /// blocking HTTP request.
+    pub fn measurements(
+        &self,
+        station_id: &str,
+        period: AggregationPeriod,
+        limit: u32,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<OWAggregatedMeasurement>, OpenWxError> {
+        let path = format!(
+            "{MEASUREMENTS_PATH}?station_id={}&type={period}&limit={limit}&from={}&to={}",
+            encode_query_value(station_id),
+            from.timestamp(),
+            to.timestamp()
+        );
+        let response_text = self.get_uncached(&path)?;
+
+        parse_response(&response_text)
+    }
+
+    /// Request current weather, forecasts, and alerts from the One Call 3.0 API, leaving out any `exclude`d blocks.
+    /// This is a blocking HTTP request, and One Call requires a separate subscription from the free tier.
+    pub fn one_call(
+        &self,
+        coords: GeodeticCoords,
+        exclude: &[OneCallBlock],
+    ) -> Result<OWOneCallResponse, OpenWxError> {
+        let response_text =
+            self.get(self.localized(one_call_path(&coords, &self.units, exclude)))?;
+
+        let response: OWOneCallResponse = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request historical weather at `timestamp` from One Call's timemachine route, this is a blocking HTTP request.
+    /// Data is available from January 1st, 1979 up to 4 days ahead.
+    pub fn timemachine(
+        &self,
+        coords: GeodeticCoords,
+        timestamp: DateTime<Utc>,
+    ) -> Result<OWTimeMachineResponse, OpenWxError> {
+        let response_text =
+            self.get(self.localized(timemachine_path(&coords, timestamp, &self.units)))?;
+
+        let response: OWTimeMachineResponse = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request weather aggregated over `date` from One Call's day summary route, this is a blocking HTTP request. Data
+    /// is available from January 2nd, 1979 up to 1.5 years ahead.
+    pub fn day_summary(
+        &self,
+        coords: GeodeticCoords,
+        date: NaiveDate,
+    ) -> Result<OWDaySummary, OpenWxError> {
+        let response_text =
+            self.get(self.localized(day_summary_path(&coords, date, &self.units)))?;
+
+        let response: OWDaySummary = parse_response(&response_text)?;
+
+        Ok(response.with_units(self.units))
+    }
+
+    /// Request a human-readable summary of the weather from One Call's overview route, this is a blocking HTTP
+    /// request. `date` may be today or tomorrow, and defaults to today.
+    pub fn overview(
+        &self,
+        coords: GeodeticCoords,
+        date: Option<NaiveDate>,
+    ) -> Result<OWWeatherOverview, OpenWxError> {
+        let response_text = self.get(overview_path(&coords, date, &self.units))?;
+
+        parse_response(&response_text)
+    }
+
+    /// Adds the client's language, if any, to the path of an endpoint which localizes its responses
+    fn localized(&self, path_and_query: String) -> String {
+        match self.language {
+            Some(language) => append_query_param(&path_and_query, "lang", &language.to_string()),
+            None => path_and_query,
+        }
+    }
+
+    /// Sends a request through the rate limiter and retry policy, if there are any. Every attempt waits its turn with
+    /// the rate limiter.
+    fn send<T>(
+        &self,
+        idempotent: bool,
+        request: impl Fn() -> Result<T, OpenWxError>,
+    ) -> Result<T, OpenWxError> {
+        let attempt = || {
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire();
+            }
+
+            request()
+        };
+
+        match &self.retry_policy {
+            Some(policy) => policy.run(idempotent, attempt),
+            None => attempt(),
+        }
+    }
+
+    /// Authenticate a request for the endpoint at `path_and_query` and send it through the transport, unless an
+    /// identical request was recently cached
+    fn get(&self, path_and_query: String) -> Result<String, OpenWxError> {
+        self.get_from(&self.base_url, path_and_query)
+    }
+
+    /// Like [`Self::get`], but for an endpoint served from `base_url` rather than the client's usual host
+    fn get_from(&self, base_url: &str, path_and_query: String) -> Result<String, OpenWxError> {
+        let url = format!("{base_url}{path_and_query}");
+
+        if let Some(body) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(&url, SystemTime::now()))
+        {
+            return Ok(body);
+        }
+
+        let request = self.credentials.authenticate(url.clone());
+        let body = self.send(true, || self.transport.get(&request))?;
+
+        if let Some(cache) = &self.cache {
+            cache.insert(url, body.clone(), SystemTime::now());
+        }
+
+        Ok(body)
+    }
+
+    /// Authenticate a request for an account resource at `path_and_query`, like triggers or stations, and send it
+    /// through the transport. These change whenever they're edited, so they're never cached.
+    fn get_uncached(&self, path_and_query: &str) -> Result<String, OpenWxError> {
+        let request = self
+            .credentials
+            .authenticate(format!("{}{path_and_query}", self.base_url));
+
+        self.send(true, || self.transport.get(&request))
+    }
+
+    /// Authenticate a JSON POST to the endpoint at `path` and send it through the transport
+    fn post(&self, path: &str, body: &str) -> Result<String, OpenWxError> {
+        let request = self
+            .credentials
+            .authenticate(format!("{}{path}", self.base_url));
+
+        self.send(false, || self.transport.post_json(&request, body))
+    }
+
+    /// Authenticate a JSON PUT to the endpoint at `path` and send it through the transport
+    fn put(&self, path: &str, body: &str) -> Result<String, OpenWxError> {
+        let request = self
+            .credentials
+            .authenticate(format!("{}{path}", self.base_url));
+
+        self.send(true, || self.transport.put_json(&request, body))
+    }
+
+    /// Authenticate a DELETE of the resource at `path` and send it through the transport
+    fn delete(&self, path: &str) -> Result<(), OpenWxError> {
+        let request = self
+            .credentials
+            .authenticate(format!("{}{path}", self.base_url));
+
+        self.send(true, || self.transport.delete(&request))
+    }
+
+    /// Authenticate a request for an absolute `url`, which may be outside the base URL, and return the raw body
+    pub(crate) fn get_bytes(&self, url: String) -> Result<Vec<u8>, OpenWxError> {
+        let request = self.credentials.authenticate(url);
+
+        self.send(true, || self.transport.get_bytes(&request))
+    }
+
+    /// Request an absolute `url` for a public resource without the API key, and return the raw body
+    pub(crate) fn get_public_bytes(&self, url: String) -> Result<Vec<u8>, OpenWxError> {
+        let request = HttpRequest {
+            url,
+            headers: Vec::new(),
+        };
+
+        self.send(true, || self.transport.get_bytes(&request))
+    }
+}
+
+/// Builder for an [`OpenWeatherClient`], created with [`OpenWeatherClient::builder`].
+pub struct OpenWeatherClientBuilder {
+    credentials: Credentials,
+    units: WeatherUnits,
+    default_coords: Option<GeodeticCoords>,
+    base_url: String,
+    pro_base_url: String,
+    user_agent: String,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    transport: Option<Box<dyn HttpTransport>>,
+    rate_limiter: Option<RateLimiter>,
+    retry_policy: Option<RetryPolicy>,
+    cache: Option<ResponseCache>,
+    language: Option<Language>,
+}
+
+impl OpenWeatherClientBuilder {
+    fn new(api_key: String) -> Self {
+        OpenWeatherClientBuilder {
+            credentials: Credentials::new(api_key),
+            units: WeatherUnits::Standard,
+            default_coords: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            pro_base_url: PRO_BASE_URL.to_string(),
+            user_agent: concat!("openwx/", env!("CARGO_PKG_VERSION")).to_string(),
+            connect_timeout: None,
+            timeout: None,
+            transport: None,
+            rate_limiter: None,
+            retry_policy: None,
+            cache: None,
+            language: None,
+        }
+    }
+
+    /// Units to request responses in, defaults to [`WeatherUnits::Standard`] like the API itself.
+    pub fn units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Where the API key is placed on each request, defaults to a query parameter.
+    pub fn api_key_placement(mut self, placement: ApiKeyPlacement) -> Self {
+        self.credentials.placement = placement;
+        self
+    }
+
+    /// Default position for [`OpenWeatherClient::current_weather_default`].
+    pub fn default_coords(mut self, coords: GeodeticCoords) -> Self {
+        self.default_coords = Some(coords);
+        self
+    }
+
+    /// Language descriptions in responses are localized into, OpenWeather defaults to English.
+    pub fn language(mut self, language: Language) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Scheme and host requests are sent to, defaults to [`DEFAULT_BASE_URL`]. Useful for pointing at a mock server
+    /// in tests or routing through a proxy.
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Scheme and host of OpenWeather's paid endpoints which are only served from the pro host, the hourly and
+    /// climate forecasts, defaults to [`PRO_BASE_URL`]. Like [`Self::base_url`], useful for a mock server in tests.
+    pub fn pro_base_url(mut self, pro_base_url: impl Into<String>) -> Self {
+        self.pro_base_url = pro_base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// User agent sent with each request, defaults to `openwx/<version>`.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Timeout for establishing a connection.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout for an entire request, from connecting through reading the response body. `reqwest` defaults to 30
+    /// seconds for blocking clients.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Throttle requests through a [`RateLimiter`], e.g. [`RateLimiter::free_tier`], so bursts sleep instead of being
+    /// rejected by OpenWeather with a 429. Unlimited by default.
+    pub fn rate_limit(mut self, limiter: RateLimiter) -> Self {
+        self.rate_limiter = Some(limiter);
+        self
+    }
+
+    /// Retry transient failures according to a [`RetryPolicy`], e.g. `RetryPolicy::default()`. Failures are returned
+    /// immediately by default.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Serve repeated requests from a [`ResponseCache`], e.g. [`ResponseCache::openweather_refresh`], instead of the
+    /// network. Nothing is cached by default.
+    pub fn cache(mut self, cache: ResponseCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Send requests through a custom [`HttpTransport`] rather than `reqwest`. The user agent and timeouts only
+    /// configure the default transport, so they're ignored when a custom transport is provided.
+    pub fn transport(mut self, transport: impl HttpTransport + 'static) -> Self {
+        self.transport = Some(Box::new(transport));
+        self
+    }
+
+    /// Builds the client, fails if the underlying `reqwest` client can't be built.
+    pub fn build(self) -> Result<OpenWeatherClient, OpenWxError> {
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut builder = reqwest::blocking::Client::builder().user_agent(self.user_agent);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+
+                let client = builder.build().map_err(OpenWxError::HttpClientError)?;
+                Box::new(ReqwestTransport::from_client(client))
+            }
+        };
+
+        Ok(OpenWeatherClient {
+            credentials: self.credentials,
+            units: self.units,
+            default_coords: self.default_coords,
+            base_url: self.base_url,
+            pro_base_url: self.pro_base_url,
+            transport,
+            rate_limiter: self.rate_limiter,
+            retry_policy: self.retry_policy,
+            cache: self.cache,
+            language: self.language,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::onecall::tests::ONE_CALL_RESPONSE;
+    use crate::types::tests::ZOCCA_RESPONSE;
+    use crate::{TriggerArea, TriggerCondition, TriggerParameter};
+    use std::sync::{Arc, Mutex};
+
+    /// Transport which serves a canned body after an optional delay and records every request it was asked for
+    #[derive(Clone, Default)]
+    pub(crate) struct FakeTransport {
+        pub(crate) body: String,
+        pub(crate) delay: Duration,
+        pub(crate) requested: Arc<Mutex<Vec<HttpRequest>>>,
+        pub(crate) posted: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl FakeTransport {
+        pub(crate) fn serving(body: &str) -> Self {
+            FakeTransport {
+                body: body.to_string(),
+                ..Default::default()
+            }
+        }
+
+        pub(crate) fn requests(&self) -> Vec<HttpRequest> {
+            self.requested.lock().unwrap().clone()
+        }
+
+        pub(crate) fn requested_urls(&self) -> Vec<String> {
+            self.requests().into_iter().map(|r| r.url).collect()
+        }
+    }
+
+    impl HttpTransport for FakeTransport {
+        fn get(&self, request: &HttpRequest) -> Result<String, OpenWxError> {
+            std::thread::sleep(self.delay);
+            self.requested.lock().unwrap().push(request.clone());
+
+            Ok(self.body.clone())
+        }
+
+        fn post_json(&self, request: &HttpRequest, body: &str) -> Result<String, OpenWxError> {
+            self.posted.lock().unwrap().push(body.to_string());
+
+            self.get(request)
+        }
+
+        fn put_json(&self, request: &HttpRequest, body: &str) -> Result<String, OpenWxError> {
+            self.post_json(request, body)
+        }
+
+        fn delete(&self, request: &HttpRequest) -> Result<(), OpenWxError> {
+            self.get(request).map(|_| ())
+        }
+    }
+
+    #[test]
+    fn current_weather_through_transport() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let response = client.current_weather(coords).unwrap();
+        assert_eq!(response.name, "Zocca");
+
+        let urls = transport.requested_urls();
+        assert_eq!(urls.len(), 1);
+        assert!(urls[0].contains("lat=44.34&lon=10.99"));
+        assert!(urls[0].contains("units=metric"));
+    }
+
+    #[test]
+    fn api_key_placement() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+        client.current_weather(coords).unwrap();
+
+        let header_client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        )
+        .with_api_key_placement(ApiKeyPlacement::Header);
+        header_client.current_weather(coords).unwrap();
+
+        let requests = transport.requests();
+        assert!(requests[0].url.ends_with("&appid=key"));
+        assert!(requests[0].headers.is_empty());
+
+        assert!(!requests[1].url.contains("appid"));
+        assert!(!requests[1].url.contains("key"));
+        assert_eq!(
+            requests[1].headers,
+            vec![(API_KEY_HEADER.to_string(), "key".to_string())]
+        );
+    }
+
+    #[test]
+    fn default_coords() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let home = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        )
+        .with_default_coords(home);
+
+        client.current_weather_default().unwrap();
+        assert!(transport.requested_urls()[0].contains("lat=33.545&lon=-117.771"));
+
+        // Explicit coordinates override the default
+        let zocca = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        client.current_weather(zocca).unwrap();
+        assert!(transport.requested_urls()[1].contains("lat=44.34&lon=10.99"));
+    }
+
+    #[test]
+    fn default_coords_unset() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let err = client.current_weather_default().unwrap_err();
+        assert!(matches!(err, OpenWxError::NoDefaultCoords));
+        assert!(transport.requested_urls().is_empty());
+    }
+
+    #[test]
+    fn builder() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let home = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+
+        let client = OpenWeatherClient::builder("key".into())
+            .units(WeatherUnits::Imperial)
+            .base_url("http://localhost:8080/")
+            .default_coords(home)
+            .transport(transport.clone())
+            .build()
+            .unwrap();
+        assert_eq!(client.units(), WeatherUnits::Imperial);
+
+        client.current_weather_default().unwrap();
+        let url = &transport.requested_urls()[0];
+        assert!(url.starts_with("http://localhost:8080/data/2.5/weather?lat=33.545&lon=-117.771"));
+        assert!(url.contains("units=imperial"));
+
+        let reqwest_client = OpenWeatherClient::builder("key".into())
+            .user_agent("weather-station/1.0")
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(10))
+            .build();
+        assert!(reqwest_client.is_ok());
+    }
+
+    #[test]
+    fn forecast_through_transport() {
+        use crate::forecast::tests::FORECAST_RESPONSE;
+
+        let transport = FakeTransport::serving(FORECAST_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Standard,
+            transport.clone(),
+        );
+
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let forecast = client.forecast(coords).unwrap();
+        assert_eq!(forecast.list.len(), 3);
+        assert_eq!(forecast.city.name, "Zocca");
+
+        let url = &transport.requested_urls()[0];
+        assert!(url.starts_with(
+            "https://api.openweathermap.org/data/2.5/forecast?lat=44.34&lon=10.99&units=standard"
+        ));
+    }
+
+    #[test]
+    fn timemachine_timestamp() {
+        let transport = FakeTransport::serving("{}");
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let coords = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+        let timestamp = DateTime::from_timestamp(1719000000, 0).unwrap();
+
+        // The empty body fails to parse, we only care about the request here
+        assert!(client.timemachine(coords, timestamp).is_err());
+        assert!(
+            transport.requested_urls()[0]
+                .contains("/onecall/timemachine?lat=33.545&lon=-117.771&dt=1719000000&")
+        );
+    }
+
+    #[test]
+    fn air_pollution_through_transport() {
+        use crate::air_quality::tests::AIR_POLLUTION_RESPONSE;
+
+        let transport = FakeTransport::serving(AIR_POLLUTION_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let coords = GeodeticCoords::new_checked(50.0, 50.0).unwrap();
+        let response = client.air_pollution(coords).unwrap();
+        assert_eq!(response.list.len(), 1);
+
+        let url = &transport.requested_urls()[0];
+        assert!(url.starts_with(
+            "https://api.openweathermap.org/data/2.5/air_pollution?lat=50&lon=50&appid="
+        ));
+    }
+
+    #[test]
+    fn road_risk_posts_track() {
+        use crate::road_risk::tests::ROAD_RISK_RESPONSE;
+
+        let transport = FakeTransport::serving(ROAD_RISK_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let track = [RoadRiskWaypoint {
+            coords: GeodeticCoords::new_checked(7.27, 44.04).unwrap(),
+            dt: DateTime::from_timestamp(1602702000, 0).unwrap(),
+        }];
+        let entries = client.road_risk(&track).unwrap();
+        assert_eq!(entries.len(), 1);
+
+        assert_eq!(
+            transport.requested_urls()[0],
+            "https://api.openweathermap.org/data/2.5/roadrisk?appid=key"
+        );
+        assert!(transport.posted.lock().unwrap()[0].contains("\"track\""));
+    }
+
+    #[test]
+    fn trigger_crud() {
+        use crate::triggers::tests::TRIGGER_RESPONSE;
+
+        let transport = FakeTransport::serving(TRIGGER_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let new_trigger = NewTrigger {
+            start_after: Duration::from_secs(132000),
+            end_after: Duration::from_secs(432000),
+            conditions: vec![TriggerCondition::on(TriggerParameter::Temp).gt(299.0)],
+            area: vec![TriggerArea::Point(
+                GeodeticCoords::new_checked(37.0, 53.0).unwrap(),
+            )],
+        };
+
+        let created = client.create_trigger(&new_trigger).unwrap();
+        client.update_trigger(&created.id, &new_trigger).unwrap();
+        client.delete_trigger(&created.id).unwrap();
+
+        let urls = transport.requested_urls();
+        assert_eq!(
+            urls[0],
+            "https://api.openweathermap.org/data/3.0/triggers?appid=key"
+        );
+        assert_eq!(
+            urls[2],
+            "https://api.openweathermap.org/data/3.0/triggers/5852816a9aaacb00153134a3?appid=key"
+        );
+        assert_eq!(transport.posted.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn station_measurements() {
+        let transport = FakeTransport::serving("[]");
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        let dt = DateTime::from_timestamp(1479817340, 0).unwrap();
+        let measurement = StationMeasurement {
+            station_id: "abc".into(),
+            dt,
+            temperature: Some(18.7),
+            ..Default::default()
+        };
+        client.push_measurements(&[measurement]).unwrap();
+
+        let measurements = client
+            .measurements("abc", AggregationPeriod::Hour, 10, dt, dt)
+            .unwrap();
+        assert!(measurements.is_empty());
+
+        assert_eq!(
+            transport.requested_urls()[1],
+            "https://api.openweathermap.org/data/3.0/measurements?station_id=abc&type=h&limit=10\
+            &from=1479817340&to=1479817340&appid=key"
+        );
+    }
+
+    #[test]
+    fn one_call_through_transport() {
+        use crate::onecall::tests::ONE_CALL_RESPONSE;
+
+        let transport = FakeTransport::serving(ONE_CALL_RESPONSE);
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Imperial,
+            transport.clone(),
+        );
+
+        let coords = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+        let response = client.one_call(coords, &[OneCallBlock::Minutely]).unwrap();
+        assert_eq!(response.timezone, "America/Los_Angeles");
+
+        let url = &transport.requested_urls()[0];
+        assert!(url.starts_with("https://api.openweathermap.org/data/3.0/onecall?"));
+        assert!(url.contains("exclude=minutely"));
+    }
+
+    #[test]
+    fn rate_limited_client() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(transport.clone())
+            .rate_limit(RateLimiter::new_checked(1, Duration::from_millis(50)).unwrap())
+            .build()
+            .unwrap();
+
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let start = Instant::now();
+        for _ in 0..3 {
+            client.current_weather(coords).unwrap();
+        }
+
+        // The first call spends the only token, the next two each wait for a refill
+        assert!(start.elapsed() >= Duration::from_millis(100));
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    #[test]
+    fn responses_labeled_with_units() {
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            FakeTransport::serving(ZOCCA_RESPONSE),
+        );
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        let response = client.current_weather(coords).unwrap();
+        assert_eq!(response.main.temp.units(), WeatherUnits::Metric);
+        assert_eq!(response.main.temp_max.units(), WeatherUnits::Metric);
+        assert_eq!(response.units, WeatherUnits::Metric);
+        assert_eq!(response.wind().unwrap().speed.units(), WeatherUnits::Metric);
+    }
+
+    #[test]
+    fn pro_endpoints_use_pro_host() {
+        let transport = FakeTransport::serving("not json");
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Standard,
+            transport.clone(),
+        );
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        client.hourly_forecast(coords).unwrap_err();
+        client.climate_forecast(coords).unwrap_err();
+        client.forecast(coords).unwrap_err();
+
+        let urls = transport.requested_urls();
+        assert!(urls[0].starts_with("https://pro.openweathermap.org/data/2.5/forecast/hourly?"));
+        assert!(urls[1].starts_with("https://pro.openweathermap.org/data/2.5/forecast/climate?"));
+        assert!(urls[2].starts_with("https://api.openweathermap.org/data/2.5/forecast?"));
+
+        // Overriding one host leaves the other alone
+        let transport = FakeTransport::serving("not json");
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(transport.clone())
+            .base_url("http://localhost:8080")
+            .pro_base_url("http://localhost:8081/")
+            .build()
+            .unwrap();
+
+        client.hourly_forecast(coords).unwrap_err();
+        client.forecast(coords).unwrap_err();
+
+        let urls = transport.requested_urls();
+        assert!(urls[0].starts_with("http://localhost:8081/data/2.5/forecast/hourly?"));
+        assert!(urls[1].starts_with("http://localhost:8080/data/2.5/forecast?"));
+    }
+
+    #[test]
+    fn one_call_labeled_with_units() {
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Imperial,
+            FakeTransport::serving(ONE_CALL_RESPONSE),
+        );
+        let coords = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+
+        let response = client.one_call(coords, &[]).unwrap();
+        assert_eq!(response.units, WeatherUnits::Imperial);
+        assert_eq!(
+            response.current.unwrap().temp.units(),
+            WeatherUnits::Imperial
+        );
+        assert_eq!(response.daily[0].temp.max.units(), WeatherUnits::Imperial);
+        assert_eq!(
+            response.hourly[0].wind_speed.units(),
+            WeatherUnits::Imperial
+        );
+    }
+
+    #[test]
+    fn localized_requests() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(transport.clone())
+            .language(Language::German)
+            .build()
+            .unwrap();
+
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        client.current_weather(coords).unwrap();
+        client.air_pollution(coords).unwrap_err();
+
+        let urls = transport.requested_urls();
+        assert!(urls[0].contains("&lang=de"));
+        assert!(!urls[1].contains("lang="));
+    }
+
+    #[test]
+    fn cached_client() {
+        let transport = FakeTransport::serving(ZOCCA_RESPONSE);
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(transport.clone())
+            .cache(ResponseCache::new(Duration::from_secs(60)))
+            .build()
+            .unwrap();
+
+        let zocca = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let elsewhere = GeodeticCoords::new_checked(44.35, 10.99).unwrap();
+        client.current_weather(zocca).unwrap();
+        client.current_weather(zocca).unwrap();
+        client.current_weather(elsewhere).unwrap();
+        client.forecast(zocca).unwrap_err();
+
+        // Only the repeated query is served from the cache
+        assert_eq!(transport.requests().len(), 3);
+    }
+
+    /// Transport which stores posted triggers and lists them back, like the Weather Triggers API
+    #[derive(Default)]
+    struct TriggerStore {
+        triggers: Mutex<Vec<String>>,
+    }
+
+    impl HttpTransport for TriggerStore {
+        fn get(&self, _request: &HttpRequest) -> Result<String, OpenWxError> {
+            Ok(format!("[{}]", self.triggers.lock().unwrap().join(",")))
+        }
+
+        fn post_json(&self, _request: &HttpRequest, _body: &str) -> Result<String, OpenWxError> {
+            let created = crate::triggers::tests::TRIGGER_RESPONSE.to_string();
+            self.triggers.lock().unwrap().push(created.clone());
+
+            Ok(created)
+        }
+    }
+
+    #[test]
+    fn cached_client_lists_new_triggers() {
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(TriggerStore::default())
+            .cache(ResponseCache::new(Duration::from_secs(60)))
+            .build()
+            .unwrap();
+        assert!(client.triggers().unwrap().is_empty());
+
+        let new_trigger = NewTrigger {
+            start_after: Duration::from_secs(132000),
+            end_after: Duration::from_secs(432000),
+            conditions: vec![TriggerCondition::on(TriggerParameter::Temp).gt(299.0)],
+            area: vec![TriggerArea::Point(
+                GeodeticCoords::new_checked(37.0, 53.0).unwrap(),
+            )],
+        };
+        let created = client.create_trigger(&new_trigger).unwrap();
+
+        // The earlier listing wasn't cached, so the new trigger shows up right away
+        assert_eq!(client.triggers().unwrap(), [created]);
+    }
+
+    /// Transport which fails with a server error a fixed number of times before serving a body
+    struct FlakyTransport {
+        failures: Mutex<u32>,
+        body: String,
+    }
+
+    impl HttpTransport for FlakyTransport {
+        fn get(&self, _request: &HttpRequest) -> Result<String, OpenWxError> {
+            let mut failures = self.failures.lock().unwrap();
+            if *failures > 0 {
+                *failures -= 1;
+                return Err(api_error(503, Some("0"), ""));
+            }
+
+            Ok(self.body.clone())
+        }
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let flaky = |failures| FlakyTransport {
+            failures: Mutex::new(failures),
+            body: ZOCCA_RESPONSE.to_string(),
+        };
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(flaky(2))
+            .retry_policy(RetryPolicy::default())
+            .build()
+            .unwrap();
+        assert!(client.current_weather(coords).is_ok());
+
+        let client = OpenWeatherClient::builder("key".into())
+            .transport(flaky(1))
+            .build()
+            .unwrap();
+        let err = client.current_weather(coords).unwrap_err();
+        assert!(err.is_transient());
+    }
+
+    #[test]
+    fn timed_request() {
+        let transport = FakeTransport {
+            delay: Duration::from_millis(5),
+            ..FakeTransport::serving(ZOCCA_RESPONSE)
+        };
+        let client =
+            OpenWeatherClient::with_transport("key".into(), WeatherUnits::Metric, transport);
+
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+        let timed = client.current_weather_timed(coords).unwrap();
+        assert_eq!(timed.value.name, "Zocca");
+        assert!(timed.elapsed >= Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,249 @@
+//! A long-running collector which polls the current weather at several locations and records every observation,
+//! backing off and retrying a location when fetching or recording it fails instead of waiting a whole interval.
+
+use crate::{NamedLocation, OWCurrentWeatherResponse, OpenWeatherClient};
+use std::io;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// OpenWeather refreshes its data every 10 minutes, collecting any faster just records duplicates
+pub const DEFAULT_COLLECT_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Wait before the first retry of a failed location, doubling on each failure up to the collection interval
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(15);
+
+#[derive(Error, Debug)]
+pub enum CollectError {
+    #[error("failed to write the observation")]
+    Io(#[from] io::Error),
+
+    #[error("failed to serialize the observation")]
+    Serialization(#[from] serde_json::Error),
+
+    #[cfg(feature = "sqlite")]
+    #[error("failed to store the observation")]
+    Storage(#[from] crate::StorageError),
+}
+
+/// Somewhere a [`Collector`] records observations
+pub trait ObservationSink {
+    fn record(
+        &mut self,
+        location: &str,
+        response: &OWCurrentWeatherResponse,
+    ) -> Result<(), CollectError>;
+}
+
+#[cfg(feature = "sqlite")]
+impl ObservationSink for crate::ObservationStore {
+    fn record(
+        &mut self,
+        location: &str,
+        response: &OWCurrentWeatherResponse,
+    ) -> Result<(), CollectError> {
+        Ok(self.append(location, response)?)
+    }
+}
+
+/// When a location is next due, and how many times in a row it has failed
+#[derive(Debug, Clone, Copy)]
+struct Schedule {
+    due: Instant,
+    failures: u32,
+}
+
+/// Polls the current weather at each of its locations every interval and records the observations to a sink. Build
+/// with [`Collector::new`] and start with [`Collector::run`].
+pub struct Collector {
+    client: OpenWeatherClient,
+    locations: Vec<NamedLocation>,
+    sink: Box<dyn ObservationSink>,
+    interval: Duration,
+    retry_delay: Duration,
+    schedules: Vec<Schedule>,
+}
+
+impl Collector {
+    /// Creates a collector recording to `sink` every [`DEFAULT_COLLECT_INTERVAL`], every location is due immediately.
+    pub fn new(
+        client: OpenWeatherClient,
+        locations: Vec<NamedLocation>,
+        sink: impl ObservationSink + 'static,
+    ) -> Self {
+        let now = Instant::now();
+        let schedules = locations
+            .iter()
+            .map(|_| Schedule {
+                due: now,
+                failures: 0,
+            })
+            .collect();
+
+        Collector {
+            client,
+            locations,
+            sink: Box::new(sink),
+            interval: DEFAULT_COLLECT_INTERVAL,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            schedules,
+        }
+    }
+
+    /// Collect on this interval instead of the default.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Wait this long before first retrying a failed location instead of the default.
+    pub fn retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Collects every location due at `now`, this makes blocking HTTP requests. Returns when the next location is
+    /// due.
+    pub fn collect_due(&mut self, now: Instant) -> Instant {
+        for (location, schedule) in self.locations.iter().zip(self.schedules.iter_mut()) {
+            if schedule.due > now {
+                continue;
+            }
+
+            let result = self
+                .client
+                .current_weather(location.coords)
+                .map_err(|e| e.to_string())
+                .and_then(|response| {
+                    self.sink
+                        .record(&location.name, &response)
+                        .map_err(|e| e.to_string())
+                });
+
+            match result {
+                Ok(()) => {
+                    if schedule.failures > 0 {
+                        log::info!(
+                            "collected `{}` after {} failed attempts",
+                            location.name,
+                            schedule.failures
+                        );
+                    } else {
+                        log::info!("collected `{}`", location.name);
+                    }
+
+                    schedule.failures = 0;
+                    schedule.due = now + self.interval;
+                }
+                Err(e) => {
+                    let delay = self
+                        .retry_delay
+                        .saturating_mul(2u32.saturating_pow(schedule.failures))
+                        .min(self.interval);
+                    log::warn!(
+                        "failed to collect `{}`, retrying in {}s: {e}",
+                        location.name,
+                        delay.as_secs()
+                    );
+
+                    schedule.failures += 1;
+                    schedule.due = now + delay;
+                }
+            }
+        }
+
+        self.schedules
+            .iter()
+            .map(|schedule| schedule.due)
+            .min()
+            .unwrap_or(now + self.interval)
+    }
+
+    /// Collects forever, sleeping until the next location is due between rounds.
+    pub fn run(mut self) -> ! {
+        log::info!(
+            "collecting {} locations every {}s",
+            self.locations.len(),
+            self.interval.as_secs()
+        );
+
+        loop {
+            let next = self.collect_due(Instant::now());
+            std::thread::sleep(next.saturating_duration_since(Instant::now()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::FakeTransport;
+    use crate::types::tests::ZOCCA_RESPONSE;
+    use crate::{GeodeticCoords, WeatherUnits};
+    use std::sync::{Arc, Mutex};
+
+    /// Records the location names it was given, shared so the test can look after handing it to the collector
+    #[derive(Clone, Default)]
+    struct MemorySink(Arc<Mutex<Vec<String>>>);
+
+    impl ObservationSink for MemorySink {
+        fn record(
+            &mut self,
+            location: &str,
+            _response: &OWCurrentWeatherResponse,
+        ) -> Result<(), CollectError> {
+            self.0.lock().unwrap().push(location.to_string());
+            Ok(())
+        }
+    }
+
+    fn collector(body: &str, sink: MemorySink) -> Collector {
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            FakeTransport::serving(body),
+        );
+        let locations = vec![
+            NamedLocation::new("zocca", GeodeticCoords::new_checked(44.34, 10.99).unwrap()),
+            NamedLocation::new("laguna", GeodeticCoords::new_checked(33.5, -117.7).unwrap()),
+        ];
+
+        Collector::new(client, locations, sink)
+            .interval(Duration::from_secs(600))
+            .retry_delay(Duration::from_secs(15))
+    }
+
+    #[test]
+    fn collects_every_location_each_interval() {
+        let sink = MemorySink::default();
+        let mut collector = collector(ZOCCA_RESPONSE, sink.clone());
+        let start = Instant::now();
+
+        let next = collector.collect_due(start);
+        assert_eq!(next, start + Duration::from_secs(600));
+        assert_eq!(*sink.0.lock().unwrap(), ["zocca", "laguna"]);
+
+        // Nothing is due before the interval has passed
+        collector.collect_due(start + Duration::from_secs(300));
+        assert_eq!(sink.0.lock().unwrap().len(), 2);
+
+        collector.collect_due(next);
+        assert_eq!(sink.0.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn failures_back_off_up_to_the_interval() {
+        let sink = MemorySink::default();
+        let mut collector = collector("not json", sink.clone());
+        let mut now = Instant::now();
+
+        let mut delays = Vec::new();
+        for _ in 0..7 {
+            let next = collector.collect_due(now);
+            delays.push((next - now).as_secs());
+            now = next;
+        }
+
+        assert_eq!(delays, [15, 30, 60, 120, 240, 480, 600]);
+        assert!(sink.0.lock().unwrap().is_empty());
+    }
+}
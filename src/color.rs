@@ -0,0 +1,91 @@
+//! ANSI colors for summaries printed to a terminal: temperatures on a blue to red gradient, severe conditions
+//! highlighted, and nighttime dimmed.
+
+use clap::ValueEnum;
+use openwx::SummaryStyle;
+use std::io::IsTerminal;
+
+/// 256 color palette indices from cold to hot, blues through cyan, green, and yellow to red
+const TEMPERATURE_COLORS: [u8; 16] = [
+    21, 27, 33, 39, 45, 51, 50, 48, 46, 118, 190, 226, 220, 214, 208, 196,
+];
+
+/// Temperatures at or below this many degrees Celsius get the coldest color
+const COLDEST_CELSIUS: f32 = -10.0;
+
+/// Temperatures at or above this many degrees Celsius get the hottest color
+const HOTTEST_CELSIUS: f32 = 40.0;
+
+/// When to color output
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Whether output is colored, `auto` colors a terminal unless `NO_COLOR` is set or the terminal is dumb
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && std::env::var_os("TERM").is_none_or(|term| term != "dumb")
+                    && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+/// Colors summaries with ANSI escape codes. Each part only resets what it set, so parts nest inside a dimmed line.
+pub struct AnsiSummary;
+
+impl SummaryStyle for AnsiSummary {
+    fn temperature(&self, text: String, celsius: f32) -> String {
+        format!("\x1b[38;5;{}m{text}\x1b[39m", temperature_color(celsius))
+    }
+
+    fn severe(&self, text: String) -> String {
+        // Yellow reverse video, bold would reset the dimming of a nighttime line when it's turned off
+        format!("\x1b[7;33m{text}\x1b[27;39m")
+    }
+
+    fn night(&self, text: String) -> String {
+        format!("\x1b[2m{text}\x1b[22m")
+    }
+}
+
+/// The palette color of a temperature
+fn temperature_color(celsius: f32) -> u8 {
+    let fraction = (celsius - COLDEST_CELSIUS) / (HOTTEST_CELSIUS - COLDEST_CELSIUS);
+    let index = (fraction * (TEMPERATURE_COLORS.len() - 1) as f32).round();
+
+    TEMPERATURE_COLORS[index.clamp(0.0, (TEMPERATURE_COLORS.len() - 1) as f32) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temperature_gradient() {
+        assert_eq!(temperature_color(-40.0), 21);
+        assert_eq!(temperature_color(COLDEST_CELSIUS), 21);
+        assert_eq!(temperature_color(20.0), 118);
+        assert_eq!(temperature_color(HOTTEST_CELSIUS), 196);
+        assert_eq!(temperature_color(f32::NAN), 21);
+
+        assert_eq!(
+            AnsiSummary.temperature("68°F".into(), 20.0),
+            "\x1b[38;5;118m68°F\x1b[39m"
+        );
+    }
+
+    #[test]
+    fn explicit_choices() {
+        assert!(ColorChoice::Always.enabled());
+        assert!(!ColorChoice::Never.enabled());
+    }
+}
@@ -0,0 +1,388 @@
+//! The CLI's configuration file, `~/.config/openwx/config.toml`, holding the API key, default units and language,
+//! and named locations. Only the subset of TOML the file needs is understood: `[table]` headers, bare and dotted
+//! keys, and double-quoted string, number, and boolean values, one `key = value` pair per line. Anything else, like
+//! single-quoted or multi-line strings, arrays, inline tables, or dates, is rejected with an error naming it.
+
+use anyhow::{Context, anyhow, bail};
+use openwx::{GeodeticCoords, Language, NamedLocation, WeatherUnits};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Written by `config init`, every setting is optional
+const TEMPLATE: &str = r#"# openwx configuration, command line flags and environment variables take precedence over these settings
+
+# OpenWeather API key, used when neither --api-key nor OPENWEATHER_API_KEY is set
+{api_key}
+
+# Units to report in: standard, metric, or imperial
+units = "imperial"
+
+# Language to localize weather descriptions into, as an OpenWeather language code
+# lang = "en"
+
+# Named locations, query one by name, e.g. `openwx current home`
+[locations.home]
+lat = 33.545
+lon = -117.771
+
+# [locations.work]
+# lat = 33.684
+# lon = -117.826
+"#;
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub api_key: Option<String>,
+    pub units: Option<WeatherUnits>,
+    lang: Option<String>,
+    #[serde(default)]
+    locations: BTreeMap<String, ConfigLocation>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct ConfigLocation {
+    lat: f32,
+    lon: f32,
+}
+
+impl Config {
+    /// Loads the config file at `path`, or the default path if none is given. A missing default config file is
+    /// treated as empty, a missing explicit one is an error.
+    pub fn load(path: Option<&Path>) -> anyhow::Result<Config> {
+        let (path, explicit) = match path {
+            Some(path) => (path.to_path_buf(), true),
+            None => match default_path() {
+                Some(path) => (path, false),
+                None => return Ok(Config::default()),
+            },
+        };
+
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !explicit => {
+                return Ok(Config::default());
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("failed to read `{}`", path.display()));
+            }
+        };
+
+        Config::parse(&text).with_context(|| format!("invalid config file `{}`", path.display()))
+    }
+
+    fn parse(text: &str) -> anyhow::Result<Config> {
+        Ok(serde_json::from_value(parse_toml(text)?)?)
+    }
+
+    /// The configured language, checked against the codes OpenWeather understands
+    pub fn lang(&self) -> anyhow::Result<Option<Language>> {
+        self.lang
+            .as_deref()
+            .map(|code| {
+                code.parse()
+                    .map_err(|_| anyhow!("`{code}` is not an OpenWeather language code"))
+            })
+            .transpose()
+    }
+
+    /// Looks up a named location
+    pub fn location(&self, name: &str) -> anyhow::Result<NamedLocation> {
+        let location = self.locations.get(name).ok_or_else(|| {
+            if self.locations.is_empty() {
+                anyhow!("no location named `{name}`, add one to the config file with `openwx config init`")
+            } else {
+                let names = self.locations.keys().cloned().collect::<Vec<_>>();
+                anyhow!("no location named `{name}`, configured locations are: {}", names.join(", "))
+            }
+        })?;
+
+        let coords = GeodeticCoords::new_checked(location.lat, location.lon)
+            .with_context(|| format!("invalid coordinates for location `{name}`"))?;
+
+        Ok(NamedLocation::new(name, coords))
+    }
+}
+
+/// `$XDG_CONFIG_HOME/openwx/config.toml`, falling back to `~/.config/openwx/config.toml`
+pub fn default_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+
+    Some(config_home.join("openwx").join("config.toml"))
+}
+
+/// Writes a starter config file to `path`, including the API key if one is given. Refuses to replace an existing file
+/// unless `force` is set.
+pub fn init(path: &Path, api_key: Option<&str>, force: bool) -> anyhow::Result<()> {
+    if path.exists() && !force {
+        bail!(
+            "`{}` already exists, pass --force to replace it",
+            path.display()
+        );
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create `{}`", dir.display()))?;
+    }
+
+    let api_key = match api_key {
+        Some(key) => format!("api_key = {}", Value::from(key)),
+        None => "# api_key = \"...\"".to_string(),
+    };
+    std::fs::write(path, TEMPLATE.replace("{api_key}", &api_key))
+        .with_context(|| format!("failed to write `{}`", path.display()))
+}
+
+/// Parses the supported subset of TOML into nested JSON objects, so the result can be deserialized with serde
+fn parse_toml(text: &str) -> anyhow::Result<Value> {
+    let mut root = Map::new();
+    let mut table: Vec<String> = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_error = |message: String| anyhow!("line {}: {message}", i + 1);
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if line.starts_with("[[") {
+            return Err(line_error(unsupported("arrays of tables")));
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let header = header
+                .strip_suffix(']')
+                .ok_or_else(|| line_error("unterminated table header".into()))?;
+            table = parse_key(header).map_err(line_error)?;
+            descend(&mut root, &table).map_err(line_error)?;
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| line_error(format!("expected `key = value`, found `{line}`")))?;
+        let mut path = table.clone();
+        path.extend(parse_key(key).map_err(line_error)?);
+        let value = parse_value(value.trim()).map_err(line_error)?;
+
+        let (key, parents) = path.split_last().expect("keys are never empty");
+        let parent = descend(&mut root, parents).map_err(line_error)?;
+        if parent.insert(key.clone(), value).is_some() {
+            return Err(line_error(format!("`{}` is set twice", path.join("."))));
+        }
+    }
+
+    Ok(Value::Object(root))
+}
+
+/// Drops a trailing `#` comment, ignoring any `#` inside a string
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+
+    line
+}
+
+/// Splits a bare, possibly dotted key into its parts
+fn parse_key(key: &str) -> Result<Vec<String>, String> {
+    key.split('.')
+        .map(|part| {
+            let part = part.trim();
+            if part.starts_with(['"', '\'']) {
+                return Err(unsupported("quoted keys"));
+            }
+            if part.is_empty()
+                || !part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+            {
+                return Err(format!("invalid key `{}`", key.trim()));
+            }
+
+            Ok(part.to_string())
+        })
+        .collect()
+}
+
+fn parse_value(value: &str) -> Result<Value, String> {
+    match value {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        _ if value.starts_with("\"\"\"") || value.starts_with("'''") => {
+            return Err(unsupported("multi-line strings"));
+        }
+        _ if value.starts_with('\'') => {
+            return Err(unsupported("single-quoted strings") + ", use double quotes");
+        }
+        _ if value.starts_with('[') => return Err(unsupported("arrays")),
+        _ if value.starts_with('{') => {
+            return Err(unsupported("inline tables") + ", use a `[table]` header");
+        }
+        _ => {}
+    }
+
+    // TOML basic strings use the same escapes as JSON
+    if value.starts_with('"') {
+        return serde_json::from_str(value).map_err(|_| format!("invalid string {value}"));
+    }
+
+    let number = value.replace('_', "");
+    if let Ok(n) = number.parse::<i64>() {
+        return Ok(Value::from(n));
+    }
+    if let Some(n) = number
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+    {
+        return Ok(Value::Number(n));
+    }
+    if value.starts_with(|c: char| c.is_ascii_digit()) && value.contains(['-', ':']) {
+        return Err(unsupported("dates and times"));
+    }
+
+    Err(format!("unsupported value `{value}`"))
+}
+
+/// An error for TOML outside the subset the config file understands
+fn unsupported(what: &str) -> String {
+    format!("{what} aren't supported in the config file")
+}
+
+/// The table at `path`, creating any missing tables along the way
+fn descend<'a>(
+    root: &'a mut Map<String, Value>,
+    path: &[String],
+) -> Result<&'a mut Map<String, Value>, String> {
+    let mut table = root;
+    for (depth, key) in path.iter().enumerate() {
+        table = table
+            .entry(key.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| format!("`{}` is not a table", path[..=depth].join(".")))?;
+    }
+
+    Ok(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_config() {
+        let config = Config::parse(
+            r#"
+            api_key = "abc#123" # the key
+            units = "metric"
+            lang = "pt_br"
+
+            [locations.home]
+            lat = 33.545
+            lon = -117.771
+
+            [locations.cabin]
+            lat = 39
+            lon = -120
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.api_key.as_deref(), Some("abc#123"));
+        assert_eq!(config.units, Some(WeatherUnits::Metric));
+        assert_eq!(config.lang().unwrap(), Some(Language::BrazilianPortuguese));
+
+        let home = config.location("home").unwrap();
+        assert_eq!(home.name, "home");
+        assert_eq!(home.coords.lon, -117.771);
+        assert_eq!(config.location("cabin").unwrap().coords.lat, 39.0);
+
+        assert_eq!(
+            config.location("work").unwrap_err().to_string(),
+            "no location named `work`, configured locations are: cabin, home"
+        );
+    }
+
+    #[test]
+    fn reject_invalid_config() {
+        let err = Config::parse("units = metric").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: unsupported value `metric`");
+
+        let err = Config::parse("lang = \"en\"\nlang = \"de\"").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: `lang` is set twice");
+
+        assert!(Config::parse("[locations").is_err());
+        assert!(Config::parse("unit = \"metric\"").is_err());
+        assert!(Config::parse("lang = \"xx\"").unwrap().lang().is_err());
+    }
+
+    #[test]
+    fn reject_unsupported_toml() {
+        let error = |text| Config::parse(text).unwrap_err().to_string();
+
+        assert_eq!(
+            error("units = 'metric'"),
+            "line 1: single-quoted strings aren't supported in the config file, use double quotes"
+        );
+        assert_eq!(
+            error("api_key = \"\"\"\nabc\"\"\""),
+            "line 1: multi-line strings aren't supported in the config file"
+        );
+        assert_eq!(
+            error("lang = [\"en\"]"),
+            "line 1: arrays aren't supported in the config file"
+        );
+        assert_eq!(
+            error("[locations]\nhome = { lat = 1, lon = 2 }"),
+            "line 2: inline tables aren't supported in the config file, use a `[table]` header"
+        );
+        assert_eq!(
+            error("[[locations]]"),
+            "line 1: arrays of tables aren't supported in the config file"
+        );
+        assert_eq!(
+            error("\"api_key\" = \"abc\""),
+            "line 1: quoted keys aren't supported in the config file"
+        );
+        assert_eq!(
+            error("since = 1979-05-27"),
+            "line 1: dates and times aren't supported in the config file"
+        );
+    }
+
+    #[test]
+    fn template_parses() {
+        let path =
+            std::env::temp_dir().join(format!("openwx-config-{}/config.toml", std::process::id()));
+
+        init(&path, Some("key\"with quote"), false).unwrap();
+        assert!(init(&path, None, false).is_err());
+
+        let config = Config::load(Some(&path)).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("key\"with quote"));
+        assert_eq!(config.units, Some(WeatherUnits::Imperial));
+        assert_eq!(config.lang().unwrap(), None);
+        assert!(config.location("home").is_ok());
+
+        init(&path, None, true).unwrap();
+        assert_eq!(Config::load(Some(&path)).unwrap().api_key, None);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
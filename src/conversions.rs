@@ -0,0 +1,133 @@
+//! Unit conversions for values reported by OpenWeather
+
+use crate::WeatherUnits;
+
+/// Offset between the Kelvin and Celsius scales
+const KELVIN_OFFSET: f32 = 273.15;
+
+/// Converts a temperature in Kelvin to degrees Celsius
+pub fn kelvin_to_celsius(k: f32) -> f32 {
+    k - KELVIN_OFFSET
+}
+
+/// Converts a temperature in Kelvin to degrees Fahrenheit
+pub fn kelvin_to_fahrenheit(k: f32) -> f32 {
+    celsius_to_fahrenheit(kelvin_to_celsius(k))
+}
+
+/// Converts a temperature in degrees Celsius to Kelvin
+pub fn celsius_to_kelvin(c: f32) -> f32 {
+    c + KELVIN_OFFSET
+}
+
+/// Converts a temperature in degrees Fahrenheit to Kelvin
+pub fn fahrenheit_to_kelvin(f: f32) -> f32 {
+    celsius_to_kelvin(fahrenheit_to_celsius(f))
+}
+
+/// Converts a temperature in degrees Celsius to degrees Fahrenheit
+pub fn celsius_to_fahrenheit(c: f32) -> f32 {
+    c * 9.0 / 5.0 + 32.0
+}
+
+/// Converts a temperature in degrees Fahrenheit to degrees Celsius
+pub fn fahrenheit_to_celsius(f: f32) -> f32 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+/// Converts a temperature reported in `units` to degrees Celsius
+pub fn to_celsius(temp: f32, units: WeatherUnits) -> f32 {
+    match units {
+        WeatherUnits::Standard => kelvin_to_celsius(temp),
+        WeatherUnits::Metric => temp,
+        WeatherUnits::Imperial => fahrenheit_to_celsius(temp),
+    }
+}
+
+/// Meters per second in one knot, a knot is exactly 1852 meters per hour
+const MPS_PER_KNOT: f32 = 1852.0 / 3600.0;
+
+/// Meters per second in one mile per hour
+const MPS_PER_MPH: f32 = 0.44704;
+
+/// Converts a speed in meters per second to knots
+pub fn mps_to_knots(mps: f32) -> f32 {
+    mps / MPS_PER_KNOT
+}
+
+/// Converts a speed in miles per hour to knots
+pub fn mph_to_knots(mph: f32) -> f32 {
+    mps_to_knots(mph_to_mps(mph))
+}
+
+/// Converts a speed in miles per hour to meters per second
+pub fn mph_to_mps(mph: f32) -> f32 {
+    mph * MPS_PER_MPH
+}
+
+/// Converts a speed in meters per second to miles per hour
+pub fn mps_to_mph(mps: f32) -> f32 {
+    mps / MPS_PER_MPH
+}
+
+/// Millimeters in one inch
+const MM_PER_INCH: f32 = 25.4;
+
+/// Converts a length in millimeters to inches
+pub fn mm_to_inches(mm: f32) -> f32 {
+    mm / MM_PER_INCH
+}
+
+/// Meters in one foot
+const METERS_PER_FOOT: f32 = 0.3048;
+
+/// Converts a length in meters to feet
+pub fn meters_to_feet(m: f32) -> f32 {
+    m / METERS_PER_FOOT
+}
+
+/// Converts a length in feet to meters
+pub fn feet_to_meters(ft: f32) -> f32 {
+    ft * METERS_PER_FOOT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn kelvin_conversions() {
+        assert_close(kelvin_to_celsius(273.15), 0.0);
+        assert_close(kelvin_to_celsius(300.0), 26.85);
+        assert_close(kelvin_to_fahrenheit(273.15), 32.0);
+        assert_close(kelvin_to_fahrenheit(300.0), 80.33);
+
+        assert_close(celsius_to_kelvin(0.0), 273.15);
+        assert_close(fahrenheit_to_kelvin(80.33), 300.0);
+    }
+
+    #[test]
+    fn celsius_fahrenheit_round_trip() {
+        assert_close(celsius_to_fahrenheit(100.0), 212.0);
+        assert_close(fahrenheit_to_celsius(-40.0), -40.0);
+        assert_close(fahrenheit_to_celsius(celsius_to_fahrenheit(21.5)), 21.5);
+    }
+
+    #[test]
+    fn unit_aware_celsius() {
+        assert_close(to_celsius(273.15, WeatherUnits::Standard), 0.0);
+        assert_close(to_celsius(12.5, WeatherUnits::Metric), 12.5);
+        assert_close(to_celsius(212.0, WeatherUnits::Imperial), 100.0);
+    }
+
+    #[test]
+    fn speed_conversions() {
+        assert!((mps_to_knots(10.0) - 19.44).abs() < 0.01);
+        assert!((mph_to_knots(10.0) - 8.69).abs() < 0.01);
+        assert_close(mph_to_mps(mps_to_mph(12.3)), 12.3);
+    }
+}
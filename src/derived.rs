@@ -0,0 +1,470 @@
+//! Meteorological quantities derived from the fields OpenWeather reports
+
+use crate::{OWCurrentWeatherResponse, OWMain, OWWind, Temperature, WeatherUnits, WindSpeed};
+use strum::Display;
+
+/// Magnus approximation coefficients (Alduchov and Eskridge, 1996): saturation vapor pressure in hPa at 0°C, and the
+/// dimensionless and °C constants of the exponent.
+const MAGNUS_HPA: f32 = 6.1094;
+const MAGNUS_B: f32 = 17.625;
+const MAGNUS_C: f32 = 243.04;
+
+/// Saturation vapor pressure over water in hPa at a temperature in degrees Celsius, using the Magnus approximation.
+pub fn saturation_vapor_pressure_hpa(temp_c: f32) -> f32 {
+    MAGNUS_HPA * ((MAGNUS_B * temp_c) / (temp_c + MAGNUS_C)).exp()
+}
+
+/// Highest air temperature in °F the NWS wind chill formula applies to
+const WIND_CHILL_MAX_TEMP_F: f32 = 50.0;
+
+/// Lowest wind speed in mph the NWS wind chill formula applies to
+const WIND_CHILL_MIN_SPEED_MPH: f32 = 3.0;
+
+/// Wind chill, how cold it feels on exposed skin in the wind, using the formula the US National Weather Service and
+/// Environment Canada adopted in 2001. Returned in the same units as `temp`.
+///
+/// The formula only applies to air at or below 50°F (10°C) with wind of at least 3 mph (1.3 m/s), `None` is returned
+/// outside that range.
+pub fn wind_chill(temp: Temperature, wind_speed: WindSpeed) -> Option<Temperature> {
+    let t = temp.to_fahrenheit();
+    let v = wind_speed.to_mph();
+
+    if t > WIND_CHILL_MAX_TEMP_F || v < WIND_CHILL_MIN_SPEED_MPH {
+        return None;
+    }
+
+    let v_exp = v.powf(0.16);
+    let wind_chill_f = 35.74 + 0.6215 * t - 35.75 * v_exp + 0.4275 * t * v_exp;
+
+    Some(Temperature::new(wind_chill_f, WeatherUnits::Imperial).in_units(temp.units()))
+}
+
+/// Air temperatures in °C Stull's wet-bulb fit is valid for
+const STULL_TEMP_C: std::ops::RangeInclusive<f32> = -20.0..=50.0;
+
+/// Relative humidities in % Stull's wet-bulb fit is valid for
+const STULL_HUMIDITY: std::ops::RangeInclusive<f32> = 5.0..=99.0;
+
+/// Global solar radiation in kW/m² assumed under clear skies during daylight, roughly the sun high in the sky
+const CLEAR_SKY_SOLAR_KW_M2: f32 = 1.0;
+
+/// Lower bounds of the heat stress categories above [`HeatStressCategory::Low`], WBGT in °F
+const HEAT_STRESS_LIMITS_F: [f32; 5] = [78.0, 82.0, 85.0, 88.0, 90.0];
+
+/// Heat stress flag conditions by Wet Bulb Globe Temperature, as used by the US military (TB MED 507) and many
+/// athletic and occupational safety programs. Work/rest cycles and water intake guidance is keyed off these.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HeatStressCategory {
+    /// Below 78°F (25.6°C)
+    Low,
+    /// 78°F to 81.9°F (25.6°C to 27.7°C), category 1
+    White,
+    /// 82°F to 84.9°F (27.8°C to 29.4°C), category 2
+    Green,
+    /// 85°F to 87.9°F (29.4°C to 31.0°C), category 3
+    Yellow,
+    /// 88°F to 89.9°F (31.1°C to 32.1°C), category 4
+    Red,
+    /// 90°F (32.2°C) and above, category 5
+    Black,
+}
+
+impl HeatStressCategory {
+    /// Categorizes a Wet Bulb Globe Temperature
+    pub fn from_wbgt(wbgt: Temperature) -> Self {
+        let wbgt_f = wbgt.to_fahrenheit();
+
+        match HEAT_STRESS_LIMITS_F
+            .iter()
+            .filter(|limit| wbgt_f >= **limit)
+            .count()
+        {
+            0 => HeatStressCategory::Low,
+            1 => HeatStressCategory::White,
+            2 => HeatStressCategory::Green,
+            3 => HeatStressCategory::Yellow,
+            4 => HeatStressCategory::Red,
+            _ => HeatStressCategory::Black,
+        }
+    }
+}
+
+/// Specific gas constant of water vapor, J/(kg·K)
+const WATER_VAPOR_GAS_CONSTANT: f32 = 461.5;
+
+/// Specific gas constant of dry air, J/(kg·K)
+const DRY_AIR_GAS_CONSTANT: f32 = 287.058;
+
+impl OWMain {
+    /// Relative humidity in % the current air would have if it were heated or cooled to `target_temp_c`, assuming
+    /// the amount of water in the air stays constant (no condensation or added moisture). Clamped to [0, 100].
+    pub fn relative_humidity_at(&self, target_temp_c: f32) -> f32 {
+        let temp_c = self.temp.to_celsius();
+
+        let vapor_pressure = self.humidity.value() / 100.0 * saturation_vapor_pressure_hpa(temp_c);
+        let target_rh = 100.0 * vapor_pressure / saturation_vapor_pressure_hpa(target_temp_c);
+
+        target_rh.clamp(0.0, 100.0)
+    }
+
+    /// Absolute humidity, the mass of water vapor per volume of air in g/m³, from the temperature and relative
+    /// humidity using the ideal gas law for water vapor.
+    pub fn absolute_humidity(&self) -> f32 {
+        let vapor_pressure_pa = self.humidity.value() / 100.0
+            * saturation_vapor_pressure_hpa(self.temp.to_celsius())
+            * 100.0;
+
+        // kg/m³ to g/m³
+        1000.0 * vapor_pressure_pa / (WATER_VAPOR_GAS_CONSTANT * self.temp.to_kelvin())
+    }
+
+    /// Density of the air at ground level in kg/m³, treating it as a mix of dry air and water vapor at the ground
+    /// level pressure. Humid air is less dense than dry air at the same temperature and pressure.
+    ///
+    /// Returns `None` if the ground level pressure wasn't reported, as is the case for XML responses.
+    pub fn air_density(&self) -> Option<f32> {
+        let pressure_pa = self.grnd_level?.hpa() * 100.0;
+
+        let temp_k = self.temp.to_kelvin();
+        let vapor_pa = self.humidity.value() / 100.0
+            * saturation_vapor_pressure_hpa(self.temp.to_celsius())
+            * 100.0;
+        let dry_pa = pressure_pa - vapor_pa;
+
+        Some(
+            dry_pa / (DRY_AIR_GAS_CONSTANT * temp_k)
+                + vapor_pa / (WATER_VAPOR_GAS_CONSTANT * temp_k),
+        )
+    }
+
+    /// Dew point, the temperature the air would need to be cooled to for it to saturate, by inverting the Magnus
+    /// approximation. Returned in the same units as `temp`.
+    ///
+    /// Perfectly dry air never saturates, so `None` is returned at 0% humidity.
+    pub fn dew_point(&self) -> Option<Temperature> {
+        let rh = self.humidity.value();
+        if rh <= 0.0 {
+            return None;
+        }
+
+        let temp_c = self.temp.to_celsius();
+
+        let gamma = (rh / 100.0).ln() + MAGNUS_B * temp_c / (MAGNUS_C + temp_c);
+        let dew_point_c = MAGNUS_C * gamma / (MAGNUS_B - gamma);
+
+        Some(Temperature::new(dew_point_c, WeatherUnits::Metric).in_units(self.temp.units()))
+    }
+
+    /// Heat index, how hot it feels once humidity is accounted for, following the US National Weather Service's
+    /// algorithm. Returned in the same units as `temp`.
+    ///
+    /// The Rothfusz regression is only fit to hot conditions, so below a heat index of about 80°F the NWS's simpler
+    /// Steadman formula is used instead, which stays close to the air temperature. The regression's adjustments for
+    /// very dry and very humid air are applied in the ranges the NWS specifies.
+    pub fn heat_index(&self) -> Temperature {
+        let t = self.temp.to_fahrenheit();
+        let rh = self.humidity.value();
+
+        let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+
+        let heat_index_f = if (simple + t) / 2.0 < 80.0 {
+            simple
+        } else {
+            let mut hi = -42.379 + 2.049_015_2 * t + 10.143_331 * rh
+                - 0.224_755_4 * t * rh
+                - 0.006_837_83 * t * t
+                - 0.054_817_17 * rh * rh
+                + 0.001_228_74 * t * t * rh
+                + 0.000_852_82 * t * rh * rh
+                - 0.000_001_99 * t * t * rh * rh;
+
+            if rh < 13.0 && (80.0..=112.0).contains(&t) {
+                hi -= (13.0 - rh) / 4.0 * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+            } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+                hi += (rh - 85.0) / 10.0 * ((87.0 - t) / 5.0);
+            }
+
+            hi
+        };
+
+        Temperature::new(heat_index_f, WeatherUnits::Imperial).in_units(self.temp.units())
+    }
+
+    /// Humidex, the Canadian counterpart to [`OWMain::heat_index`], computed from the dew point with Environment
+    /// Canada's formula. Humidex is defined on the Celsius scale, it's returned converted to the same units as `temp`
+    /// for consistency with the other metrics but is usually quoted as a bare number like "humidex 40". Perfectly dry
+    /// air has no dew point but also no water vapor, so its vapor pressure is taken as zero.
+    pub fn humidex(&self) -> Temperature {
+        let vapor_pressure_hpa = self.dew_point().map_or(0.0, |dew_point| {
+            6.11 * (5417.753 * (1.0 / 273.16 - 1.0 / dew_point.to_kelvin())).exp()
+        });
+        let humidex_c = self.temp.to_celsius() + 0.5555 * (vapor_pressure_hpa - 10.0);
+
+        Temperature::new(humidex_c, WeatherUnits::Metric).in_units(self.temp.units())
+    }
+
+    /// Wet-bulb temperature, the lowest temperature evaporative cooling can bring air to, using Stull's (2011)
+    /// empirical fit. Returned in the same units as `temp`.
+    ///
+    /// The fit is only valid for air between -20°C and 50°C with 5% to 99% relative humidity, `None` is returned
+    /// outside that range. It was made at sea level pressure (1013.25 hPa) and isn't adjusted for `grnd_level`, so
+    /// it somewhat overestimates the wet-bulb temperature at altitude where evaporation is more effective.
+    pub fn wet_bulb(&self) -> Option<Temperature> {
+        let t = self.temp.to_celsius();
+        let rh = self.humidity.value();
+
+        if !STULL_TEMP_C.contains(&t) || !STULL_HUMIDITY.contains(&rh) {
+            return None;
+        }
+
+        let wet_bulb_c = t * (0.151_977 * (rh + 8.313_659).sqrt()).atan() + (t + rh).atan()
+            - (rh - 1.676_331).atan()
+            + 0.003_918_38 * rh.powf(1.5) * (0.023_101 * rh).atan()
+            - 4.686_035;
+
+        Some(Temperature::new(wet_bulb_c, WeatherUnits::Metric).in_units(self.temp.units()))
+    }
+
+    /// Wind chill given the sustained `wind` reported alongside these conditions, see [`wind_chill`].
+    pub fn wind_chill(&self, wind: &OWWind) -> Option<Temperature> {
+        wind_chill(self.temp, wind.speed)
+    }
+}
+
+impl OWCurrentWeatherResponse {
+    /// Estimated outdoor Wet Bulb Globe Temperature, the standard heat stress index for work and exercise in the
+    /// sun. Returned in the same units as `main.temp`.
+    ///
+    /// Real WBGT needs a black globe thermometer, this estimate uses Tonouchi's (2006) regression on air temperature,
+    /// humidity, wind speed, and global solar radiation. OpenWeather doesn't report solar radiation, so it's assumed
+    /// to be that of a high sun, reduced by the cloud cover (Kasten and Czeplak, 1980), during daylight and zero at
+    /// night. That's conservative in the morning and evening when the sun is low. Missing wind is taken as calm.
+    pub fn wbgt(&self) -> Temperature {
+        let t = self.main.temp.to_celsius();
+        let rh = self.main.humidity.value();
+        let wind_mps = self.wind().map_or(0.0, |wind| wind.speed.to_mps());
+
+        let solar = if self.is_daytime() {
+            let cloud_fraction = self.clouds.all.value() / 100.0;
+            CLEAR_SKY_SOLAR_KW_M2 * (1.0 - 0.75 * cloud_fraction.powf(3.4))
+        } else {
+            0.0
+        };
+
+        let wbgt_c = 0.735 * t + 0.0374 * rh + 0.00292 * t * rh + 7.619 * solar
+            - 4.557 * solar * solar
+            - 0.0572 * wind_mps
+            - 4.064;
+
+        Temperature::new(wbgt_c, WeatherUnits::Metric).in_units(self.main.temp.units())
+    }
+
+    /// The [`HeatStressCategory`] of the estimated [`OWCurrentWeatherResponse::wbgt`]
+    pub fn heat_stress_category(&self) -> HeatStressCategory {
+        HeatStressCategory::from_wbgt(self.wbgt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+    use crate::{Percentage, Pressure};
+
+    #[test]
+    fn heating_saturated_air() {
+        let mut main = zocca().main;
+        main.temp = Temperature::new(10.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(100.0).unwrap();
+
+        let rh = main.relative_humidity_at(22.0);
+        assert!((rh - 46.0).abs() < 1.0, "{rh}");
+
+        // Cooling saturated air would condense, so we clamp
+        assert_eq!(main.relative_humidity_at(5.0), 100.0);
+    }
+
+    #[test]
+    fn same_temperature() {
+        let main = zocca().main;
+        let rh = main.relative_humidity_at(main.temp.to_celsius());
+        assert!((rh - main.humidity.value()).abs() < 1e-3, "{rh}");
+    }
+
+    #[test]
+    fn absolute_humidity() {
+        let mut main = zocca().main;
+        main.temp = Temperature::new(20.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(50.0).unwrap();
+
+        let absolute = main.absolute_humidity();
+        assert!((absolute - 8.64).abs() < 0.05, "{absolute}");
+
+        // The same air reported in other units holds the same water
+        main.temp = Temperature::new(68.0, WeatherUnits::Imperial);
+        assert!((main.absolute_humidity() - absolute).abs() < 1e-3);
+    }
+
+    #[test]
+    fn air_density() {
+        // Standard atmosphere at sea level and at 5000 ft
+        let mut main = zocca().main;
+        main.grnd_level = Some(Pressure::from_hpa(1013.25));
+        main.temp = Temperature::new(15.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(0.0).unwrap();
+        let dry = main.air_density().unwrap();
+        assert!((dry - 1.225).abs() < 1e-3, "{dry}");
+
+        main.grnd_level = Some(Pressure::from_hpa(843.07));
+        main.temp = Temperature::new(5.1, WeatherUnits::Metric);
+        let high = main.air_density().unwrap();
+        assert!((high - 1.0556).abs() < 1e-3, "{high}");
+
+        main.grnd_level = Some(Pressure::from_hpa(1013.25));
+        main.temp = Temperature::new(15.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(100.0).unwrap();
+        assert!(main.air_density().unwrap() < dry);
+
+        main.grnd_level = None;
+        assert_eq!(main.air_density(), None);
+    }
+
+    #[test]
+    fn dew_point() {
+        let mut main = zocca().main;
+        main.temp = Temperature::new(20.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(50.0).unwrap();
+
+        let dew_point = main.dew_point().unwrap();
+        assert_eq!(dew_point.units(), WeatherUnits::Metric);
+        assert!((dew_point.value() - 9.3).abs() < 0.1, "{dew_point}");
+
+        // Saturated air is already at its dew point, in whatever units it was reported in
+        main.temp = Temperature::new(50.0, WeatherUnits::Imperial);
+        main.humidity = Percentage::new_checked(100.0).unwrap();
+        assert!((main.dew_point().unwrap().value() - 50.0).abs() < 1e-3);
+
+        // Perfectly dry air never saturates
+        main.humidity = Percentage::new_checked(0.0).unwrap();
+        assert_eq!(main.dew_point(), None);
+    }
+
+    #[test]
+    fn heat_index() {
+        let mut main = zocca().main;
+        main.temp = Temperature::new(90.0, WeatherUnits::Imperial);
+        main.humidity = Percentage::new_checked(70.0).unwrap();
+
+        let heat_index = main.heat_index();
+        assert_eq!(heat_index.units(), WeatherUnits::Imperial);
+        assert!((heat_index.value() - 105.9).abs() < 0.5, "{heat_index}");
+
+        // Mild conditions use the simple formula, which stays near the air temperature
+        main.temp = Temperature::new(70.0, WeatherUnits::Imperial);
+        main.humidity = Percentage::new_checked(50.0).unwrap();
+        assert!((main.heat_index().value() - 69.05).abs() < 0.01);
+
+        main.temp = Temperature::new(35.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(60.0).unwrap();
+        let heat_index = main.heat_index();
+        assert_eq!(heat_index.units(), WeatherUnits::Metric);
+        assert!(heat_index.value() > 45.0, "{heat_index}");
+    }
+
+    #[test]
+    fn humidex() {
+        let mut main = zocca().main;
+        main.temp = Temperature::new(30.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(100.0).unwrap();
+
+        let humidex = main.humidex();
+        assert_eq!(humidex.units(), WeatherUnits::Metric);
+        assert!((humidex.value() - 48.6).abs() < 0.3, "{humidex}");
+
+        // Dry air feels cooler than it is
+        main.humidity = Percentage::new_checked(10.0).unwrap();
+        assert!(main.humidex().value() < 30.0);
+
+        // Perfectly dry air has no dew point, but still has a humidex
+        main.humidity = Percentage::new_checked(0.0).unwrap();
+        let humidex = main.humidex();
+        assert!(humidex.value().is_finite(), "{humidex}");
+        assert!((humidex.value() - 24.445).abs() < 1e-3, "{humidex}");
+    }
+
+    #[test]
+    fn wet_bulb() {
+        // The worked example from Stull's paper
+        let mut main = zocca().main;
+        main.temp = Temperature::new(20.0, WeatherUnits::Metric);
+        main.humidity = Percentage::new_checked(50.0).unwrap();
+
+        let wet_bulb = main.wet_bulb().unwrap();
+        assert_eq!(wet_bulb.units(), WeatherUnits::Metric);
+        assert!((wet_bulb.value() - 13.7).abs() < 0.1, "{wet_bulb}");
+
+        main.humidity = Percentage::new_checked(2.0).unwrap();
+        assert_eq!(main.wet_bulb(), None);
+    }
+
+    #[test]
+    fn wbgt_estimate() {
+        let mut response = zocca().with_units(WeatherUnits::Metric);
+        response.main.temp = Temperature::new(30.0, WeatherUnits::Metric);
+        response.main.humidity = Percentage::new_checked(50.0).unwrap();
+        response.wind.as_mut().unwrap().speed = WindSpeed::new(2.0, WeatherUnits::Metric);
+        response.clouds.all = Percentage::new_checked(0.0).unwrap();
+        response.dt = response.sys.sunrise.timestamp() as u64 + 4 * 3600;
+
+        let sunny = response.wbgt();
+        assert_eq!(sunny.units(), WeatherUnits::Metric);
+        assert!((sunny.value() - 27.2).abs() < 0.1, "{sunny}");
+        assert_eq!(response.heat_stress_category(), HeatStressCategory::White);
+
+        // Overcast, and then nighttime, take the sun out of it
+        response.clouds.all = Percentage::new_checked(100.0).unwrap();
+        let overcast = response.wbgt();
+        assert!(overcast.value() < sunny.value());
+
+        response.dt = response.sys.sunset.timestamp() as u64 + 3600;
+        assert!(response.wbgt().value() < overcast.value());
+        assert_eq!(response.heat_stress_category(), HeatStressCategory::Low);
+    }
+
+    #[test]
+    fn heat_stress_categories() {
+        let category =
+            |f| HeatStressCategory::from_wbgt(Temperature::new(f, WeatherUnits::Imperial));
+
+        assert_eq!(category(70.0), HeatStressCategory::Low);
+        assert_eq!(category(78.0), HeatStressCategory::White);
+        assert_eq!(category(86.0), HeatStressCategory::Yellow);
+        assert_eq!(category(95.0), HeatStressCategory::Black);
+    }
+
+    #[test]
+    fn wind_chill_range() {
+        let freezing = Temperature::new(0.0, WeatherUnits::Imperial);
+        let chill = wind_chill(freezing, WindSpeed::new(15.0, WeatherUnits::Imperial)).unwrap();
+        assert!((chill.value() + 19.4).abs() < 0.1, "{chill}");
+
+        // Too warm, or too calm
+        let mild = Temperature::new(15.0, WeatherUnits::Metric);
+        assert_eq!(
+            wind_chill(mild, WindSpeed::new(10.0, WeatherUnits::Metric)),
+            None
+        );
+        assert_eq!(
+            wind_chill(freezing, WindSpeed::new(1.0, WeatherUnits::Imperial)),
+            None
+        );
+
+        let mut response = zocca();
+        response.main.temp = Temperature::new(-5.0, WeatherUnits::Metric);
+        response.wind.as_mut().unwrap().speed = WindSpeed::new(10.0, WeatherUnits::Metric);
+
+        let chill = response.main.wind_chill(response.wind().unwrap()).unwrap();
+        assert_eq!(chill.units(), WeatherUnits::Metric);
+        assert!((chill.value() + 13.65).abs() < 0.1, "{chill}");
+    }
+}
@@ -0,0 +1,358 @@
+//! Exporting responses to formats consumed by other tools
+
+use crate::{
+    GeodeticCoords, OWClouds, OWCurrentWeatherResponse, OWForecastResponse,
+    OWHourlyForecastResponse, OWMain, OWRain, OWSnow, OWWeather, OWWind,
+};
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+use std::fmt::Display;
+
+/// Columns of exported CSV rows. These are the columns of OpenWeather's History Bulk CSV archives followed by `pop`,
+/// so exports line up with archives and can be read back with [`crate::csv_records`]. Values are in the units the
+/// response was requested in, and `pop` is empty for observations.
+pub const EXPORT_CSV_COLUMNS: [&str; 29] = [
+    "dt",
+    "dt_iso",
+    "timezone",
+    "city_name",
+    "lat",
+    "lon",
+    "temp",
+    "visibility",
+    "dew_point",
+    "feels_like",
+    "temp_min",
+    "temp_max",
+    "pressure",
+    "sea_level",
+    "grnd_level",
+    "humidity",
+    "wind_speed",
+    "wind_deg",
+    "wind_gust",
+    "rain_1h",
+    "rain_3h",
+    "snow_1h",
+    "snow_3h",
+    "clouds_all",
+    "weather_id",
+    "weather_main",
+    "weather_description",
+    "weather_icon",
+    "pop",
+];
+
+/// The header row for exported CSV, see [`EXPORT_CSV_COLUMNS`]
+pub fn export_csv_header() -> String {
+    EXPORT_CSV_COLUMNS.join(",")
+}
+
+/// A GeoJSON Feature with a point geometry, properties are whatever the wrapped value serializes to
+#[derive(Serialize)]
+struct Feature<'a, P: Serialize> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    geometry: Point,
+    properties: &'a P,
+}
+
+impl<'a, P: Serialize> Feature<'a, P> {
+    fn new(coords: GeodeticCoords, properties: &'a P) -> Self {
+        Feature {
+            kind: "Feature",
+            geometry: Point {
+                kind: "Point",
+                // GeoJSON orders positions longitude first
+                coordinates: [coords.lon, coords.lat],
+            },
+            properties,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Point {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    coordinates: [f32; 2],
+}
+
+#[derive(Serialize)]
+struct FeatureCollection<'a, P: Serialize> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    features: Vec<Feature<'a, P>>,
+}
+
+/// Response types only contain string keyed maps, so serializing them to JSON can't fail
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("responses always serialize to JSON")
+}
+
+impl OWCurrentWeatherResponse {
+    /// Renders the response as a GeoJSON Feature, a point at `coord` with the response's fields, in OpenWeather's
+    /// wire format, as its properties.
+    pub fn to_geojson(&self) -> String {
+        to_json(&Feature::new(self.coord, self))
+    }
+}
+
+impl OWForecastResponse {
+    /// Renders the forecast as a GeoJSON FeatureCollection with a Feature per entry, each a point at the forecast
+    /// city with the entry's fields, in OpenWeather's wire format, as its properties.
+    pub fn to_geojson(&self) -> String {
+        let features = self
+            .list
+            .iter()
+            .map(|entry| Feature::new(self.city.coord, entry))
+            .collect();
+
+        to_json(&FeatureCollection {
+            kind: "FeatureCollection",
+            features,
+        })
+    }
+}
+
+/// An observation or forecast entry along with the location it's for, flattened into a CSV row
+struct CsvRecord<'a> {
+    dt: u64,
+    timezone: FixedOffset,
+    city_name: &'a str,
+    coord: GeodeticCoords,
+    main: &'a OWMain,
+    visibility: Option<f32>,
+    wind: Option<&'a OWWind>,
+    clouds: &'a OWClouds,
+    weather: Option<&'a OWWeather>,
+    rain: Option<&'a OWRain>,
+    snow: Option<&'a OWSnow>,
+    pop: Option<f32>,
+}
+
+impl CsvRecord<'_> {
+    fn to_row(&self) -> String {
+        let optional = |value: Option<f32>| value.map(|v| v.to_string()).unwrap_or_default();
+        let dt_iso = DateTime::from_timestamp(self.dt as i64, 0)
+            .map(|dt| dt.format("%Y-%m-%d %H:%M:%S +0000 UTC").to_string())
+            .unwrap_or_default();
+
+        let main = self.main;
+        let fields: [String; EXPORT_CSV_COLUMNS.len()] = [
+            self.dt.to_string(),
+            dt_iso,
+            self.timezone.local_minus_utc().to_string(),
+            self.city_name.to_string(),
+            self.coord.lat.to_string(),
+            self.coord.lon.to_string(),
+            main.temp.value().to_string(),
+            optional(self.visibility),
+            optional(main.dew_point().map(|dew_point| dew_point.value())),
+            main.feels_like.value().to_string(),
+            main.temp_min.value().to_string(),
+            main.temp_max.value().to_string(),
+            main.pressure.hpa().to_string(),
+            main.sea_level.hpa().to_string(),
+            optional(main.grnd_level.map(|pressure| pressure.hpa())),
+            main.humidity.value().to_string(),
+            optional(self.wind.map(|wind| wind.speed.value())),
+            optional(self.wind.map(|wind| wind.deg.value())),
+            optional(
+                self.wind
+                    .and_then(|wind| wind.gust)
+                    .map(|gust| gust.value()),
+            ),
+            optional(
+                self.rain
+                    .and_then(|rain| rain.one_hour)
+                    .map(|rate| rate.mm_per_hour()),
+            ),
+            optional(self.rain.and_then(|rain| rain.three_hours)),
+            optional(
+                self.snow
+                    .and_then(|snow| snow.one_hour)
+                    .map(|rate| rate.mm_per_hour()),
+            ),
+            optional(self.snow.and_then(|snow| snow.three_hours)),
+            self.clouds.all.value().to_string(),
+            csv_text(self.weather.map(|weather| weather.id.code())),
+            csv_text(self.weather.map(|weather| &weather.main)),
+            csv_text(self.weather.map(|weather| &weather.description)),
+            csv_text(self.weather.map(|weather| &weather.icon)),
+            optional(self.pop),
+        ];
+
+        fields.map(|field| csv_escape(&field)).join(",")
+    }
+}
+
+/// Renders an optional value as a CSV field, empty if there isn't one
+fn csv_text<T: Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Quotes a field if it contains a delimiter, quote, or line break, doubling any embedded quotes
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl OWCurrentWeatherResponse {
+    /// Flattens the observation into a CSV row with the columns in [`EXPORT_CSV_COLUMNS`], without a trailing line
+    /// break. Only the primary weather condition is included. Write [`export_csv_header`] once before the rows.
+    pub fn to_csv_row(&self) -> String {
+        CsvRecord {
+            dt: self.dt,
+            timezone: self.timezone,
+            city_name: &self.name,
+            coord: self.coord,
+            main: &self.main,
+            visibility: Some(self.visibility),
+            wind: self.wind(),
+            clouds: &self.clouds,
+            weather: self.primary_condition(),
+            rain: self.rain.as_ref(),
+            snow: self.snow.as_ref(),
+            pop: None,
+        }
+        .to_row()
+    }
+}
+
+/// A header row followed by a row per record
+fn csv_with_header<'a>(records: impl Iterator<Item = CsvRecord<'a>>) -> String {
+    let mut csv = export_csv_header();
+
+    for record in records {
+        csv.push('\n');
+        csv.push_str(&record.to_row());
+    }
+    csv.push('\n');
+
+    csv
+}
+
+impl OWForecastResponse {
+    /// Renders the forecast as CSV with a header row and a row per entry, see [`EXPORT_CSV_COLUMNS`]. Only the
+    /// primary weather condition of each entry is included.
+    pub fn to_csv(&self) -> String {
+        csv_with_header(self.list.iter().map(|entry| CsvRecord {
+            dt: entry.dt,
+            timezone: self.city.timezone,
+            city_name: &self.city.name,
+            coord: self.city.coord,
+            main: &entry.main,
+            visibility: entry.visibility,
+            wind: entry.wind.as_ref(),
+            clouds: &entry.clouds,
+            weather: entry.weather.first(),
+            rain: entry.rain.as_ref(),
+            snow: entry.snow.as_ref(),
+            pop: Some(entry.pop),
+        }))
+    }
+}
+
+impl OWHourlyForecastResponse {
+    /// Renders the forecast as CSV with a header row and a row per entry, see [`EXPORT_CSV_COLUMNS`]. Only the
+    /// primary weather condition of each entry is included.
+    pub fn to_csv(&self) -> String {
+        csv_with_header(self.list.iter().map(|entry| CsvRecord {
+            dt: entry.dt,
+            timezone: self.city.timezone,
+            city_name: &self.city.name,
+            coord: self.city.coord,
+            main: &entry.main,
+            visibility: entry.visibility,
+            wind: entry.wind.as_ref(),
+            clouds: &entry.clouds,
+            weather: entry.weather.first(),
+            rain: entry.rain.as_ref(),
+            snow: entry.snow.as_ref(),
+            pop: Some(entry.pop),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_records;
+    use crate::forecast::tests::FORECAST_RESPONSE;
+    use crate::types::tests::zocca;
+    use serde_json::Value;
+    use std::io::Cursor;
+
+    #[test]
+    fn current_weather_feature() {
+        let geojson = zocca().to_geojson();
+        assert!(
+            geojson.contains(r#""coordinates":[10.99,44.34]"#),
+            "{geojson}"
+        );
+
+        let feature: Value = serde_json::from_str(&geojson).unwrap();
+        assert_eq!(feature["type"], "Feature");
+        assert_eq!(feature["geometry"]["type"], "Point");
+        assert_eq!(feature["properties"]["name"], "Zocca");
+        assert_eq!(feature["properties"]["main"]["humidity"], 95.0);
+    }
+
+    #[test]
+    fn forecast_feature_collection() {
+        let forecast: OWForecastResponse = serde_json::from_str(FORECAST_RESPONSE).unwrap();
+
+        let collection: Value = serde_json::from_str(&forecast.to_geojson()).unwrap();
+        assert_eq!(collection["type"], "FeatureCollection");
+
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), forecast.list.len());
+        assert_eq!(features[0]["properties"]["dt"], forecast.list[0].dt);
+    }
+
+    #[test]
+    fn current_weather_csv_row() {
+        let row = zocca().to_csv_row();
+        assert!(
+            row.starts_with(
+                "1763077522,2025-11-13 23:45:22 +0000 UTC,3600,Zocca,44.34,10.99,281.29,10000,"
+            ),
+            "{row}"
+        );
+        assert!(row.ends_with(",78,803,Clouds,broken clouds,04n,"), "{row}");
+        assert_eq!(row.split(',').count(), EXPORT_CSV_COLUMNS.len());
+
+        // Exports read back as History Bulk records
+        let csv = format!("{}\n{row}\n", export_csv_header());
+        let records: Vec<_> = csv_records(Cursor::new(csv))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records[0].main.temp, 281.29);
+        assert_eq!(records[0].wind.gust.map(|gust| gust.value()), Some(3.51));
+    }
+
+    #[test]
+    fn escape_csv_fields() {
+        let mut response = zocca();
+        response.name = "Zocca, \"IT\"".into();
+
+        assert!(response.to_csv_row().contains(r#","Zocca, ""IT""","#));
+    }
+
+    #[test]
+    fn forecast_csv() {
+        let forecast: OWForecastResponse = serde_json::from_str(FORECAST_RESPONSE).unwrap();
+
+        let csv = forecast.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], export_csv_header());
+        assert_eq!(lines.len(), forecast.list.len() + 1);
+        assert!(lines[1].starts_with(&forecast.list[0].dt.to_string()));
+        assert!(lines[1].ends_with(&format!(",{}", forecast.list[0].pop)));
+    }
+}
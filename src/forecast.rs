@@ -0,0 +1,776 @@
+//! Types for OpenWeather's forecast APIs
+
+use crate::{
+    GeodeticCoords, OWClouds, OWDailyFeelsLike, OWDailyTemp, OWMain, OWRain, OWSnow, OWWeather,
+    OWWind, Percentage, Pressure, WeatherUnits, WindDirection, WindSpeed,
+    from_optional_raw_wind_speed, from_optional_unix_offset, from_raw_percentage,
+    from_raw_pressure, from_raw_wind_direction, from_raw_wind_speed, from_unix_offset,
+    from_utc_shift, to_optional_raw_wind_speed, to_optional_unix_offset, to_raw_percentage,
+    to_raw_pressure, to_raw_wind_direction, to_raw_wind_speed, to_unix_offset, to_utc_shift,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+/// OpenWeather response from the 5 day / 3 hour forecast API, more details
+/// [here](https://openweathermap.org/forecast5).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWForecastResponse {
+    /// Number of forecast entries in `list`
+    pub cnt: u32,
+
+    /// Forecast entries in chronological order, one per 3 hour slice
+    pub list: Vec<OWForecastEntry>,
+
+    pub city: OWForecastCity,
+
+    /// Units the forecast was requested in, see [`crate::OWCurrentWeatherResponse::units`]
+    #[serde(skip)]
+    pub units: WeatherUnits,
+}
+
+impl OWForecastResponse {
+    /// Tags the forecast, its temperatures, and its wind speeds with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.list.iter_mut().for_each(|entry| {
+            entry.main.set_units(units);
+            if let Some(wind) = &mut entry.wind {
+                wind.set_units(units);
+            }
+        });
+        self
+    }
+}
+
+/// A single 3 hour slice of a forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWForecastEntry {
+    /// Time of data forecasted, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    pub main: OWMain,
+
+    pub weather: Vec<OWWeather>,
+
+    pub clouds: OWClouds,
+
+    pub wind: Option<OWWind>,
+
+    /// Average visibility, meter. The maximum value of the visibility is 10 km
+    pub visibility: Option<f32>,
+
+    /// Probability of precipitation, from 0 to 1
+    pub pop: f32,
+
+    /// Rain volume for the 3 hour slice, mm
+    pub rain: Option<OWRain>,
+
+    /// Snow volume for the 3 hour slice, mm
+    pub snow: Option<OWSnow>,
+}
+
+impl OWForecastEntry {
+    /// Total liquid precipitation forecast over this 3 hour slice in mm, rain and snow combined.
+    pub fn precipitation_mm(&self) -> f32 {
+        let rain = self
+            .rain
+            .as_ref()
+            .and_then(|r| r.three_hours)
+            .unwrap_or(0.0);
+        let snow = self
+            .snow
+            .as_ref()
+            .and_then(|s| s.three_hours)
+            .unwrap_or(0.0);
+
+        rain + snow
+    }
+}
+
+/// The location a forecast is for
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWForecastCity {
+    /// City ID
+    pub id: u32,
+
+    /// City name
+    pub name: String,
+
+    pub coord: GeodeticCoords,
+
+    /// Country code (GB, JP etc.)
+    pub country: String,
+
+    pub population: Option<u64>,
+
+    /// Shift in seconds from UTC
+    #[serde(deserialize_with = "from_utc_shift", serialize_with = "to_utc_shift")]
+    pub timezone: FixedOffset,
+
+    /// Sunrise time, seconds since UNIX epoch, UTC
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
+    pub sunrise: DateTime<Utc>,
+
+    /// Sunset time, seconds since UNIX epoch, UTC
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
+    pub sunset: DateTime<Utc>,
+}
+
+/// OpenWeather response from the pro 4 day hourly forecast API, more details
+/// [here](https://openweathermap.org/api/hourly-forecast).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWHourlyForecastResponse {
+    /// Number of forecast entries in `list`
+    pub cnt: u32,
+
+    /// Forecast entries in chronological order, one per hour
+    pub list: Vec<OWHourlyForecastEntry>,
+
+    pub city: OWForecastCity,
+
+    /// Units the forecast was requested in, see [`crate::OWCurrentWeatherResponse::units`]
+    #[serde(skip)]
+    pub units: WeatherUnits,
+}
+
+impl OWHourlyForecastResponse {
+    /// Tags the forecast, its temperatures, and its wind speeds with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.list.iter_mut().for_each(|entry| {
+            entry.main.set_units(units);
+            if let Some(wind) = &mut entry.wind {
+                wind.set_units(units);
+            }
+        });
+        self
+    }
+}
+
+/// A single hour of an hourly forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWHourlyForecastEntry {
+    /// Time of data forecasted, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    pub main: OWMain,
+
+    pub weather: Vec<OWWeather>,
+
+    pub clouds: OWClouds,
+
+    pub wind: Option<OWWind>,
+
+    /// Average visibility, meter. The maximum value of the visibility is 10 km
+    pub visibility: Option<f32>,
+
+    /// Probability of precipitation, from 0 to 1
+    pub pop: f32,
+
+    /// Rain volume for the hour, mm
+    pub rain: Option<OWRain>,
+
+    /// Snow volume for the hour, mm
+    pub snow: Option<OWSnow>,
+}
+
+impl OWHourlyForecastEntry {
+    /// Total liquid precipitation forecast over this hour in mm, rain and snow combined.
+    pub fn precipitation_mm(&self) -> f32 {
+        let rain = self.rain.as_ref().and_then(|r| r.one_hour);
+        let snow = self.snow.as_ref().and_then(|s| s.one_hour);
+
+        let rain = rain.map_or(0.0, |rate| rate.mm_per_hour());
+        let snow = snow.map_or(0.0, |rate| rate.mm_per_hour());
+
+        rain + snow
+    }
+}
+
+/// OpenWeather response from the 16 day daily forecast API, more details
+/// [here](https://openweathermap.org/forecast16).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDailyForecastResponse {
+    /// Number of forecast entries in `list`
+    pub cnt: u32,
+
+    /// Forecast entries in chronological order, one per day
+    pub list: Vec<OWDailyForecastEntry>,
+
+    pub city: OWDailyForecastCity,
+
+    /// Units the forecast was requested in, see [`crate::OWCurrentWeatherResponse::units`]
+    #[serde(skip)]
+    pub units: WeatherUnits,
+}
+
+impl OWDailyForecastResponse {
+    /// Tags the forecast, its temperatures, and its wind speeds with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.list
+            .iter_mut()
+            .for_each(|entry| entry.set_units(units));
+        self
+    }
+}
+
+/// A single day of a daily forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDailyForecastEntry {
+    /// Time of data forecasted, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Sunrise time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunrise: Option<DateTime<Utc>>,
+
+    /// Sunset time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunset: Option<DateTime<Utc>>,
+
+    pub temp: OWDailyTemp,
+
+    pub feels_like: OWDailyFeelsLike,
+
+    /// Atmospheric pressure on the sea level, hPa
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub pressure: Pressure,
+
+    /// Humidity, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
+
+    pub weather: Vec<OWWeather>,
+
+    /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub speed: WindSpeed,
+
+    /// Wind direction, degrees (meteorological)
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
+    pub deg: WindDirection,
+
+    /// Wind gust. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        default,
+        deserialize_with = "from_optional_raw_wind_speed",
+        serialize_with = "to_optional_raw_wind_speed"
+    )]
+    pub gust: Option<WindSpeed>,
+
+    /// Cloudiness, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub clouds: Percentage,
+
+    /// Probability of precipitation, from 0 to 1
+    pub pop: f32,
+
+    /// Rain volume for the day, mm
+    pub rain: Option<f32>,
+
+    /// Snow volume for the day, mm
+    pub snow: Option<f32>,
+}
+
+impl OWDailyForecastEntry {
+    /// Labels the temperatures and wind speeds with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        self.temp.set_units(units);
+        self.feels_like.set_units(units);
+        self.speed = WindSpeed::new(self.speed.value(), units);
+        self.gust = self.gust.map(|gust| WindSpeed::new(gust.value(), units));
+    }
+
+    /// Total liquid precipitation forecast over the day in mm, rain and snow combined.
+    pub fn precipitation_mm(&self) -> f32 {
+        self.rain.unwrap_or(0.0) + self.snow.unwrap_or(0.0)
+    }
+}
+
+/// The location a daily forecast is for, which unlike [`OWForecastCity`] carries no sunrise or sunset since those
+/// are reported per day
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDailyForecastCity {
+    /// City ID
+    pub id: u32,
+
+    /// City name
+    pub name: String,
+
+    pub coord: GeodeticCoords,
+
+    /// Country code (GB, JP etc.)
+    pub country: String,
+
+    pub population: Option<u64>,
+
+    /// Shift in seconds from UTC
+    #[serde(deserialize_with = "from_utc_shift", serialize_with = "to_utc_shift")]
+    pub timezone: FixedOffset,
+}
+
+/// OpenWeather response from the 30 day climate forecast API, more details
+/// [here](https://openweathermap.org/api/forecast30).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWClimateForecastResponse {
+    /// Number of forecast entries in `list`
+    pub cnt: u32,
+
+    /// Forecast entries in chronological order, one per day
+    pub list: Vec<OWClimateForecastEntry>,
+
+    pub city: OWDailyForecastCity,
+
+    /// Units the forecast was requested in, see [`crate::OWCurrentWeatherResponse::units`]
+    #[serde(skip)]
+    pub units: WeatherUnits,
+}
+
+impl OWClimateForecastResponse {
+    /// Tags the forecast, its temperatures, and its wind speeds with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.list
+            .iter_mut()
+            .for_each(|entry| entry.set_units(units));
+        self
+    }
+}
+
+/// A single day of a climate forecast. Climate forecasts are statistical rather than modelled, so they don't carry
+/// a probability of precipitation or gusts.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWClimateForecastEntry {
+    /// Time of data forecasted, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Sunrise time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunrise: Option<DateTime<Utc>>,
+
+    /// Sunset time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunset: Option<DateTime<Utc>>,
+
+    pub temp: OWDailyTemp,
+
+    pub feels_like: OWDailyFeelsLike,
+
+    /// Atmospheric pressure on the sea level, hPa
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub pressure: Pressure,
+
+    /// Humidity, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
+
+    pub weather: Vec<OWWeather>,
+
+    /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub speed: WindSpeed,
+
+    /// Wind direction, degrees (meteorological)
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
+    pub deg: WindDirection,
+
+    /// Cloudiness, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub clouds: Percentage,
+
+    /// Rain volume for the day, mm
+    pub rain: Option<f32>,
+
+    /// Snow volume for the day, mm
+    pub snow: Option<f32>,
+}
+
+impl OWClimateForecastEntry {
+    /// Labels the temperatures and wind speeds with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        self.temp.set_units(units);
+        self.feels_like.set_units(units);
+        self.speed = WindSpeed::new(self.speed.value(), units);
+    }
+
+    /// Total liquid precipitation forecast over the day in mm, rain and snow combined.
+    pub fn precipitation_mm(&self) -> f32 {
+        self.rain.unwrap_or(0.0) + self.snow.unwrap_or(0.0)
+    }
+}
+
+/// A forecast value along with the time it's forecast for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extreme {
+    pub value: f32,
+
+    /// Time of data forecasted, UNIX time in seconds, UTC
+    pub dt: u64,
+}
+
+/// Headline numbers across an entire forecast
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ForecastExtremes {
+    /// Coldest forecast temperature, `None` for an empty forecast
+    pub min_temp: Option<Extreme>,
+
+    /// Hottest forecast temperature, `None` for an empty forecast
+    pub max_temp: Option<Extreme>,
+
+    /// Strongest forecast sustained wind speed, `None` if no entries include wind
+    pub peak_wind_speed: Option<Extreme>,
+
+    /// Rain and snow summed over the whole forecast in mm, zero for an empty forecast
+    pub total_precipitation_mm: f32,
+}
+
+impl OWForecastResponse {
+    /// Computes the coldest and hottest temperatures, the peak wind speed, and total precipitation across every entry
+    /// in the forecast. Ties go to the earliest entry. An empty forecast yields [`ForecastExtremes::default`].
+    pub fn extremes(&self) -> ForecastExtremes {
+        let mut extremes = ForecastExtremes::default();
+
+        let replace_if =
+            |current: &mut Option<Extreme>, candidate: Extreme, better: fn(f32, f32) -> bool| {
+                if current.is_none_or(|c| better(candidate.value, c.value)) {
+                    *current = Some(candidate);
+                }
+            };
+
+        for entry in &self.list {
+            let temp = Extreme {
+                value: entry.main.temp.value(),
+                dt: entry.dt,
+            };
+            replace_if(&mut extremes.min_temp, temp, |a, b| a < b);
+            replace_if(&mut extremes.max_temp, temp, |a, b| a > b);
+
+            if let Some(wind) = &entry.wind {
+                let speed = Extreme {
+                    value: wind.speed.value(),
+                    dt: entry.dt,
+                };
+                replace_if(&mut extremes.peak_wind_speed, speed, |a, b| a > b);
+            }
+
+            extremes.total_precipitation_mm += entry.precipitation_mm();
+        }
+
+        extremes
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::Temperature;
+
+    /// A trimmed down forecast response with three entries
+    pub(crate) const FORECAST_RESPONSE: &str = r#"
+        {
+        "cod": "200",
+        "message": 0,
+        "cnt": 3,
+        "list": [
+            {
+            "dt": 1763089200,
+            "main": {
+                "temp": 280.1, "feels_like": 278.2, "temp_min": 279.5, "temp_max": 280.1,
+                "pressure": 1024, "sea_level": 1024, "grnd_level": 956, "humidity": 90, "temp_kf": 0.6
+            },
+            "weather": [{ "id": 803, "main": "Clouds", "description": "broken clouds", "icon": "04n" }],
+            "clouds": { "all": 75 },
+            "wind": { "speed": 2.1, "deg": 200, "gust": 3.0 },
+            "visibility": 10000,
+            "pop": 0,
+            "sys": { "pod": "n" },
+            "dt_txt": "2025-11-14 03:00:00"
+            },
+            {
+            "dt": 1763100000,
+            "main": {
+                "temp": 277.4, "feels_like": 275.0, "temp_min": 277.4, "temp_max": 277.4,
+                "pressure": 1022, "sea_level": 1022, "grnd_level": 954, "humidity": 97, "temp_kf": 0
+            },
+            "weather": [{ "id": 500, "main": "Rain", "description": "light rain", "icon": "10d" }],
+            "clouds": { "all": 100 },
+            "wind": { "speed": 6.4, "deg": 250, "gust": 11.2 },
+            "visibility": 6000,
+            "pop": 0.8,
+            "rain": { "3h": 1.25 },
+            "sys": { "pod": "d" },
+            "dt_txt": "2025-11-14 06:00:00"
+            },
+            {
+            "dt": 1763110800,
+            "main": {
+                "temp": 283.9, "feels_like": 282.8, "temp_min": 283.9, "temp_max": 283.9,
+                "pressure": 1020, "sea_level": 1020, "grnd_level": 953, "humidity": 70, "temp_kf": 0
+            },
+            "weather": [{ "id": 600, "main": "Snow", "description": "light snow", "icon": "13d" }],
+            "clouds": { "all": 100 },
+            "wind": { "speed": 4.0, "deg": 270 },
+            "visibility": 8000,
+            "pop": 0.5,
+            "snow": { "3h": 0.5 },
+            "sys": { "pod": "d" },
+            "dt_txt": "2025-11-14 09:00:00"
+            }
+        ],
+        "city": {
+            "id": 3163858,
+            "name": "Zocca",
+            "coord": { "lat": 44.34, "lon": 10.99 },
+            "country": "IT",
+            "population": 4593,
+            "timezone": 3600,
+            "sunrise": 1763100641,
+            "sunset": 1763135429
+        }
+        }
+        "#;
+
+    #[test]
+    fn forecast_extremes() {
+        let forecast: OWForecastResponse = serde_json::from_str(FORECAST_RESPONSE).unwrap();
+        assert_eq!(forecast.list.len(), 3);
+
+        let extremes = forecast.extremes();
+        assert_eq!(
+            extremes.min_temp,
+            Some(Extreme {
+                value: 277.4,
+                dt: 1763100000
+            })
+        );
+        assert_eq!(
+            extremes.max_temp,
+            Some(Extreme {
+                value: 283.9,
+                dt: 1763110800
+            })
+        );
+        assert_eq!(
+            extremes.peak_wind_speed,
+            Some(Extreme {
+                value: 6.4,
+                dt: 1763100000
+            })
+        );
+        assert!((extremes.total_precipitation_mm - 1.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parse_hourly_forecast() {
+        let json = r#"
+            {
+            "cod": "200",
+            "message": 0,
+            "cnt": 1,
+            "list": [
+                {
+                "dt": 1763089200,
+                "main": {
+                    "temp": 280.1, "feels_like": 278.2, "temp_min": 279.5, "temp_max": 280.1,
+                    "pressure": 1024, "sea_level": 1024, "grnd_level": 956, "humidity": 90, "temp_kf": 0.6
+                },
+                "weather": [{ "id": 500, "main": "Rain", "description": "light rain", "icon": "10n" }],
+                "clouds": { "all": 100 },
+                "wind": { "speed": 2.1, "deg": 200, "gust": 3.0 },
+                "visibility": 10000,
+                "pop": 0.6,
+                "rain": { "1h": 0.4 },
+                "sys": { "pod": "n" },
+                "dt_txt": "2025-11-14 03:00:00"
+                }
+            ],
+            "city": {
+                "id": 3163858,
+                "name": "Zocca",
+                "coord": { "lat": 44.34, "lon": 10.99 },
+                "country": "IT",
+                "timezone": 3600,
+                "sunrise": 1763100641,
+                "sunset": 1763135429
+            }
+            }
+            "#;
+
+        let forecast: OWHourlyForecastResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(forecast.list.len(), 1);
+        assert_eq!(forecast.list[0].precipitation_mm(), 0.4);
+        assert_eq!(forecast.city.population, None);
+    }
+
+    #[test]
+    fn parse_daily_forecast() {
+        let json = r#"
+            {
+            "city": {
+                "id": 3163858,
+                "name": "Zocca",
+                "coord": { "lat": 44.34, "lon": 10.99 },
+                "country": "IT",
+                "population": 4593,
+                "timezone": 3600
+            },
+            "cod": "200",
+            "message": 0.0582,
+            "cnt": 1,
+            "list": [
+                {
+                "dt": 1763118000,
+                "sunrise": 1763100641,
+                "sunset": 1763135429,
+                "temp": { "day": 283.9, "min": 277.4, "max": 284.6, "night": 278.1, "eve": 281.0, "morn": 277.9 },
+                "feels_like": { "day": 282.8, "night": 276.0, "eve": 279.9, "morn": 275.5 },
+                "pressure": 1022,
+                "humidity": 70,
+                "weather": [{ "id": 600, "main": "Snow", "description": "light snow", "icon": "13d" }],
+                "speed": 6.4,
+                "deg": 250,
+                "gust": 11.2,
+                "clouds": 100,
+                "pop": 0.8,
+                "rain": 1.25,
+                "snow": 0.5
+                }
+            ]
+            }
+            "#;
+
+        let forecast: OWDailyForecastResponse = serde_json::from_str(json).unwrap();
+        let day = &forecast.list[0];
+        assert_eq!(day.temp.max.value(), 284.6);
+        assert_eq!(day.feels_like.morn.value(), 275.5);
+        assert!((day.precipitation_mm() - 1.75).abs() < 1e-6);
+        assert_eq!(forecast.city.timezone.local_minus_utc(), 3600);
+
+        // Tagging relabels the values without converting them
+        let forecast = forecast.with_units(WeatherUnits::Imperial);
+        let day = &forecast.list[0];
+        assert_eq!(
+            day.temp.max,
+            Temperature::new(284.6, WeatherUnits::Imperial)
+        );
+        assert_eq!(day.feels_like.morn.units(), WeatherUnits::Imperial);
+        assert_eq!(day.gust, Some(WindSpeed::new(11.2, WeatherUnits::Imperial)));
+        assert!((day.speed.to_mps() - 2.861).abs() < 1e-3);
+        assert_eq!(day.pressure.hpa(), 1022.0);
+    }
+
+    #[test]
+    fn parse_climate_forecast() {
+        let json = r#"
+            {
+            "cod": "200",
+            "city": {
+                "id": 3163858,
+                "name": "Zocca",
+                "coord": { "lat": 44.34, "lon": 10.99 },
+                "country": "IT",
+                "population": 4593,
+                "timezone": 3600
+            },
+            "message": 0.353472054,
+            "list": [
+                {
+                "dt": 1763118000,
+                "sunrise": 1763100641,
+                "sunset": 1763135429,
+                "temp": { "day": 283.9, "min": 277.4, "max": 284.6, "night": 278.1, "eve": 281.0, "morn": 277.9 },
+                "feels_like": { "day": 282.8, "night": 276.0, "eve": 279.9, "morn": 275.5 },
+                "pressure": 1022,
+                "humidity": 70,
+                "weather": [{ "id": 500, "main": "Rain", "description": "light rain", "icon": "10d" }],
+                "speed": 3.2,
+                "deg": 250,
+                "clouds": 84,
+                "rain": 2.1
+                }
+            ],
+            "cnt": 1
+            }
+            "#;
+
+        let forecast: OWClimateForecastResponse = serde_json::from_str(json).unwrap();
+        let day = &forecast.list[0];
+        assert_eq!(day.temp.night.value(), 278.1);
+        assert_eq!(day.precipitation_mm(), 2.1);
+        assert_eq!(forecast.city.name, "Zocca");
+
+        let forecast = forecast.with_units(WeatherUnits::Metric);
+        let day = &forecast.list[0];
+        assert_eq!(forecast.units, WeatherUnits::Metric);
+        assert_eq!(
+            day.temp.night,
+            Temperature::new(278.1, WeatherUnits::Metric)
+        );
+        assert_eq!(day.speed, WindSpeed::new(3.2, WeatherUnits::Metric));
+    }
+
+    #[test]
+    fn empty_forecast_extremes() {
+        let mut forecast: OWForecastResponse = serde_json::from_str(FORECAST_RESPONSE).unwrap();
+        forecast.list.clear();
+
+        assert_eq!(forecast.extremes(), ForecastExtremes::default());
+    }
+}
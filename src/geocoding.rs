@@ -0,0 +1,144 @@
+//! Types for OpenWeather's geocoding API, which maps place names to coordinates and back
+
+use crate::GeodeticCoords;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A single place matched by the geocoding API, more details [here](https://openweathermap.org/api/geocoding-api).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GeoLocation {
+    /// Name of the found location
+    pub name: String,
+
+    /// Name of the found location in different languages, keyed by ISO 639 language code
+    pub local_names: Option<HashMap<String, String>>,
+
+    /// Latitude of the found location
+    pub lat: f32,
+
+    /// Longitude of the found location
+    pub lon: f32,
+
+    /// Country code (GB, JP etc.)
+    pub country: String,
+
+    /// State of the found location, where available
+    pub state: Option<String>,
+}
+
+impl GeoLocation {
+    /// Coordinates of this location
+    pub fn coords(&self) -> GeodeticCoords {
+        GeodeticCoords {
+            lat: self.lat,
+            lon: self.lon,
+        }
+    }
+
+    /// Picks the candidate closest to `reference`, which is a handy way to disambiguate common place names by
+    /// biasing toward a known location. Returns `None` if there are no candidates.
+    pub fn nearest<'a>(
+        candidates: &'a [GeoLocation],
+        reference: &GeodeticCoords,
+    ) -> Option<&'a GeoLocation> {
+        candidates.iter().min_by(|a, b| {
+            let dist_a = a.coords().distance_to(reference);
+            let dist_b = b.coords().distance_to(reference);
+
+            dist_a.total_cmp(&dist_b)
+        })
+    }
+}
+
+/// The place a zip or postal code belongs to, as returned by the zip geocoding API
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ZipLocation {
+    /// The zip or postal code which was looked up
+    pub zip: String,
+
+    /// Name of the found location
+    pub name: String,
+
+    /// Latitude of the centroid of the zip code
+    pub lat: f32,
+
+    /// Longitude of the centroid of the zip code
+    pub lon: f32,
+
+    /// Country code (GB, JP etc.)
+    pub country: String,
+}
+
+impl ZipLocation {
+    /// Coordinates of this location
+    pub fn coords(&self) -> GeodeticCoords {
+        GeodeticCoords {
+            lat: self.lat,
+            lon: self.lon,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn springfield(state: &str, lat: f32, lon: f32) -> GeoLocation {
+        GeoLocation {
+            name: "Springfield".into(),
+            local_names: None,
+            lat,
+            lon,
+            country: "US".into(),
+            state: Some(state.into()),
+        }
+    }
+
+    #[test]
+    fn parse_direct_geocoding() {
+        let json = r#"
+            [
+                {
+                "name": "San Diego",
+                "local_names": { "en": "San Diego", "es": "San Diego", "ru": "Сан-Диего" },
+                "lat": 32.7174202,
+                "lon": -117.1627728,
+                "country": "US",
+                "state": "California"
+                }
+            ]
+            "#;
+
+        let locations: Vec<GeoLocation> = serde_json::from_str(json).unwrap();
+        assert_eq!(locations[0].state.as_deref(), Some("California"));
+        assert_eq!(
+            locations[0].local_names.as_ref().unwrap()["ru"],
+            "Сан-Диего"
+        );
+        assert!((locations[0].coords().lat - 32.717).abs() < 1e-3);
+    }
+
+    #[test]
+    fn parse_zip_geocoding() {
+        let json = r#"{ "zip": "92651", "name": "Laguna Beach", "lat": 33.5427, "lon": -117.7854, "country": "US" }"#;
+
+        let location: ZipLocation = serde_json::from_str(json).unwrap();
+        assert_eq!(location.name, "Laguna Beach");
+        assert!((location.coords().lon + 117.785).abs() < 1e-3);
+    }
+
+    #[test]
+    fn nearest_candidate() {
+        let candidates = [
+            springfield("Massachusetts", 42.101, -72.590),
+            springfield("Illinois", 39.799, -89.644),
+            springfield("Oregon", 44.046, -123.022),
+        ];
+
+        let chicago = GeodeticCoords::new_checked(41.878, -87.630).unwrap();
+        let nearest = GeoLocation::nearest(&candidates, &chicago).unwrap();
+        assert_eq!(nearest.state.as_deref(), Some("Illinois"));
+
+        assert!(GeoLocation::nearest(&[], &chicago).is_none());
+    }
+}
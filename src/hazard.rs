@@ -0,0 +1,165 @@
+//! Summarizing the most notable weather in a current weather response
+
+use crate::{
+    ConditionGroup, OWCurrentWeatherResponse, PrecipIntensity, PrecipRate, WeatherConditionId,
+};
+use strum::Display;
+
+/// Conditions which count as heavy rain
+const HEAVY_RAIN_IDS: [WeatherConditionId; 5] = [
+    WeatherConditionId::HeavyRain,
+    WeatherConditionId::VeryHeavyRain,
+    WeatherConditionId::ExtremeRain,
+    WeatherConditionId::HeavyShowerRain,
+    WeatherConditionId::RaggedShowerRain,
+];
+
+/// Conditions which count as heavy snow
+const HEAVY_SNOW_IDS: [WeatherConditionId; 2] = [
+    WeatherConditionId::HeavySnow,
+    WeatherConditionId::HeavyShowerSnow,
+];
+
+/// Beaufort number at or above which wind is considered a gale
+pub(crate) const GALE_BEAUFORT: u8 = 8;
+
+/// Visibility in meters below which we consider it foggy, regardless of the reported condition
+const FOG_VISIBILITY_M: f32 = 1000.0;
+
+/// Notable weather hazards, declared from most to least severe.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum Hazard {
+    Tornado,
+    Thunderstorm,
+    HeavySnow,
+    HeavyRain,
+    GaleWind,
+    Fog,
+}
+
+impl OWCurrentWeatherResponse {
+    /// Returns the single most notable hazard in this response, or `None` if conditions are benign.
+    ///
+    /// When several hazards are present the most severe wins, in the order:
+    /// tornado > thunderstorm > heavy snow > heavy rain > gale force wind > fog.
+    ///
+    /// Every reported weather condition is considered, not just the primary one. Heavy precipitation is detected
+    /// from either the condition ID or the reported 1h precipitation rate, and fog from either the condition ID or
+    /// reduced visibility.
+    pub fn primary_hazard(&self) -> Option<Hazard> {
+        let any_condition =
+            |pred: fn(WeatherConditionId) -> bool| self.weather.iter().any(|w| pred(w.id));
+        let heavy_rate = |rate: Option<PrecipRate>| {
+            rate.is_some_and(|r| r.intensity() == PrecipIntensity::Heavy)
+        };
+
+        if any_condition(|id| id == WeatherConditionId::Tornado) {
+            return Some(Hazard::Tornado);
+        }
+
+        if any_condition(|id| id.group() == ConditionGroup::Thunderstorm) {
+            return Some(Hazard::Thunderstorm);
+        }
+
+        if any_condition(|id| HEAVY_SNOW_IDS.contains(&id))
+            || heavy_rate(self.snow.as_ref().and_then(|s| s.one_hour))
+        {
+            return Some(Hazard::HeavySnow);
+        }
+
+        if any_condition(|id| HEAVY_RAIN_IDS.contains(&id))
+            || heavy_rate(self.rain.as_ref().and_then(|r| r.one_hour))
+        {
+            return Some(Hazard::HeavyRain);
+        }
+
+        if self.wind().is_some_and(|w| w.beaufort() >= GALE_BEAUFORT) {
+            return Some(Hazard::GaleWind);
+        }
+
+        if any_condition(|id| id == WeatherConditionId::Fog) || self.visibility < FOG_VISIBILITY_M {
+            return Some(Hazard::Fog);
+        }
+
+        None
+    }
+}
+
+impl WeatherConditionId {
+    /// Returns the hazard this condition reports on its own, without the precipitation rates, wind, and visibility
+    /// [`OWCurrentWeatherResponse::primary_hazard`] also considers.
+    pub fn hazard(&self) -> Option<Hazard> {
+        match *self {
+            WeatherConditionId::Tornado => Some(Hazard::Tornado),
+            id if id.group() == ConditionGroup::Thunderstorm => Some(Hazard::Thunderstorm),
+            id if HEAVY_SNOW_IDS.contains(&id) => Some(Hazard::HeavySnow),
+            id if HEAVY_RAIN_IDS.contains(&id) => Some(Hazard::HeavyRain),
+            WeatherConditionId::Fog => Some(Hazard::Fog),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+    use crate::{OWRain, OWWeather, WeatherUnits, WindSpeed};
+
+    fn condition(id: WeatherConditionId) -> OWWeather {
+        OWWeather {
+            id,
+            main: String::new(),
+            description: String::new(),
+            icon: String::new(),
+        }
+    }
+
+    #[test]
+    fn benign_conditions() {
+        assert_eq!(zocca().primary_hazard(), None);
+    }
+
+    #[test]
+    fn thunderstorm_outranks_fog() {
+        let mut response = zocca();
+        response.weather = vec![
+            condition(WeatherConditionId::Fog),
+            condition(WeatherConditionId::Thunderstorm),
+        ];
+        response.visibility = 200.0;
+
+        assert_eq!(response.primary_hazard(), Some(Hazard::Thunderstorm));
+    }
+
+    #[test]
+    fn hazards_from_measurements() {
+        let mut response = zocca();
+        response.visibility = 200.0;
+        assert_eq!(response.primary_hazard(), Some(Hazard::Fog));
+
+        response.wind.as_mut().unwrap().speed = WindSpeed::new(20.0, WeatherUnits::Standard);
+        assert_eq!(response.primary_hazard(), Some(Hazard::GaleWind));
+
+        response.rain = Some(OWRain {
+            one_hour: Some(PrecipRate::from_mm_per_hour(10.0)),
+            three_hours: None,
+        });
+        assert_eq!(response.primary_hazard(), Some(Hazard::HeavyRain));
+    }
+
+    #[test]
+    fn hazards_from_conditions() {
+        assert_eq!(WeatherConditionId::Tornado.hazard(), Some(Hazard::Tornado));
+        assert_eq!(
+            WeatherConditionId::LightThunderstorm.hazard(),
+            Some(Hazard::Thunderstorm)
+        );
+        assert_eq!(
+            WeatherConditionId::HeavyShowerSnow.hazard(),
+            Some(Hazard::HeavySnow)
+        );
+        assert_eq!(WeatherConditionId::LightRain.hazard(), None);
+        assert_eq!(WeatherConditionId::BrokenClouds.hazard(), None);
+    }
+}
@@ -0,0 +1,108 @@
+//! URLs and downloads for OpenWeather's weather condition icons
+
+use crate::{OWWeather, OpenWeatherClient, OpenWxError};
+use strum::Display;
+
+/// Host serving weather condition icons, icons don't need an API key
+pub const ICON_BASE_URL: &str = "https://openweathermap.org/img/wn";
+
+/// Sizes OpenWeather renders its weather condition icons in
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IconSize {
+    /// 50x50 pixels
+    #[strum(serialize = "")]
+    Small,
+    /// 100x100 pixels
+    #[default]
+    #[strum(serialize = "@2x")]
+    Medium,
+    /// 200x200 pixels
+    #[strum(serialize = "@4x")]
+    Large,
+}
+
+/// URL of the PNG for a weather condition icon, e.g. `04n` from [`OWWeather::icon`]
+pub fn icon_url(icon: &str, size: IconSize) -> String {
+    format!("{ICON_BASE_URL}/{icon}{size}.png")
+}
+
+impl OWWeather {
+    /// URL of the PNG for this condition's icon
+    pub fn icon_url(&self, size: IconSize) -> String {
+        icon_url(&self.icon, size)
+    }
+
+    /// Whether the icon is the daytime (`d` suffix) or nighttime (`n` suffix) variant, `None` for an icon code
+    /// without either suffix.
+    pub fn is_day_icon(&self) -> Option<bool> {
+        match self.icon.chars().last() {
+            Some('d') => Some(true),
+            Some('n') => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl OpenWeatherClient {
+    /// Fetch a weather condition icon as PNG bytes, this is a blocking HTTP request. Icons always come from
+    /// [`ICON_BASE_URL`] regardless of the client's base URL, and are requested without the API key.
+    pub fn weather_icon(&self, icon: &str, size: IconSize) -> Result<Vec<u8>, OpenWxError> {
+        self.get_public_bytes(icon_url(icon, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::FakeTransport;
+    use crate::types::tests::zocca;
+
+    #[test]
+    fn icon_urls() {
+        let weather = &zocca().weather[0];
+
+        assert_eq!(
+            weather.icon_url(IconSize::default()),
+            "https://openweathermap.org/img/wn/04n@2x.png"
+        );
+        assert_eq!(
+            icon_url("10d", IconSize::Small),
+            "https://openweathermap.org/img/wn/10d.png"
+        );
+        assert_eq!(
+            icon_url("10d", IconSize::Large),
+            "https://openweathermap.org/img/wn/10d@4x.png"
+        );
+    }
+
+    #[test]
+    fn icon_day_night() {
+        let mut weather = zocca().weather[0].clone();
+        assert_eq!(weather.is_day_icon(), Some(false));
+
+        weather.icon = "01d".into();
+        assert_eq!(weather.is_day_icon(), Some(true));
+
+        weather.icon.clear();
+        assert_eq!(weather.is_day_icon(), None);
+    }
+
+    #[test]
+    fn fetch_icon() {
+        let transport = FakeTransport::serving("PNG");
+        let client = OpenWeatherClient::with_transport(
+            "secret".into(),
+            crate::WeatherUnits::Metric,
+            transport.clone(),
+        );
+
+        assert_eq!(
+            client.weather_icon("01d", IconSize::Medium).unwrap(),
+            b"PNG"
+        );
+
+        let request = &transport.requests()[0];
+        assert_eq!(request.url, "https://openweathermap.org/img/wn/01d@2x.png");
+        assert!(request.headers.is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+//! Append-only JSON Lines logs of observations, for keeping flat files rather than a database. Each line is a
+//! [`JsonlRecord`], the serialized response plus where and when it was fetched.
+
+use crate::{
+    CollectError, OWCurrentWeatherResponse, ObservationSink, WeatherUnits, from_unix_offset,
+    to_unix_offset,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::Path;
+
+/// One line of a JSON Lines log
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct JsonlRecord {
+    /// Name the observation was recorded under
+    pub location: String,
+
+    /// When the observation was fetched, which can be well after OpenWeather calculated it
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
+    pub fetched_at: DateTime<Utc>,
+
+    /// Units the observation was requested in
+    pub units: WeatherUnits,
+
+    pub observation: OWCurrentWeatherResponse,
+}
+
+impl JsonlRecord {
+    /// A record of an observation fetched just now
+    pub fn new(location: impl Into<String>, observation: OWCurrentWeatherResponse) -> Self {
+        JsonlRecord {
+            location: location.into(),
+            fetched_at: Utc::now(),
+            units: observation.units,
+            observation,
+        }
+    }
+}
+
+/// Appends observations to a file as JSON lines, usable directly or as a [`crate::Collector`] sink.
+pub struct JsonlSink {
+    file: File,
+}
+
+impl JsonlSink {
+    /// Opens the file for appending, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(JsonlSink { file })
+    }
+
+    /// Appends a record as a single line.
+    pub fn append(&mut self, record: &JsonlRecord) -> Result<(), CollectError> {
+        let mut line = serde_json::to_vec(record)?;
+        line.push(b'\n');
+
+        // A single write keeps lines whole even if another process appends to the same file
+        self.file.write_all(&line)?;
+
+        Ok(())
+    }
+}
+
+impl ObservationSink for JsonlSink {
+    fn record(
+        &mut self,
+        location: &str,
+        response: &OWCurrentWeatherResponse,
+    ) -> Result<(), CollectError> {
+        self.append(&JsonlRecord::new(location, response.clone()))
+    }
+}
+
+/// Reads every record from a JSON Lines log, oldest first. Blank lines are skipped, and observations are labelled with
+/// the units they were recorded in.
+pub fn read_jsonl(path: impl AsRef<Path>) -> io::Result<Vec<JsonlRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    let mut records = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut record: JsonlRecord = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {e}", i + 1))
+        })?;
+        record.observation = record.observation.with_units(record.units);
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+
+    #[test]
+    fn append_and_read_back() {
+        let path = std::env::temp_dir().join(format!("openwx-jsonl-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let response = zocca().with_units(WeatherUnits::Metric);
+        let mut sink = JsonlSink::open(&path).unwrap();
+        sink.record("zocca", &response).unwrap();
+        sink.record("laguna", &response).unwrap();
+
+        let records = read_jsonl(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].location, "zocca");
+        assert_eq!(records[1].location, "laguna");
+        assert_eq!(records[0].units, WeatherUnits::Metric);
+        assert_eq!(records[0].observation, response);
+
+        let first_line: serde_json::Value = serde_json::from_str(
+            std::fs::read_to_string(&path)
+                .unwrap()
+                .lines()
+                .next()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(first_line["units"], "metric");
+        assert!(first_line["fetched_at"].is_i64());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn bad_line_reported() {
+        let path =
+            std::env::temp_dir().join(format!("openwx-jsonl-bad-{}.jsonl", std::process::id()));
+        std::fs::write(&path, "\n{}\n").unwrap();
+
+        let err = read_jsonl(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(err.to_string().starts_with("line 2: "));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,7 +1,87 @@
 //! Library code for working with the OpenWeather API
 
+pub mod air_quality;
 pub mod apis;
+pub mod astronomy;
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod aviation;
+pub mod bulk;
+pub mod cache;
+pub mod client;
+pub mod collector;
+pub mod conversions;
+pub mod derived;
+pub mod export;
+pub mod forecast;
+pub mod geocoding;
+pub mod hazard;
+pub mod icons;
+pub mod jsonl;
+pub mod maps;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod onecall;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod provider;
+pub mod quantities;
+pub mod rate_limit;
+pub mod report;
+pub mod retry;
+pub mod road_risk;
+#[cfg(feature = "async")]
+mod single_flight;
+pub mod smoothing;
+pub mod solar;
+pub mod stations;
+#[cfg(feature = "sqlite")]
+pub mod storage;
+pub mod triggers;
 pub mod types;
+pub mod validation;
+#[cfg(feature = "xml")]
+pub mod xml;
 
+pub use air_quality::*;
 pub use apis::*;
+#[cfg(feature = "async")]
+pub use async_client::*;
+pub use aviation::*;
+pub use bulk::*;
+pub use cache::*;
+pub use client::*;
+pub use collector::*;
+pub use derived::*;
+pub use export::*;
+pub use forecast::*;
+pub use geocoding::*;
+pub use hazard::*;
+pub use icons::*;
+pub use jsonl::*;
+pub use maps::*;
+#[cfg(feature = "metrics")]
+pub use metrics::*;
+#[cfg(feature = "mqtt")]
+pub use mqtt::*;
+pub use onecall::*;
+#[cfg(feature = "parquet")]
+pub use parquet::*;
+pub use provider::*;
+pub use quantities::*;
+pub use rate_limit::*;
+pub use report::*;
+pub use retry::*;
+pub use road_risk::*;
+pub use smoothing::*;
+pub use solar::*;
+pub use stations::*;
+#[cfg(feature = "sqlite")]
+pub use storage::*;
+pub use triggers::*;
 pub use types::*;
+pub use validation::*;
+#[cfg(feature = "xml")]
+pub use xml::*;
@@ -1,10 +1,192 @@
-use clap::Parser;
-use openwx::{GeodeticCoords, WeatherUnits};
+use anyhow::{Context, anyhow};
+use clap::{Parser, Subcommand, ValueEnum};
+use color::{AnsiSummary, ColorChoice};
+use config::Config;
+use openwx::{
+    Collector, GeoLocation, GeodeticCoords, JsonlRecord, JsonlSink, Language, NamedLocation,
+    OWCurrentWeatherResponse, OpenWeatherClient, PlainSummary, ResponseCache, ResponseMode,
+    SummaryStyle, WeatherUnits,
+};
+use output::{OutputFormat, describe_place};
+use serde_json::Value;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::time::Duration;
 
-/// Trivial CLI to hit the OpenWeather API for the current weather at a position
+mod color;
+mod config;
+mod output;
+
+/// Trivial CLI to hit the OpenWeather API
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
+    #[command(flatten)]
+    client: ClientOptions,
+
+    #[command(flatten)]
+    output: OutputOptions,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// How to reach OpenWeather, shared by every command
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Client Options")]
+struct ClientOptions {
+    /// OpenWeather API key, overrides the environment variable
+    #[arg(
+        short,
+        long,
+        env = "OPENWEATHER_API_KEY",
+        hide_env_values = true,
+        global = true
+    )]
+    api_key: Option<String>,
+
+    /// Units to report in, overrides the config file [default: imperial]
+    #[arg(long, value_name = "UNITS", global = true)]
+    units: Option<WeatherUnits>,
+
+    /// Language to localize weather descriptions into, as an OpenWeather language code, e.g. `de` or `pt_br`
+    #[arg(long, value_name = "CODE", global = true)]
+    lang: Option<Language>,
+
+    /// Cache responses in this directory so repeated runs within the TTL don't spend API calls
+    #[arg(long, value_name = "DIR", global = true)]
+    cache_dir: Option<PathBuf>,
+
+    /// Seconds a cached response is reused for, OpenWeather refreshes its data every 10 minutes
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        default_value_t = 600,
+        requires = "cache_dir",
+        global = true
+    )]
+    cache_ttl: u64,
+
+    /// Scheme and host to send requests to, e.g. an internal proxy in front of OpenWeather
+    #[arg(long, value_name = "URL", default_value = openwx::DEFAULT_BASE_URL, global = true)]
+    base_url: String,
+
+    /// Give up on a request which hasn't completed after this many seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 30, global = true)]
+    timeout: u64,
+
+    /// Give up on connecting to OpenWeather after this many seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 10, global = true)]
+    connect_timeout: u64,
+
+    /// Read settings and named locations from this file instead of `~/.config/openwx/config.toml`
+    #[arg(long, value_name = "PATH", global = true)]
+    config: Option<PathBuf>,
+}
+
+/// How responses are printed, shared by every command
+#[derive(clap::Args, Debug)]
+#[command(next_help_heading = "Output Options")]
+struct OutputOptions {
+    /// Print responses as a readable summary, or in a structured format for scripts
+    #[arg(long, value_enum, default_value_t = OutputFormat::Summary, global = true)]
+    format: OutputFormat,
+
+    /// When to color summaries, `auto` colors a terminal unless NO_COLOR is set
+    #[arg(long, value_enum, value_name = "WHEN", default_value_t = ColorChoice::Auto, global = true)]
+    color: ColorChoice,
+}
+
+impl ClientOptions {
+    /// Builds a client from these options, falling back to the config file for anything not given on the command line
+    fn build(&self, config: &Config) -> anyhow::Result<OpenWeatherClient> {
+        let api_key = self.api_key.clone().or_else(|| config.api_key.clone()).context(
+            "an OpenWeather API key is required, set OPENWEATHER_API_KEY, pass --api-key, or add it to the config file",
+        )?;
+        let units = self
+            .units
+            .or(config.units)
+            .unwrap_or(WeatherUnits::Imperial);
+        let mut builder = OpenWeatherClient::builder(api_key)
+            .units(units)
+            .base_url(self.base_url.as_str())
+            .connect_timeout(Duration::from_secs(self.connect_timeout))
+            .timeout(Duration::from_secs(self.timeout));
+        let lang = match self.lang {
+            Some(lang) => Some(lang),
+            None => config.lang()?,
+        };
+        if let Some(language) = lang {
+            builder = builder.language(language);
+        }
+        if let Some(dir) = &self.cache_dir {
+            let cache = ResponseCache::on_disk(dir, Duration::from_secs(self.cache_ttl))
+                .with_context(|| format!("failed to create cache directory `{}`", dir.display()))?;
+            builder = builder.cache(cache);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// OpenWeather refreshes its data every 10 minutes, watching any faster mostly spends API calls on unchanged data
+const MIN_WATCH_INTERVAL_SECS: u64 = 60;
+
+/// Re-running a command on a timer
+#[derive(clap::Args, Debug)]
+struct WatchOptions {
+    /// Keep running, fetching and printing again every this many seconds. A terminal is redrawn, otherwise output is
+    /// only printed when it changes
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        num_args = 0..=1,
+        default_missing_value = "600",
+        value_parser = parse_watch_interval
+    )]
+    watch: Option<u64>,
+}
+
+impl WatchOptions {
+    /// Prints the output of `fetch` once, or when watching, again every interval until interrupted. Failed refreshes
+    /// are logged and retried on the next interval.
+    fn run(&self, mut fetch: impl FnMut() -> anyhow::Result<String>) -> anyhow::Result<()> {
+        let Some(interval) = self.watch else {
+            print!("{}", fetch()?);
+
+            return Ok(());
+        };
+
+        let redraw = std::io::stdout().is_terminal();
+        let mut last: Option<String> = None;
+        loop {
+            match fetch() {
+                Ok(output) => {
+                    if redraw {
+                        // Clear the screen and move the cursor home before drawing
+                        print!("\x1b[2J\x1b[H{output}");
+                    } else if last.as_ref() != Some(&output) {
+                        print!("{output}");
+                    }
+                    std::io::stdout().flush()?;
+
+                    last = Some(output);
+                }
+                Err(e) => log::warn!("failed to refresh, retrying in {interval}s: {e:#}"),
+            }
+
+            std::thread::sleep(Duration::from_secs(interval));
+        }
+    }
+}
+
+/// The position a command queries
+#[derive(clap::Args, Debug)]
+struct Position {
+    /// Name of a location in the config file, e.g. `home`, used instead of lat/lon
+    #[arg(conflicts_with_all = ["lat", "lon", "zip", "place"])]
+    location: Option<String>,
+
     /// Latitude of the query position
     #[arg(long, default_value_t = 33.545)]
     lat: f32,
@@ -13,31 +195,629 @@ struct Args {
     #[arg(long, default_value_t = -117.771)]
     lon: f32,
 
-    /// OpenWeather API key
-    #[arg(short, long)]
-    api_key: String,
+    /// Zip or postal code and country code of the query position, e.g. `92651,US`, used instead of lat/lon
+    #[arg(long, value_name = "ZIP,COUNTRY", conflicts_with_all = ["lat", "lon"])]
+    zip: Option<String>,
+
+    /// Name of the query position, e.g. `Laguna Beach, CA, US`, used instead of lat/lon
+    #[arg(long, value_name = "NAME", conflicts_with_all = ["lat", "lon", "zip"])]
+    place: Option<String>,
+}
+
+impl Position {
+    /// Resolves the position, looking up a named location in the config file or geocoding a place name or zip code
+    /// if one was given. The location is named after the configured name, place, zip code, or coordinates.
+    fn resolve(
+        &self,
+        client: &OpenWeatherClient,
+        config: &Config,
+    ) -> anyhow::Result<NamedLocation> {
+        if let Some(name) = &self.location {
+            return config.location(name);
+        }
+
+        if let Some(place) = &self.place {
+            // The geocoding API wants `city,state,country` without spaces around the commas
+            let query = place
+                .split(',')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join(",");
+            let place = pick_place(place, client.geocode(&query, Some(5))?)?;
+
+            return Ok(NamedLocation::new(describe_place(&place), place.coords()));
+        }
+
+        match &self.zip {
+            Some(zip_arg) => {
+                let (zip, country) = zip_arg.split_once(',').context(
+                    "--zip must be a zip code and country code separated by a comma, e.g. `92651,US`",
+                )?;
+
+                Ok(NamedLocation::new(
+                    zip_arg.as_str(),
+                    client.geocode_zip(zip, country)?.coords(),
+                ))
+            }
+            None => {
+                let coords = GeodeticCoords::new_checked(self.lat, self.lon)?;
+
+                Ok(NamedLocation::new(
+                    format!("{},{}", coords.lat, coords.lon),
+                    coords,
+                ))
+            }
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print the current weather at a position
+    Current {
+        #[command(flatten)]
+        position: Position,
+
+        /// Print only the value at this dot-separated path in the response, e.g. `main.temp` or `weather.0.description`
+        #[arg(long, value_name = "PATH")]
+        field: Option<String>,
+
+        /// Print a compact METAR-style line instead of the summary
+        #[arg(long, conflicts_with = "field")]
+        metar: bool,
+
+        /// Also append the fetched current weather to this JSON Lines file, with the query position and fetch time
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["field", "from_file"])]
+        append_to: Option<PathBuf>,
+
+        /// Parse a saved current weather JSON response from disk instead of querying OpenWeather, it's read as
+        /// standard units unless --units or the config file say otherwise
+        #[arg(long, value_name = "PATH", conflicts_with = "watch")]
+        from_file: Option<PathBuf>,
+
+        #[command(flatten)]
+        watch: WatchOptions,
+    },
+
+    /// Print the 5 day forecast in 3 hour steps at a position
+    Forecast {
+        #[command(flatten)]
+        position: Position,
+
+        #[command(flatten)]
+        watch: WatchOptions,
+    },
+
+    /// Look up the coordinates of places matching a name
+    Geocode {
+        /// Place name, optionally with a state and country code, e.g. `Laguna Beach,CA,US`
+        query: String,
+
+        /// Most matches to print, OpenWeather returns at most 5
+        #[arg(long, default_value_t = 5)]
+        limit: u8,
+    },
+
+    /// Print the current air quality at a position
+    Air {
+        #[command(flatten)]
+        position: Position,
+
+        /// Print the hourly forecast for the next 4 days instead
+        #[arg(long)]
+        forecast: bool,
+
+        #[command(flatten)]
+        watch: WatchOptions,
+    },
+
+    /// Poll one or more locations on an interval and record every observation, retrying failures with backoff
+    Collect {
+        #[command(flatten)]
+        position: Position,
+
+        /// Location to collect as `NAME=LAT,LON`, repeat for several locations. Defaults to the query position
+        #[arg(long = "location", value_name = "NAME=LAT,LON", value_parser = parse_named_location)]
+        locations: Vec<NamedLocation>,
+
+        /// File to record observations to, created if it doesn't exist
+        #[arg(long, value_name = "PATH")]
+        store: PathBuf,
+
+        /// How observations are recorded in the store
+        #[arg(long, value_enum, default_value_t = StoreFormat::Jsonl)]
+        store_format: StoreFormat,
+
+        /// Seconds between collections of each location
+        #[arg(long, value_name = "SECONDS", default_value_t = 600)]
+        interval: u64,
+    },
+
+    /// Serve the current weather at a position as Prometheus metrics on `/metrics`
+    #[cfg(feature = "metrics")]
+    ServeMetrics {
+        /// Address to listen on, e.g. `0.0.0.0:9464`
+        addr: String,
+
+        #[command(flatten)]
+        position: Position,
+
+        /// Seconds between polls of OpenWeather
+        #[arg(long, value_name = "SECONDS", default_value_t = 600)]
+        poll_interval: u64,
+    },
+
+    /// Manage the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Write a starter config file, including the API key if one is set
+    Init {
+        /// Replace an existing config file
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum StoreFormat {
+    /// One JSON object per line
+    Jsonl,
+
+    /// A SQLite database
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+/// Writes log records to stderr with a timestamp, so long-running modes report their status
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{} {:<5} {}",
+                chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+                record.level(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
 }
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let query_position = GeodeticCoords::new_checked(args.lat, args.lon)?;
+    log::set_logger(&StderrLogger).expect("no other logger is installed");
+    log::set_max_level(log::LevelFilter::Info);
+
+    // Scaffolding the config file comes first, so a broken config file can be replaced
+    if let Command::Config {
+        command: ConfigCommand::Init { force },
+    } = args.command
+    {
+        let path = args
+            .client
+            .config
+            .or_else(config::default_path)
+            .context("couldn't find a config directory, pass --config")?;
+        config::init(&path, args.client.api_key.as_deref(), force)?;
+        println!("wrote {}", path.display());
+
+        return Ok(());
+    }
+
+    let config = Config::load(args.client.config.as_deref())?;
+    let format = args.output.format;
+    let style: &dyn SummaryStyle = if args.output.color.enabled() {
+        &AnsiSummary
+    } else {
+        &PlainSummary
+    };
+
+    match args.command {
+        Command::Current {
+            position,
+            field,
+            metar,
+            append_to,
+            from_file,
+            watch,
+        } => {
+            if format != OutputFormat::Summary && (metar || field.is_some()) {
+                return Err(anyhow!(
+                    "--format can't be combined with --metar or --field"
+                ));
+            }
+
+            if let Some(path) = from_file {
+                let body = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read `{}`", path.display()))?;
+
+                let mut response = openwx::parse_current_weather(&body).with_context(|| {
+                    format!(
+                        "`{}` is not a valid current weather response",
+                        path.display()
+                    )
+                })?;
+                if let Some(units) = args.client.units.or(config.units) {
+                    response = response.with_units(units);
+                }
+
+                print!("{}", render_current(&response, metar, format, style));
+
+                return Ok(());
+            }
+
+            let client = args.client.build(&config)?;
+            let location = position.resolve(&client, &config)?;
+
+            watch.run(|| {
+                if let Some(path) = &field {
+                    let body = client.raw_current_weather(location.coords, ResponseMode::Json)?;
+                    let json: Value = serde_json::from_str(&body)
+                        .context("the response from open weather is not valid JSON")?;
+
+                    return Ok(match resolve_field(&json, path)? {
+                        Value::String(s) => format!("{s}\n"),
+                        value => format!("{value}\n"),
+                    });
+                }
+
+                let response = client.current_weather(location.coords)?;
+
+                if let Some(path) = &append_to {
+                    JsonlSink::open(path)
+                        .and_then(|mut sink| {
+                            sink.append(&JsonlRecord::new(&location.name, response.clone()))
+                                .map_err(std::io::Error::other)
+                        })
+                        .with_context(|| format!("failed to append to `{}`", path.display()))?;
+                }
+
+                Ok(render_current(&response, metar, format, style))
+            })?;
+        }
+
+        Command::Forecast { position, watch } => {
+            let client = args.client.build(&config)?;
+            let location = position.resolve(&client, &config)?;
+
+            watch.run(|| {
+                let forecast = client.forecast(location.coords)?;
 
-    let response =
-        openwx::open_weather_request(query_position, WeatherUnits::Imperial, args.api_key)?;
+                Ok(output::render(format, &forecast, style))
+            })?;
+        }
 
-    println!("{response:#?}");
+        Command::Geocode { query, limit } => {
+            let client = args.client.build(&config)?;
 
-    let local_sunrise = response.sunrise_local().time();
-    let local_sunset = response.sunset_local().time();
+            let places = client.geocode(&query, Some(limit))?;
 
-    println!("Sunrise: {local_sunrise}");
-    println!("Sunset: {local_sunset}");
-    println!(
-        "Wind coming from: {}, blowing towards: {}",
-        response.wind.deg.compass_point(),
-        response.wind.deg.blowing_towards()
-    );
+            print!("{}", output::render(format, &places, style));
+        }
+
+        Command::Air {
+            position,
+            forecast,
+            watch,
+        } => {
+            let client = args.client.build(&config)?;
+            let location = position.resolve(&client, &config)?;
+
+            watch.run(|| {
+                let response = if forecast {
+                    client.air_pollution_forecast(location.coords)?
+                } else {
+                    client.air_pollution(location.coords)?
+                };
+
+                Ok(output::render(format, &response, style))
+            })?;
+        }
+
+        Command::Collect {
+            position,
+            mut locations,
+            store,
+            store_format,
+            interval,
+        } => {
+            let client = args.client.build(&config)?;
+            if locations.is_empty() {
+                locations.push(position.resolve(&client, &config)?);
+            }
+
+            let collector = match store_format {
+                StoreFormat::Jsonl => {
+                    let sink = JsonlSink::open(&store)
+                        .with_context(|| format!("failed to open `{}`", store.display()))?;
+                    Collector::new(client, locations, sink)
+                }
+                #[cfg(feature = "sqlite")]
+                StoreFormat::Sqlite => {
+                    let sink = openwx::ObservationStore::open(&store)
+                        .with_context(|| format!("failed to open `{}`", store.display()))?;
+                    Collector::new(client, locations, sink)
+                }
+            };
+
+            collector.interval(Duration::from_secs(interval)).run();
+        }
+
+        Command::Config { .. } => unreachable!("handled before loading the config file"),
+
+        #[cfg(feature = "metrics")]
+        Command::ServeMetrics {
+            position,
+            addr,
+            poll_interval,
+        } => {
+            let client = args.client.build(&config)?;
+            let location = position.resolve(&client, &config)?;
+            let listener = std::net::TcpListener::bind(&addr)
+                .with_context(|| format!("failed to listen on `{addr}`"))?;
+
+            openwx::MetricsExporter::new(client, vec![location])
+                .poll_interval(Duration::from_secs(poll_interval))
+                .serve(listener)?;
+        }
+    }
 
     Ok(())
 }
+
+/// Renders the current weather as a METAR-style line or in an output format
+fn render_current(
+    response: &OWCurrentWeatherResponse,
+    metar: bool,
+    format: OutputFormat,
+    style: &dyn SummaryStyle,
+) -> String {
+    if metar {
+        format!("{}\n", response.metar())
+    } else {
+        output::render(format, response, style)
+    }
+}
+
+/// Chooses the place a `--place` name refers to. OpenWeather often lists the same place more than once with slightly
+/// different coordinates, so matches only count as ambiguous if they differ by name, state, or country.
+fn pick_place(query: &str, mut candidates: Vec<GeoLocation>) -> anyhow::Result<GeoLocation> {
+    let mut distinct: Vec<String> = Vec::new();
+    for description in candidates.iter().map(describe_place) {
+        if !distinct.contains(&description) {
+            distinct.push(description);
+        }
+    }
+
+    match distinct.len() {
+        0 => Err(anyhow!("no places match `{query}`")),
+        1 => Ok(candidates.swap_remove(0)),
+        _ => Err(anyhow!(
+            "`{query}` matches several places, add a state or country code to pick one, e.g. `{}`:\n  {}",
+            distinct[0],
+            distinct.join("\n  ")
+        )),
+    }
+}
+
+/// Parses a `NAME=LAT,LON` location argument
+fn parse_named_location(arg: &str) -> anyhow::Result<NamedLocation> {
+    let (name, coords) = arg
+        .split_once('=')
+        .context("a location must be a name and coordinates, e.g. `home=33.545,-117.771`")?;
+    let (lat, lon) = coords
+        .split_once(',')
+        .context("coordinates must be a latitude and longitude separated by a comma")?;
+
+    let coords = GeodeticCoords::new_checked(lat.trim().parse()?, lon.trim().parse()?)?;
+
+    Ok(NamedLocation::new(name, coords))
+}
+
+/// Parses a `--watch` interval, refusing intervals shorter than [`MIN_WATCH_INTERVAL_SECS`]
+fn parse_watch_interval(arg: &str) -> anyhow::Result<u64> {
+    let secs: u64 = arg.parse()?;
+    if secs < MIN_WATCH_INTERVAL_SECS {
+        return Err(anyhow!(
+            "must be at least {MIN_WATCH_INTERVAL_SECS} seconds, OpenWeather only refreshes its data every 10 minutes"
+        ));
+    }
+
+    Ok(secs)
+}
+
+/// Walks a dot-separated path through a JSON response, numeric segments index into arrays.
+fn resolve_field<'a>(json: &'a Value, path: &str) -> anyhow::Result<&'a Value> {
+    let mut current = json;
+
+    for segment in path.split('.') {
+        let next = match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+
+        current = next.ok_or_else(|| {
+            let top_level = json
+                .as_object()
+                .map(|map| map.keys().cloned().collect::<Vec<_>>().join(", "))
+                .unwrap_or_default();
+
+            anyhow!(
+                "no field `{segment}` in path `{path}`, valid top-level fields are: {top_level}"
+            )
+        })?;
+    }
+
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Value {
+        serde_json::json!({
+            "main": { "temp": 281.29 },
+            "weather": [{ "description": "broken clouds" }],
+            "wind": { "deg": 202 }
+        })
+    }
+
+    #[test]
+    fn resolve_valid_paths() {
+        let json = sample();
+
+        assert_eq!(resolve_field(&json, "main.temp").unwrap(), 281.29);
+        assert_eq!(
+            resolve_field(&json, "weather.0.description").unwrap(),
+            "broken clouds"
+        );
+        assert_eq!(resolve_field(&json, "wind.deg").unwrap(), 202);
+    }
+
+    #[test]
+    fn cli_is_valid() {
+        use clap::CommandFactory;
+
+        Args::command().debug_assert();
+    }
+
+    fn place(name: &str, state: Option<&str>, lat: f32) -> GeoLocation {
+        GeoLocation {
+            name: name.into(),
+            local_names: None,
+            lat,
+            lon: -117.78,
+            country: "US".into(),
+            state: state.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn pick_unambiguous_place() {
+        let candidates = vec![
+            place("Laguna Beach", Some("California"), 33.54),
+            place("Laguna Beach", Some("California"), 33.55),
+        ];
+
+        let picked = pick_place("Laguna Beach", candidates).unwrap();
+        assert_eq!(picked.lat, 33.54);
+        assert_eq!(describe_place(&picked), "Laguna Beach, California, US");
+
+        assert!(pick_place("Nowhere", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn pick_ambiguous_place() {
+        let candidates = vec![
+            place("Springfield", Some("Illinois"), 39.8),
+            place("Springfield", Some("Missouri"), 37.2),
+        ];
+
+        let err = pick_place("Springfield", candidates).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "`Springfield` matches several places, add a state or country code to pick one, e.g. \
+            `Springfield, Illinois, US`:\n  Springfield, Illinois, US\n  Springfield, Missouri, US"
+        );
+    }
+
+    #[test]
+    fn parse_locations() {
+        let location = parse_named_location("home=33.545, -117.771").unwrap();
+        assert_eq!(location.name, "home");
+        assert_eq!(location.coords.lon, -117.771);
+
+        assert!(parse_named_location("33.545,-117.771").is_err());
+        assert!(parse_named_location("home=33.545").is_err());
+        assert!(parse_named_location("home=95,0").is_err());
+    }
+
+    #[test]
+    fn watch_intervals() {
+        assert_eq!(parse_watch_interval("600").unwrap(), 600);
+        assert!(parse_watch_interval("10").is_err());
+        assert!(parse_watch_interval("soon").is_err());
+
+        let args = Args::try_parse_from(["openwx", "forecast", "--watch"]).unwrap();
+        let Command::Forecast { watch, .. } = args.command else {
+            panic!("parsed {:?}", args.command);
+        };
+        assert_eq!(watch.watch, Some(600));
+    }
+
+    #[test]
+    fn lang_flag_overrides_config() {
+        let path = std::env::temp_dir().join(format!("openwx-lang-{}.toml", std::process::id()));
+        std::fs::write(&path, "api_key = \"key\"\nlang = \"klingon\"\n").unwrap();
+        let config = Config::load(Some(&path)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // The configured language is only checked when it's used
+        let args = Args::try_parse_from(["openwx", "--lang", "it", "forecast"]).unwrap();
+        assert!(args.client.build(&config).is_ok());
+
+        let args = Args::try_parse_from(["openwx", "forecast"]).unwrap();
+        assert!(args.client.build(&config).is_err());
+    }
+
+    #[test]
+    fn collect_args() {
+        let args = Args::try_parse_from([
+            "openwx",
+            "collect",
+            "--location",
+            "home=33.545,-117.771",
+            "--store",
+            "observations.jsonl",
+            "--store-format",
+            "jsonl",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(args.output.format, OutputFormat::Json);
+
+        let Command::Collect {
+            locations,
+            store,
+            store_format,
+            interval,
+            ..
+        } = args.command
+        else {
+            panic!("parsed {:?}", args.command);
+        };
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].name, "home");
+        assert_eq!(store, PathBuf::from("observations.jsonl"));
+        assert!(matches!(store_format, StoreFormat::Jsonl));
+        assert_eq!(interval, 600);
+    }
+
+    #[test]
+    fn resolve_invalid_path() {
+        let err = resolve_field(&sample(), "main.nope").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "no field `nope` in path `main.nope`, valid top-level fields are: main, weather, wind"
+        );
+
+        assert!(resolve_field(&sample(), "weather.3").is_err());
+    }
+}
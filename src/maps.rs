@@ -0,0 +1,313 @@
+//! OpenWeather's weather map tiles, which overlay weather layers on standard web map tiles
+
+use crate::{OpenWeatherClient, OpenWxError, WeatherUnits, client::append_query_param};
+use chrono::{DateTime, Utc};
+use strum::Display;
+use thiserror::Error;
+
+/// Host serving OpenWeather's map tiles
+pub const TILE_BASE_URL: &str = "https://tile.openweathermap.org";
+
+/// Host serving OpenWeather's Weather Maps 2.0 tiles
+pub const MAPS_V2_BASE_URL: &str = "https://maps.openweathermap.org";
+
+/// Weather layers available as Weather Maps 1.0 tiles, more details [here](https://openweathermap.org/api/weathermaps)
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum MapLayer {
+    #[strum(serialize = "clouds_new")]
+    Clouds,
+    #[strum(serialize = "precipitation_new")]
+    Precipitation,
+    #[strum(serialize = "pressure_new")]
+    Pressure,
+    #[strum(serialize = "wind_new")]
+    Wind,
+    #[strum(serialize = "temp_new")]
+    Temperature,
+}
+
+/// Weather layers available as Weather Maps 2.0 tiles, more details
+/// [here](https://openweathermap.org/api/weather-map-2)
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum MapLayerV2 {
+    /// Convective precipitation, mm
+    #[strum(serialize = "PAC0")]
+    ConvectivePrecipitation,
+    /// Precipitation intensity, mm/s
+    #[strum(serialize = "PR0")]
+    PrecipitationIntensity,
+    /// Accumulated precipitation, mm
+    #[strum(serialize = "PA0")]
+    AccumulatedPrecipitation,
+    /// Accumulated precipitation of rain, mm
+    #[strum(serialize = "PAR0")]
+    AccumulatedRain,
+    /// Accumulated precipitation of snow, mm
+    #[strum(serialize = "PAS0")]
+    AccumulatedSnow,
+    /// Depth of snow, m
+    #[strum(serialize = "SD0")]
+    SnowDepth,
+    /// Wind speed at an altitude of 10 meters, m/s
+    #[strum(serialize = "WS10")]
+    WindSpeed,
+    /// Joint display of speed wind and wind direction at an altitude of 10 meters, m/s
+    #[strum(serialize = "WND")]
+    Wind,
+    /// Atmospheric pressure on mean sea level, hPa
+    #[strum(serialize = "APM")]
+    Pressure,
+    /// Air temperature at a height of 2 meters, °C
+    #[strum(serialize = "TA2")]
+    Temperature,
+    /// Temperature of a dew point, °C
+    #[strum(serialize = "TD2")]
+    DewPoint,
+    /// Soil temperature 0-10 cm, K
+    #[strum(serialize = "TS0")]
+    SoilTemperatureShallow,
+    /// Soil temperature more than 10 cm, K
+    #[strum(serialize = "TS10")]
+    SoilTemperatureDeep,
+    /// Relative humidity, %
+    #[strum(serialize = "HRD0")]
+    Humidity,
+    /// Cloudiness, %
+    #[strum(serialize = "CL")]
+    Clouds,
+}
+
+/// A color stop in a custom [`MapOptions::palette`], values at or above `value` are drawn in `rgb`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteStop {
+    pub value: f32,
+    pub rgb: [u8; 3],
+}
+
+/// Optional rendering settings for Weather Maps 2.0 tiles, the defaults leave everything up to OpenWeather
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MapOptions {
+    /// Time to show the layer at, defaults to now. History and forecasts are available depending on the plan
+    pub date: Option<DateTime<Utc>>,
+
+    /// Opacity of the layer from 0 (transparent) to 1 (opaque)
+    pub opacity: Option<f32>,
+
+    /// Custom color palette replacing the layer's default, in ascending order of value
+    pub palette: Vec<PaletteStop>,
+
+    /// Color every tile pixel, including values outside the palette
+    pub fill_bound: bool,
+}
+
+impl MapOptions {
+    /// Query parameters for the options which were set
+    fn query_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+
+        if let Some(date) = self.date {
+            params.push(("date", date.timestamp().to_string()));
+        }
+
+        if let Some(opacity) = self.opacity {
+            params.push(("opacity", opacity.to_string()));
+        }
+
+        if !self.palette.is_empty() {
+            let stops: Vec<String> = self
+                .palette
+                .iter()
+                .map(|stop| {
+                    let [r, g, b] = stop.rgb;
+                    format!("{}:{r:02X}{g:02X}{b:02X}", stop.value)
+                })
+                .collect();
+            params.push(("palette", stops.join(";")));
+        }
+
+        if self.fill_bound {
+            params.push(("fill_bound", "true".to_string()));
+        }
+
+        params
+    }
+}
+
+/// A tile in the standard web map (slippy map) tiling scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tile {
+    zoom: u8,
+    x: u32,
+    y: u32,
+}
+
+#[derive(Error, Debug)]
+pub enum TileError {
+    #[error("provided zoom level of `{0}` is outside the valid range [0, 20]")]
+    InvalidZoom(u8),
+
+    #[error("provided tile `{x}/{y}` doesn't exist at zoom level `{zoom}`")]
+    InvalidTile { zoom: u8, x: u32, y: u32 },
+}
+
+/// Deepest zoom level OpenWeather serves tiles for
+const MAX_ZOOM: u8 = 20;
+
+impl Tile {
+    /// Validates the tile exists at the zoom level, there are 2^zoom tiles along each axis.
+    pub fn new_checked(zoom: u8, x: u32, y: u32) -> Result<Self, TileError> {
+        if zoom > MAX_ZOOM {
+            return Err(TileError::InvalidZoom(zoom));
+        }
+
+        let tiles_per_axis = 1u32 << zoom;
+        if x >= tiles_per_axis || y >= tiles_per_axis {
+            return Err(TileError::InvalidTile { zoom, x, y });
+        }
+
+        Ok(Tile { zoom, x, y })
+    }
+
+    pub fn zoom(&self) -> u8 {
+        self.zoom
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+}
+
+/// Full URL of a PNG map tile including the API key, suitable for handing straight to a web map library.
+pub fn tile_url(layer: MapLayer, tile: Tile, api_key: &str) -> String {
+    append_query_param(&unauthenticated_tile_url(layer, tile), "appid", api_key)
+}
+
+/// URL of a map tile before the API key is added
+fn unauthenticated_tile_url(layer: MapLayer, tile: Tile) -> String {
+    format!(
+        "{TILE_BASE_URL}/map/{layer}/{}/{}/{}.png",
+        tile.zoom, tile.x, tile.y
+    )
+}
+
+/// Full URL of a Weather Maps 2.0 PNG tile including the API key, suitable for handing straight to a web map library.
+pub fn tile_url_v2(layer: MapLayerV2, tile: Tile, options: &MapOptions, api_key: &str) -> String {
+    append_query_param(
+        &unauthenticated_tile_url_v2(layer, tile, options),
+        "appid",
+        api_key,
+    )
+}
+
+/// URL of a Weather Maps 2.0 tile before the API key is added
+fn unauthenticated_tile_url_v2(layer: MapLayerV2, tile: Tile, options: &MapOptions) -> String {
+    let mut url = format!(
+        "{MAPS_V2_BASE_URL}/maps/2.0/weather/{layer}/{}/{}/{}",
+        tile.zoom, tile.x, tile.y
+    );
+
+    for (name, value) in options.query_params() {
+        url = append_query_param(&url, name, &value);
+    }
+
+    url
+}
+
+/// Fetch a map tile from OpenWeather as PNG bytes, this is a blocking HTTP request.
+pub fn open_weather_map_tile(
+    layer: MapLayer,
+    tile: Tile,
+    api_key: String,
+) -> Result<Vec<u8>, OpenWxError> {
+    OpenWeatherClient::new(api_key, WeatherUnits::Standard).map_tile(layer, tile)
+}
+
+impl OpenWeatherClient {
+    /// Fetch a map tile as PNG bytes, this is a blocking HTTP request. Tiles always come from [`TILE_BASE_URL`]
+    /// regardless of the client's base URL.
+    pub fn map_tile(&self, layer: MapLayer, tile: Tile) -> Result<Vec<u8>, OpenWxError> {
+        self.get_bytes(unauthenticated_tile_url(layer, tile))
+    }
+
+    /// Fetch a Weather Maps 2.0 tile as PNG bytes, this is a blocking HTTP request. Tiles always come from
+    /// [`MAPS_V2_BASE_URL`] regardless of the client's base URL.
+    pub fn map_tile_v2(
+        &self,
+        layer: MapLayerV2,
+        tile: Tile,
+        options: &MapOptions,
+    ) -> Result<Vec<u8>, OpenWxError> {
+        self.get_bytes(unauthenticated_tile_url_v2(layer, tile, options))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::FakeTransport;
+
+    #[test]
+    fn tile_urls() {
+        let tile = Tile::new_checked(3, 1, 5).unwrap();
+
+        assert_eq!(
+            tile_url(MapLayer::Precipitation, tile, "key"),
+            "https://tile.openweathermap.org/map/precipitation_new/3/1/5.png?appid=key"
+        );
+
+        let transport = FakeTransport::serving("png");
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            transport.clone(),
+        );
+        assert_eq!(client.map_tile(MapLayer::Clouds, tile).unwrap(), b"png");
+        assert_eq!(
+            transport.requested_urls()[0],
+            "https://tile.openweathermap.org/map/clouds_new/3/1/5.png?appid=key"
+        );
+    }
+
+    #[test]
+    fn tile_urls_v2() {
+        let tile = Tile::new_checked(2, 1, 1).unwrap();
+
+        assert_eq!(
+            tile_url_v2(MapLayerV2::Temperature, tile, &MapOptions::default(), "key"),
+            "https://maps.openweathermap.org/maps/2.0/weather/TA2/2/1/1?appid=key"
+        );
+
+        let options = MapOptions {
+            date: DateTime::from_timestamp(1552861800, 0),
+            opacity: Some(0.6),
+            palette: vec![
+                PaletteStop {
+                    value: -65.0,
+                    rgb: [0x82, 0x16, 0x92],
+                },
+                PaletteStop {
+                    value: 30.0,
+                    rgb: [0xFC, 0x80, 0x14],
+                },
+            ],
+            fill_bound: true,
+        };
+        assert_eq!(
+            tile_url_v2(MapLayerV2::Temperature, tile, &options, "key"),
+            "https://maps.openweathermap.org/maps/2.0/weather/TA2/2/1/1?date=1552861800&opacity=0.6\
+            &palette=-65:821692;30:FC8014&fill_bound=true&appid=key"
+        );
+    }
+
+    #[test]
+    fn invalid_tiles() {
+        assert!(Tile::new_checked(0, 0, 0).is_ok());
+        assert!(Tile::new_checked(0, 1, 0).is_err());
+        assert!(Tile::new_checked(2, 3, 4).is_err());
+        assert!(Tile::new_checked(21, 0, 0).is_err());
+    }
+}
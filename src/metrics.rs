@@ -0,0 +1,339 @@
+//! A Prometheus exporter which polls the current weather at a set of locations on an interval and serves the latest
+//! observations as gauges on an HTTP `/metrics` endpoint
+
+use crate::{NamedLocation, OWCurrentWeatherResponse, OpenWeatherClient};
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// OpenWeather refreshes its data every 10 minutes, polling any faster just spends API calls
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Requests are answered one at a time, so a client which stops sending or reading is dropped after this long
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A gauge exported for every observation, values are always in SI units regardless of the client's units. Units
+/// are given in the help text rather than the name.
+struct Gauge {
+    name: &'static str,
+    help: &'static str,
+    value: fn(&OWCurrentWeatherResponse) -> Option<f64>,
+}
+
+const GAUGES: [Gauge; 8] = [
+    Gauge {
+        name: "openwx_temperature",
+        help: "Air temperature in degrees Celsius",
+        value: |r| Some(r.main.temp.to_celsius().into()),
+    },
+    Gauge {
+        name: "openwx_feels_like",
+        help: "Apparent temperature accounting for humidity and wind, in degrees Celsius",
+        value: |r| Some(r.main.feels_like.to_celsius().into()),
+    },
+    Gauge {
+        name: "openwx_humidity",
+        help: "Relative humidity in percent",
+        value: |r| Some(r.main.humidity.value().into()),
+    },
+    Gauge {
+        name: "openwx_pressure",
+        help: "Atmospheric pressure at sea level in hectopascals",
+        value: |r| Some(r.main.sea_level.hpa().into()),
+    },
+    Gauge {
+        name: "openwx_wind_speed",
+        help: "Sustained wind speed in meters per second",
+        value: |r| r.wind().map(|w| w.speed.to_mps().into()),
+    },
+    Gauge {
+        name: "openwx_cloudiness",
+        help: "Cloud cover in percent",
+        value: |r| Some(r.clouds.all.value().into()),
+    },
+    Gauge {
+        name: "openwx_visibility",
+        help: "Visibility in meters, capped at 10 km",
+        value: |r| Some(r.visibility.into()),
+    },
+    Gauge {
+        name: "openwx_observation_timestamp",
+        help: "Unix time in seconds the observation was made",
+        value: |r| Some(r.dt as f64),
+    },
+];
+
+/// Renders observations in the Prometheus text exposition format, one sample per gauge per location labelled with
+/// the location's name. Gauges which don't apply to an observation, e.g. wind speed when no wind was reported, are
+/// left out for that location.
+pub fn render_metrics(observations: &[(&str, &OWCurrentWeatherResponse)]) -> String {
+    let mut text = String::new();
+
+    for gauge in &GAUGES {
+        let _ = writeln!(text, "# HELP {} {}", gauge.name, gauge.help);
+        let _ = writeln!(text, "# TYPE {} gauge", gauge.name);
+
+        for (location, response) in observations {
+            if let Some(value) = (gauge.value)(response) {
+                let _ = writeln!(
+                    text,
+                    "{}{{location=\"{}\"}} {value}",
+                    gauge.name,
+                    escape_label(location)
+                );
+            }
+        }
+    }
+
+    text
+}
+
+/// Escapes a label value per the exposition format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+/// Latest state of a polled location
+#[derive(Debug, Default)]
+struct LocationState {
+    observation: Option<OWCurrentWeatherResponse>,
+    poll_errors: u64,
+}
+
+/// Polls the current weather at each of its locations and serves the results to Prometheus, labelled with the
+/// location names. Build with [`MetricsExporter::new`] and start with [`MetricsExporter::serve`].
+pub struct MetricsExporter {
+    client: OpenWeatherClient,
+    locations: Vec<NamedLocation>,
+    poll_interval: Duration,
+    request_timeout: Duration,
+    state: Mutex<Vec<LocationState>>,
+}
+
+impl MetricsExporter {
+    /// Creates an exporter polling the given locations every [`DEFAULT_POLL_INTERVAL`].
+    pub fn new(client: OpenWeatherClient, locations: Vec<NamedLocation>) -> Self {
+        let state = locations.iter().map(|_| LocationState::default()).collect();
+
+        MetricsExporter {
+            client,
+            locations,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Poll the locations on this interval instead of the default.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Give up on reading a request or writing its response after this long instead of
+    /// [`DEFAULT_REQUEST_TIMEOUT`].
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Fetches the current weather at every location once, this makes blocking HTTP requests. A failed fetch keeps
+    /// the previous observation and bumps the location's `openwx_poll_errors_total` counter.
+    pub fn poll(&self) {
+        for (i, location) in self.locations.iter().enumerate() {
+            let result = self.client.current_weather(location.coords);
+
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            match result {
+                Ok(response) => state[i].observation = Some(response),
+                Err(e) => {
+                    log::warn!("failed to poll the weather at `{}`: {e}", location.name);
+                    state[i].poll_errors += 1;
+                }
+            }
+        }
+    }
+
+    /// The body served on `/metrics`: the gauges from [`render_metrics`] for every location polled successfully so
+    /// far, plus a poll error counter for every location.
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let observations: Vec<_> = self
+            .locations
+            .iter()
+            .zip(state.iter())
+            .filter_map(|(location, s)| Some((location.name.as_str(), s.observation.as_ref()?)))
+            .collect();
+        let mut text = render_metrics(&observations);
+
+        text.push_str("# HELP openwx_poll_errors_total Failed attempts to fetch the weather\n");
+        text.push_str("# TYPE openwx_poll_errors_total counter\n");
+        for (location, s) in self.locations.iter().zip(state.iter()) {
+            let _ = writeln!(
+                text,
+                "openwx_poll_errors_total{{location=\"{}\"}} {}",
+                escape_label(&location.name),
+                s.poll_errors
+            );
+        }
+
+        text
+    }
+
+    /// Polls every location once, then keeps polling on a background thread while answering HTTP requests on the
+    /// listener. `GET /metrics` returns the rendered metrics, anything else is a 404. Only returns if accepting a
+    /// connection fails.
+    pub fn serve(self, listener: TcpListener) -> io::Result<()> {
+        self.poll();
+
+        let exporter = Arc::new(self);
+        let poller = Arc::clone(&exporter);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(poller.poll_interval);
+                poller.poll();
+            }
+        });
+
+        for stream in listener.incoming() {
+            if let Err(e) = exporter.respond(stream?) {
+                log::warn!("failed to answer a metrics request: {e}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Answers a single HTTP/1.x request and closes the connection.
+    fn respond(&self, stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(self.request_timeout))?;
+        stream.set_write_timeout(Some(self.request_timeout))?;
+
+        let mut reader = BufReader::new(&stream);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+
+        // Drain the headers, nothing in them changes the response
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or_default();
+        let path = parts.next().unwrap_or_default();
+        let path = path.split_once('?').map_or(path, |(path, _)| path);
+
+        let (status, body) = if method == "GET" && path == "/metrics" {
+            ("200 OK", self.render())
+        } else {
+            (
+                "404 Not Found",
+                "not found, metrics are served on /metrics\n".into(),
+            )
+        };
+
+        let mut stream = &stream;
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::FakeTransport;
+    use crate::types::tests::{ZOCCA_RESPONSE, zocca};
+    use crate::{GeodeticCoords, WeatherUnits};
+    use std::io::Read;
+
+    fn exporter() -> MetricsExporter {
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            WeatherUnits::Metric,
+            FakeTransport::serving(ZOCCA_RESPONSE),
+        );
+
+        MetricsExporter::new(
+            client,
+            vec![NamedLocation::new(
+                "zocca",
+                GeodeticCoords::new_checked(44.34, 10.99).unwrap(),
+            )],
+        )
+    }
+
+    #[test]
+    fn render_sample() {
+        let response = zocca();
+        let text = render_metrics(&[("zocca", &response)]);
+
+        assert!(text.contains("# HELP openwx_temperature Air temperature in degrees Celsius\n"));
+        assert!(text.contains("# TYPE openwx_temperature gauge\n"));
+        assert!(text.contains("openwx_humidity{location=\"zocca\"} 95\n"));
+        assert!(text.contains("openwx_wind_speed{location=\"zocca\"} 2.69"));
+        assert!(text.contains("openwx_observation_timestamp{location=\"zocca\"} 1763077522\n"));
+    }
+
+    #[test]
+    fn render_escapes_labels() {
+        let response = zocca();
+        let text = render_metrics(&[("say \"hi\"", &response)]);
+
+        assert!(text.contains("openwx_humidity{location=\"say \\\"hi\\\"\"} 95\n"));
+    }
+
+    #[test]
+    fn serves_metrics() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || exporter().serve(listener));
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let metrics = get("/metrics");
+        assert!(metrics.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(metrics.contains("openwx_humidity{location=\"zocca\"} 95\n"));
+        assert!(metrics.contains("openwx_poll_errors_total{location=\"zocca\"} 0\n"));
+
+        assert!(get("/").starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+
+    #[test]
+    fn idle_clients_time_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let exporter = exporter().request_timeout(Duration::from_millis(100));
+        std::thread::spawn(move || exporter.serve(listener));
+
+        // A client which never sends its request doesn't hold up the next one
+        let _idle = TcpStream::connect(addr).unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        write!(stream, "GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    }
+}
@@ -0,0 +1,556 @@
+//! Publishing observations to an MQTT broker, for feeding home automation systems. This speaks just enough MQTT
+//! 3.1.1 to connect and publish at QoS 0, which is all a periodic sensor reading needs.
+
+use crate::{GeodeticCoords, OWCurrentWeatherResponse, OpenWeatherClient, WeatherUnits};
+use serde_json::json;
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use thiserror::Error;
+
+/// The standard unencrypted MQTT port
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// Topic observations are published to unless configured otherwise
+pub const DEFAULT_MQTT_TOPIC: &str = "openwx/current";
+
+/// Topic prefix Home Assistant listens for discovery messages under unless configured otherwise
+pub const DEFAULT_DISCOVERY_PREFIX: &str = "homeassistant";
+
+/// How long to wait on the broker to accept a connection, acknowledge it, or take a packet unless configured
+/// otherwise
+pub const DEFAULT_MQTT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Seconds the broker waits without hearing from us before dropping the connection, we only stay connected long
+/// enough to publish so this is generous.
+const KEEP_ALIVE_SECS: u16 = 60;
+
+const CONNECT: u8 = 0x10;
+const CONNACK: u8 = 0x20;
+const PUBLISH: u8 = 0x30;
+const DISCONNECT: u8 = 0xE0;
+
+/// A sensor announced to Home Assistant, reading one field of the published observation
+struct HomeAssistantSensor {
+    object_id: &'static str,
+    name: &'static str,
+    device_class: &'static str,
+
+    /// Path to the value in the observation JSON. The template falls back to `none`, which Home Assistant shows as
+    /// unknown, when the value is missing, e.g. `wind` is null for stations which don't report it.
+    path: &'static str,
+    unit: fn(WeatherUnits) -> &'static str,
+}
+
+const HOME_ASSISTANT_SENSORS: [HomeAssistantSensor; 4] = [
+    HomeAssistantSensor {
+        object_id: "temperature",
+        name: "Temperature",
+        device_class: "temperature",
+        path: "main.temp",
+        unit: |units| units.temperature_symbol(),
+    },
+    HomeAssistantSensor {
+        object_id: "humidity",
+        name: "Humidity",
+        device_class: "humidity",
+        path: "main.humidity",
+        unit: |_| "%",
+    },
+    HomeAssistantSensor {
+        object_id: "pressure",
+        name: "Pressure",
+        device_class: "atmospheric_pressure",
+        path: "main.pressure",
+        unit: |_| "hPa",
+    },
+    HomeAssistantSensor {
+        object_id: "wind_speed",
+        name: "Wind speed",
+        device_class: "wind_speed",
+        path: "wind.speed",
+        unit: |units| units.wind_speed_symbol(),
+    },
+];
+
+#[derive(Error, Debug)]
+pub enum MqttError {
+    #[error("failed to talk to the MQTT broker")]
+    Io(#[from] io::Error),
+
+    #[error("the MQTT broker refused the connection with return code {0}")]
+    ConnectionRefused(u8),
+
+    #[error("expected a CONNACK from the MQTT broker but got packet type `{0:#04x}`")]
+    UnexpectedPacket(u8),
+
+    #[error("`{0}` is not a valid topic to publish to")]
+    InvalidTopic(String),
+
+    #[error("packet of {0} bytes is too large for MQTT")]
+    PacketTooLarge(usize),
+}
+
+/// Where and how to publish observations. Create with [`MqttOptions::new`] and adjust with the `with_*` methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttOptions {
+    pub host: String,
+    pub port: u16,
+    pub client_id: String,
+    pub credentials: Option<(String, String)>,
+    pub topic: String,
+
+    /// Topic prefix Home Assistant discovery messages are published under
+    pub discovery_prefix: String,
+
+    /// Ask the broker to hold on to the latest observation for subscribers which connect later
+    pub retain: bool,
+
+    /// Give up on an unresponsive broker after this long
+    pub timeout: Duration,
+}
+
+impl MqttOptions {
+    /// Publishes to [`DEFAULT_MQTT_TOPIC`] on the broker at `host`, on the default port, retaining the latest
+    /// observation.
+    pub fn new(host: impl Into<String>) -> Self {
+        MqttOptions {
+            host: host.into(),
+            port: DEFAULT_MQTT_PORT,
+            client_id: "openwx".into(),
+            credentials: None,
+            topic: DEFAULT_MQTT_TOPIC.into(),
+            discovery_prefix: DEFAULT_DISCOVERY_PREFIX.into(),
+            retain: true,
+            timeout: DEFAULT_MQTT_TIMEOUT,
+        }
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = client_id.into();
+        self
+    }
+
+    /// Authenticate with the broker using a username and password.
+    pub fn with_credentials(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.credentials = Some((username.into(), password.into()));
+        self
+    }
+
+    pub fn with_topic(mut self, topic: impl Into<String>) -> Self {
+        self.topic = topic.into();
+        self
+    }
+
+    pub fn with_discovery_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.discovery_prefix = prefix.into();
+        self
+    }
+
+    pub fn with_retain(mut self, retain: bool) -> Self {
+        self.retain = retain;
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+/// Sends observations to an MQTT broker as JSON. Each publish opens a fresh connection, so a broker restart between
+/// scheduled publishes goes unnoticed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttPublisher {
+    options: MqttOptions,
+}
+
+impl MqttPublisher {
+    pub fn new(options: MqttOptions) -> Self {
+        MqttPublisher { options }
+    }
+
+    /// Publishes a single message to a topic, this is a blocking network call.
+    pub fn publish(&self, topic: &str, payload: &[u8], retain: bool) -> Result<(), MqttError> {
+        self.publish_all(&[(topic.to_string(), payload.to_vec())], retain)
+    }
+
+    /// Publishes several messages over a single connection, this is a blocking network call.
+    fn publish_all(&self, messages: &[(String, Vec<u8>)], retain: bool) -> Result<(), MqttError> {
+        if let Some((topic, _)) = messages
+            .iter()
+            .find(|(topic, _)| topic.is_empty() || topic.contains(['+', '#']))
+        {
+            return Err(MqttError::InvalidTopic(topic.clone()));
+        }
+
+        let mut stream = self.connect()?;
+
+        stream.write_all(&self.connect_packet()?)?;
+        let (packet_type, body) = read_packet(&mut stream)?;
+        if packet_type & 0xF0 != CONNACK {
+            return Err(MqttError::UnexpectedPacket(packet_type));
+        }
+        match body.get(1) {
+            Some(0) => {}
+            Some(&code) => return Err(MqttError::ConnectionRefused(code)),
+            None => return Err(MqttError::UnexpectedPacket(packet_type)),
+        }
+
+        for (topic, payload) in messages {
+            let mut publish = encode_string(topic)?;
+            publish.extend_from_slice(payload);
+            stream.write_all(&packet(PUBLISH | u8::from(retain), &publish)?)?;
+        }
+        stream.write_all(&[DISCONNECT, 0])?;
+
+        Ok(())
+    }
+
+    /// Publishes an observation as JSON to the configured topic, this is a blocking network call.
+    pub fn publish_observation(
+        &self,
+        response: &OWCurrentWeatherResponse,
+    ) -> Result<(), MqttError> {
+        let payload = serde_json::to_vec(response).expect("responses always serialize to JSON");
+
+        self.publish(&self.options.topic, &payload, self.options.retain)
+    }
+
+    /// Fetches the current weather at a position and publishes it every `interval`, forever. Failures to fetch or
+    /// publish are logged and retried on the next tick rather than ending the schedule.
+    pub fn publish_every(
+        &self,
+        client: &OpenWeatherClient,
+        coords: GeodeticCoords,
+        interval: Duration,
+    ) -> ! {
+        loop {
+            match client.current_weather(coords) {
+                Ok(response) => {
+                    if let Err(e) = self.publish_observation(&response) {
+                        log::warn!(
+                            "failed to publish the weather to `{}`: {e}",
+                            self.options.topic
+                        );
+                    }
+                }
+                Err(e) => log::warn!("failed to fetch the weather for publishing: {e}"),
+            }
+
+            std::thread::sleep(interval);
+        }
+    }
+
+    /// Home Assistant MQTT discovery messages announcing temperature, humidity, pressure, and wind speed sensors
+    /// which read their state from the observations on the configured topic, as (config topic, JSON payload)
+    /// pairs. `units` must match the client the observations are fetched with, since the payloads carry bare
+    /// numbers.
+    pub fn home_assistant_discovery(&self, units: WeatherUnits) -> Vec<(String, String)> {
+        let node_id: String = self
+            .options
+            .client_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        HOME_ASSISTANT_SENSORS
+            .iter()
+            .map(|sensor| {
+                let topic = format!(
+                    "{}/sensor/{node_id}/{}/config",
+                    self.options.discovery_prefix, sensor.object_id
+                );
+                let payload = json!({
+                    "name": sensor.name,
+                    "unique_id": format!("{node_id}_{}", sensor.object_id),
+                    "state_topic": self.options.topic,
+                    "value_template": format!("{{{{ value_json.{} | default(none) }}}}", sensor.path),
+                    "device_class": sensor.device_class,
+                    "unit_of_measurement": (sensor.unit)(units),
+                    "state_class": "measurement",
+                    "device": {
+                        "identifiers": [node_id],
+                        "name": "OpenWeather",
+                        "manufacturer": "OpenWeather",
+                    },
+                });
+
+                (topic, payload.to_string())
+            })
+            .collect()
+    }
+
+    /// Publishes the [`MqttPublisher::home_assistant_discovery`] messages, retained so Home Assistant finds the
+    /// sensors again after it restarts. This is a blocking network call, and only needs to happen once.
+    pub fn publish_home_assistant_discovery(&self, units: WeatherUnits) -> Result<(), MqttError> {
+        let messages: Vec<_> = self
+            .home_assistant_discovery(units)
+            .into_iter()
+            .map(|(topic, payload)| (topic, payload.into_bytes()))
+            .collect();
+
+        self.publish_all(&messages, true)
+    }
+
+    /// Opens a connection to the broker, trying each of its addresses in turn
+    fn connect(&self) -> io::Result<TcpStream> {
+        let timeout = self.options.timeout;
+        let mut last_error = None;
+
+        for addr in (self.options.host.as_str(), self.options.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(timeout))?;
+                    stream.set_write_timeout(Some(timeout))?;
+                    return Ok(stream);
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("`{}` has no addresses", self.options.host),
+            )
+        }))
+    }
+
+    fn connect_packet(&self) -> Result<Vec<u8>, MqttError> {
+        let mut flags = 0x02; // clean session
+        if self.options.credentials.is_some() {
+            flags |= 0x80 | 0x40;
+        }
+
+        let mut body = encode_string("MQTT")?;
+        body.push(4); // protocol level 3.1.1
+        body.push(flags);
+        body.extend_from_slice(&KEEP_ALIVE_SECS.to_be_bytes());
+        body.extend(encode_string(&self.options.client_id)?);
+        if let Some((username, password)) = &self.options.credentials {
+            body.extend(encode_string(username)?);
+            body.extend(encode_string(password)?);
+        }
+
+        packet(CONNECT, &body)
+    }
+}
+
+/// Prefixes a packet body with its fixed header, the type byte and the variable length encoded remaining length.
+fn packet(header: u8, body: &[u8]) -> Result<Vec<u8>, MqttError> {
+    // Four length bytes of seven bits each
+    if body.len() >= 1 << 28 {
+        return Err(MqttError::PacketTooLarge(body.len()));
+    }
+
+    let mut packet = vec![header];
+    let mut remaining = body.len();
+    loop {
+        let mut byte = (remaining % 128) as u8;
+        remaining /= 128;
+        if remaining > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+
+        if remaining == 0 {
+            break;
+        }
+    }
+    packet.extend_from_slice(body);
+
+    Ok(packet)
+}
+
+/// Strings in MQTT are prefixed with their length as a big endian u16
+fn encode_string(s: &str) -> Result<Vec<u8>, MqttError> {
+    let len = u16::try_from(s.len()).map_err(|_| MqttError::PacketTooLarge(s.len()))?;
+
+    let mut encoded = len.to_be_bytes().to_vec();
+    encoded.extend_from_slice(s.as_bytes());
+
+    Ok(encoded)
+}
+
+/// Reads a whole packet, returning its type byte and body.
+fn read_packet(stream: &mut impl Read) -> io::Result<(u8, Vec<u8>)> {
+    let mut byte = [0; 1];
+    stream.read_exact(&mut byte)?;
+    let header = byte[0];
+
+    let mut remaining = 0;
+    for shift in (0..28).step_by(7) {
+        stream.read_exact(&mut byte)?;
+        remaining |= usize::from(byte[0] & 0x7F) << shift;
+
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+
+    let mut body = vec![0; remaining];
+    stream.read_exact(&mut body)?;
+
+    Ok((header, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+    use std::net::TcpListener;
+    use std::thread::JoinHandle;
+
+    type Packet = (u8, Vec<u8>);
+
+    /// Accepts one connection, acknowledges it with `return_code`, and returns every packet received
+    fn fake_broker(return_code: u8) -> (u16, JoinHandle<Vec<Packet>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut packets = vec![read_packet(&mut stream).unwrap()];
+            stream.write_all(&[CONNACK, 2, 0, return_code]).unwrap();
+
+            while let Ok(packet) = read_packet(&mut stream) {
+                packets.push(packet);
+            }
+            packets
+        });
+
+        (port, handle)
+    }
+
+    #[test]
+    fn remaining_length_encoding() {
+        assert_eq!(packet(DISCONNECT, &[]).unwrap(), [DISCONNECT, 0]);
+        assert_eq!(
+            &packet(PUBLISH, &[0; 321]).unwrap()[..3],
+            [PUBLISH, 0xC1, 0x02]
+        );
+
+        let encoded = packet(PUBLISH, &[7; 16_384]).unwrap();
+        assert_eq!(
+            read_packet(&mut encoded.as_slice()).unwrap(),
+            (PUBLISH, vec![7; 16_384])
+        );
+    }
+
+    #[test]
+    fn publishes_observation() {
+        let (port, broker) = fake_broker(0);
+        let publisher = MqttPublisher::new(
+            MqttOptions::new("127.0.0.1")
+                .with_port(port)
+                .with_credentials("user", "hunter2")
+                .with_topic("home/weather"),
+        );
+
+        publisher.publish_observation(&zocca()).unwrap();
+
+        let packets = broker.join().unwrap();
+        assert_eq!(packets.len(), 3);
+
+        let (connect, body) = &packets[0];
+        assert_eq!(*connect, CONNECT);
+        assert_eq!(&body[..8], b"\x00\x04MQTT\x04\xC2");
+        assert!(body.ends_with(b"\x00\x04user\x00\x07hunter2"));
+
+        let (publish, body) = &packets[1];
+        assert_eq!(*publish, PUBLISH | 1);
+        assert_eq!(&body[..14], b"\x00\x0Chome/weather");
+        let payload: OWCurrentWeatherResponse = serde_json::from_slice(&body[14..]).unwrap();
+        assert_eq!(payload, zocca());
+
+        assert_eq!(packets[2], (DISCONNECT, vec![]));
+    }
+
+    #[test]
+    fn refused_connection() {
+        let (port, broker) = fake_broker(5);
+        let publisher = MqttPublisher::new(MqttOptions::new("127.0.0.1").with_port(port));
+
+        assert!(matches!(
+            publisher.publish("openwx/test", b"{}", false),
+            Err(MqttError::ConnectionRefused(5))
+        ));
+        broker.join().unwrap();
+    }
+
+    #[test]
+    fn unresponsive_broker() {
+        // Accepts the connection but never acknowledges it
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let publisher = MqttPublisher::new(
+            MqttOptions::new("127.0.0.1")
+                .with_port(port)
+                .with_timeout(Duration::from_millis(100)),
+        );
+
+        assert!(matches!(
+            publisher.publish("openwx/test", b"{}", false),
+            Err(MqttError::Io(_))
+        ));
+        drop(listener);
+    }
+
+    #[test]
+    fn home_assistant_discovery() {
+        let publisher = MqttPublisher::new(
+            MqttOptions::new("127.0.0.1")
+                .with_client_id("openwx-garden")
+                .with_topic("home/weather"),
+        );
+
+        let messages = publisher.home_assistant_discovery(WeatherUnits::Imperial);
+        let topics: Vec<_> = messages.iter().map(|(topic, _)| topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            [
+                "homeassistant/sensor/openwx_garden/temperature/config",
+                "homeassistant/sensor/openwx_garden/humidity/config",
+                "homeassistant/sensor/openwx_garden/pressure/config",
+                "homeassistant/sensor/openwx_garden/wind_speed/config",
+            ]
+        );
+
+        let temperature: serde_json::Value = serde_json::from_str(&messages[0].1).unwrap();
+        assert_eq!(temperature["unique_id"], "openwx_garden_temperature");
+        assert_eq!(temperature["state_topic"], "home/weather");
+        assert_eq!(
+            temperature["value_template"],
+            "{{ value_json.main.temp | default(none) }}"
+        );
+        assert_eq!(temperature["device_class"], "temperature");
+        assert_eq!(temperature["unit_of_measurement"], "°F");
+
+        let wind: serde_json::Value = serde_json::from_str(&messages[3].1).unwrap();
+        assert_eq!(wind["unit_of_measurement"], "mph");
+        assert_eq!(
+            wind["value_template"],
+            "{{ value_json.wind.speed | default(none) }}"
+        );
+    }
+
+    #[test]
+    fn wildcard_topic() {
+        let publisher = MqttPublisher::new(MqttOptions::new("127.0.0.1"));
+
+        assert!(matches!(
+            publisher.publish("openwx/#", b"{}", false),
+            Err(MqttError::InvalidTopic(_))
+        ));
+    }
+}
@@ -0,0 +1,1188 @@
+//! Types for OpenWeather's One Call 3.0 API
+
+use crate::{
+    GeodeticCoords, OWRain, OWSnow, OWWeather, Percentage, Pressure, Temperature, WeatherUnits,
+    WindDirection, WindSpeed, from_optional_raw_wind_speed, from_optional_unix_offset,
+    from_raw_percentage, from_raw_pressure, from_raw_temperature, from_raw_wind_direction,
+    from_raw_wind_speed, from_unix_offset, from_utc_shift, to_optional_raw_wind_speed,
+    to_optional_unix_offset, to_raw_percentage, to_raw_pressure, to_raw_temperature,
+    to_raw_wind_direction, to_raw_wind_speed, to_unix_offset, to_utc_shift,
+};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, str::FromStr};
+use strum::Display;
+use thiserror::Error;
+
+/// Blocks of the One Call response which can be excluded from the request to save bandwidth
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum OneCallBlock {
+    Current,
+    Minutely,
+    Hourly,
+    Daily,
+    Alerts,
+}
+
+/// OpenWeather response from the One Call 3.0 API, more details [here](https://openweathermap.org/api/one-call-3).
+/// Blocks excluded from the request are `None` or empty.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWOneCallResponse {
+    /// Latitude of the location
+    pub lat: f32,
+
+    /// Longitude of the location
+    pub lon: f32,
+
+    /// Timezone name for the requested location, e.g. "America/Los_Angeles"
+    pub timezone: String,
+
+    /// Shift in seconds from UTC
+    #[serde(deserialize_with = "from_utc_shift", serialize_with = "to_utc_shift")]
+    pub timezone_offset: FixedOffset,
+
+    pub current: Option<OWOneCallCurrent>,
+
+    /// Minute forecast for the next hour, where available
+    #[serde(default)]
+    pub minutely: Vec<OWMinutely>,
+
+    /// Hourly forecast for the next 48 hours
+    #[serde(default)]
+    pub hourly: Vec<OWOneCallHourly>,
+
+    /// Daily forecast for the next 8 days
+    #[serde(default)]
+    pub daily: Vec<OWOneCallDaily>,
+
+    /// National weather alerts for the location
+    #[serde(default)]
+    pub alerts: Vec<OWAlert>,
+
+    /// Units the response was requested in, see [`crate::OWCurrentWeatherResponse::units`]
+    #[serde(skip)]
+    pub units: WeatherUnits,
+}
+
+impl OWOneCallResponse {
+    /// Tags the response, its temperatures, and its wind speeds with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.current
+            .iter_mut()
+            .for_each(|current| current.set_units(units));
+        self.hourly
+            .iter_mut()
+            .for_each(|hour| hour.set_units(units));
+        self.daily.iter_mut().for_each(|day| day.set_units(units));
+        self
+    }
+
+    /// Coordinates of the location
+    pub fn coords(&self) -> GeodeticCoords {
+        GeodeticCoords {
+            lat: self.lat,
+            lon: self.lon,
+        }
+    }
+}
+
+/// OpenWeather response from One Call's timemachine route, historical weather for a single moment
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWTimeMachineResponse {
+    /// Latitude of the location
+    pub lat: f32,
+
+    /// Longitude of the location
+    pub lon: f32,
+
+    /// Timezone name for the requested location, e.g. "America/Los_Angeles"
+    pub timezone: String,
+
+    /// Shift in seconds from UTC
+    #[serde(deserialize_with = "from_utc_shift", serialize_with = "to_utc_shift")]
+    pub timezone_offset: FixedOffset,
+
+    /// Observations closest to the requested time, usually exactly one
+    pub data: Vec<OWOneCallCurrent>,
+
+    /// Units the response was requested in, see [`crate::OWCurrentWeatherResponse::units`]
+    #[serde(skip)]
+    pub units: WeatherUnits,
+}
+
+impl OWTimeMachineResponse {
+    /// Tags the response, its temperatures, and its wind speeds with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.data.iter_mut().for_each(|data| data.set_units(units));
+        self
+    }
+}
+
+/// OpenWeather response from One Call's day summary route, weather aggregated over a single day
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDaySummary {
+    /// Latitude of the location
+    pub lat: f32,
+
+    /// Longitude of the location
+    pub lon: f32,
+
+    /// Timezone the day is aggregated in
+    #[serde(deserialize_with = "from_str_value", serialize_with = "to_str_value")]
+    pub tz: FixedOffset,
+
+    /// The summarized day
+    #[serde(deserialize_with = "from_str_value", serialize_with = "to_str_value")]
+    pub date: NaiveDate,
+
+    /// Units the summary was requested in, which unlike other responses OpenWeather echoes back
+    pub units: WeatherUnits,
+
+    pub cloud_cover: OWAfternoonValue,
+
+    pub humidity: OWAfternoonValue,
+
+    pub precipitation: OWDayPrecipitation,
+
+    pub temperature: OWDayTemperature,
+
+    pub pressure: OWAfternoonValue,
+
+    pub wind: OWDayWind,
+}
+
+impl OWDaySummary {
+    /// Tags the summary, its temperatures, and its wind speed with the units it was requested in, see
+    /// [`crate::OWCurrentWeatherResponse::with_units`].
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.temperature.set_units(units);
+        self.wind.max.speed = WindSpeed::new(self.wind.max.speed.value(), units);
+        self
+    }
+}
+
+/// OpenWeather response from One Call's overview route, a generated prose summary of the day's weather
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWWeatherOverview {
+    /// Latitude of the location
+    pub lat: f32,
+
+    /// Longitude of the location
+    pub lon: f32,
+
+    /// Timezone of the location
+    #[serde(deserialize_with = "from_str_value", serialize_with = "to_str_value")]
+    pub tz: FixedOffset,
+
+    /// The day being described
+    #[serde(deserialize_with = "from_str_value", serialize_with = "to_str_value")]
+    pub date: NaiveDate,
+
+    /// Units the overview was requested in, e.g. "metric"
+    pub units: String,
+
+    /// Human-readable summary of the weather for the day
+    pub weather_overview: String,
+}
+
+/// A value sampled at 12:00 local time. Cloud cover and humidity in %, pressure in hPa
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAfternoonValue {
+    pub afternoon: f32,
+}
+
+/// Precipitation over the day
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDayPrecipitation {
+    /// Total amount of liquid water equivalent of precipitation, mm
+    pub total: f32,
+}
+
+/// Temperatures over the day. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDayTemperature {
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub min: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub max: Temperature,
+
+    /// Temperature at 12:00
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub afternoon: Temperature,
+
+    /// Temperature at 00:00
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub night: Temperature,
+
+    /// Temperature at 18:00
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub evening: Temperature,
+
+    /// Temperature at 06:00
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub morning: Temperature,
+}
+
+impl OWDayTemperature {
+    /// Labels the temperatures with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        for temp in [
+            &mut self.min,
+            &mut self.max,
+            &mut self.afternoon,
+            &mut self.night,
+            &mut self.evening,
+            &mut self.morning,
+        ] {
+            *temp = Temperature::new(temp.value(), units);
+        }
+    }
+}
+
+/// Wind over the day
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDayWind {
+    pub max: OWDayMaxWind,
+}
+
+/// The strongest wind of the day
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDayMaxWind {
+    /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub speed: WindSpeed,
+
+    /// Wind direction, degrees (meteorological)
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
+    pub direction: WindDirection,
+}
+
+/// Named phases of the moon, each covering an eighth of the lunar cycle centered on its namesake
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum MoonPhaseName {
+    New,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    Full,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+/// Position in the lunar cycle, constrained to the range [0, 1]. 0 and 1 are new moon, 0.25 is first quarter, 0.5
+/// is full moon, and 0.75 is last quarter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonPhase(f32);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MoonPhaseError {
+    #[error("provided moon phase of `{0}` is outside the valid range [0, 1]")]
+    OutOfRange(f32),
+}
+
+impl MoonPhase {
+    /// Validates the cycle fraction falls inside [0, 1].
+    pub fn new_checked(fraction: f32) -> Result<Self, MoonPhaseError> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return Err(MoonPhaseError::OutOfRange(fraction));
+        }
+
+        Ok(MoonPhase(fraction))
+    }
+
+    /// Returns the position in the lunar cycle as a number in [0, 1]
+    pub fn cycle_fraction(&self) -> f32 {
+        self.0
+    }
+
+    /// Returns the named phase
+    pub fn name(&self) -> MoonPhaseName {
+        const NAMES: [MoonPhaseName; 8] = [
+            MoonPhaseName::New,
+            MoonPhaseName::WaxingCrescent,
+            MoonPhaseName::FirstQuarter,
+            MoonPhaseName::WaxingGibbous,
+            MoonPhaseName::Full,
+            MoonPhaseName::WaningGibbous,
+            MoonPhaseName::LastQuarter,
+            MoonPhaseName::WaningCrescent,
+        ];
+
+        // Each phase is centered on a multiple of 1/8, the last sector wraps around to new moon
+        let sector = (self.0 * 8.0).round() as usize % NAMES.len();
+        NAMES[sector]
+    }
+
+    /// Returns the illuminated fraction of the moon's disc as seen from Earth, 0 at new moon and 1 at full moon
+    pub fn illumination(&self) -> f32 {
+        (1.0 - (2.0 * std::f32::consts::PI * self.0).cos()) / 2.0
+    }
+
+    /// Whether the illuminated portion is growing, from new moon up to full moon
+    pub fn is_waxing(&self) -> bool {
+        self.0 < 0.5
+    }
+}
+
+/// OpenWeather returns moon phases as bare numbers, we validate them and wrap them in a [`MoonPhase`].
+fn from_raw_moon_phase<'de, D>(deserializer: D) -> Result<MoonPhase, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = f32::deserialize(deserializer)?;
+
+    MoonPhase::new_checked(raw).map_err(serde::de::Error::custom)
+}
+
+/// Serializes a [`MoonPhase`] back to a bare number, the inverse of [`from_raw_moon_phase`].
+fn to_raw_moon_phase<S>(phase: &MoonPhase, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(phase.0)
+}
+
+/// WHO exposure categories for the UV index, with protection recommended from moderate upwards
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UvCategory {
+    /// 0 to 2
+    Low,
+    /// 3 to 5
+    Moderate,
+    /// 6 and 7
+    High,
+    /// 8 to 10
+    #[strum(serialize = "Very High")]
+    VeryHigh,
+    /// 11 and above
+    Extreme,
+}
+
+/// A UV index, a measure of the strength of sunburn-producing ultraviolet radiation
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct UvIndex(f32);
+
+impl UvIndex {
+    /// Creates a UV index of `value`.
+    pub fn new(value: f32) -> Self {
+        UvIndex(value)
+    }
+
+    /// Returns the index as reported, which may be fractional
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+
+    /// Returns the WHO exposure category. The bands are defined on whole numbers, so the index is rounded first.
+    pub fn category(&self) -> UvCategory {
+        match self.0.round() {
+            index if index >= 11.0 => UvCategory::Extreme,
+            index if index >= 8.0 => UvCategory::VeryHigh,
+            index if index >= 6.0 => UvCategory::High,
+            index if index >= 3.0 => UvCategory::Moderate,
+            _ => UvCategory::Low,
+        }
+    }
+}
+
+/// OpenWeather returns UV indices as bare numbers, we wrap them in a [`UvIndex`].
+fn from_raw_uv_index<'de, D>(deserializer: D) -> Result<UvIndex, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = f32::deserialize(deserializer)?;
+
+    Ok(UvIndex::new(raw))
+}
+
+/// Serializes a [`UvIndex`] back to a bare number, the inverse of [`from_raw_uv_index`].
+fn to_raw_uv_index<S>(uvi: &UvIndex, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(uvi.0)
+}
+
+/// Deserializes anything with a string representation via [`FromStr`], such as dates and timezone offsets
+fn from_str_value<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    let raw = String::deserialize(deserializer)?;
+
+    raw.parse().map_err(serde::de::Error::custom)
+}
+
+/// Serializes anything with a string representation via [`Display`], the inverse of [`from_str_value`]
+fn to_str_value<S, T>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    T: Display,
+{
+    serializer.collect_str(value)
+}
+
+/// Current weather from the One Call API, also used for historical observations
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWOneCallCurrent {
+    /// Current time, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Sunrise time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunrise: Option<DateTime<Utc>>,
+
+    /// Sunset time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunset: Option<DateTime<Utc>>,
+
+    /// Temperature. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub temp: Temperature,
+
+    /// Temperature accounting for the human perception of weather. Unit Default: Kelvin, Metric: Celsius,
+    /// Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub feels_like: Temperature,
+
+    /// Atmospheric pressure on the sea level, hPa
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub pressure: Pressure,
+
+    /// Humidity, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
+
+    /// Atmospheric temperature below which water droplets begin to condense. Unit Default: Kelvin, Metric: Celsius,
+    /// Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub dew_point: Temperature,
+
+    /// Current UV index
+    #[serde(
+        deserialize_with = "from_raw_uv_index",
+        serialize_with = "to_raw_uv_index"
+    )]
+    pub uvi: UvIndex,
+
+    /// Cloudiness, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub clouds: Percentage,
+
+    /// Average visibility, meter. The maximum value of the visibility is 10 km
+    pub visibility: Option<f32>,
+
+    /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub wind_speed: WindSpeed,
+
+    /// Wind direction, degrees (meteorological)
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
+    pub wind_deg: WindDirection,
+
+    /// Wind gust. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        default,
+        deserialize_with = "from_optional_raw_wind_speed",
+        serialize_with = "to_optional_raw_wind_speed"
+    )]
+    pub wind_gust: Option<WindSpeed>,
+
+    pub weather: Vec<OWWeather>,
+
+    pub rain: Option<OWRain>,
+
+    pub snow: Option<OWSnow>,
+}
+
+impl OWOneCallCurrent {
+    /// Labels the temperatures and wind speeds with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        for temp in [&mut self.temp, &mut self.feels_like, &mut self.dew_point] {
+            *temp = Temperature::new(temp.value(), units);
+        }
+        self.wind_speed = WindSpeed::new(self.wind_speed.value(), units);
+        self.wind_gust = self
+            .wind_gust
+            .map(|gust| WindSpeed::new(gust.value(), units));
+    }
+}
+
+/// A single minute of the minute forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWMinutely {
+    /// Time of the forecasted data, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Precipitation, mm/h
+    pub precipitation: f32,
+}
+
+/// A single hour of the hourly forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWOneCallHourly {
+    /// Time of the forecasted data, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Temperature. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub temp: Temperature,
+
+    /// Temperature accounting for the human perception of weather. Unit Default: Kelvin, Metric: Celsius,
+    /// Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub feels_like: Temperature,
+
+    /// Atmospheric pressure on the sea level, hPa
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub pressure: Pressure,
+
+    /// Humidity, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
+
+    /// Atmospheric temperature below which water droplets begin to condense. Unit Default: Kelvin, Metric: Celsius,
+    /// Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub dew_point: Temperature,
+
+    /// UV index
+    #[serde(
+        deserialize_with = "from_raw_uv_index",
+        serialize_with = "to_raw_uv_index"
+    )]
+    pub uvi: UvIndex,
+
+    /// Cloudiness, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub clouds: Percentage,
+
+    /// Average visibility, meter. The maximum value of the visibility is 10 km
+    pub visibility: Option<f32>,
+
+    /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub wind_speed: WindSpeed,
+
+    /// Wind direction, degrees (meteorological)
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
+    pub wind_deg: WindDirection,
+
+    /// Wind gust. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        default,
+        deserialize_with = "from_optional_raw_wind_speed",
+        serialize_with = "to_optional_raw_wind_speed"
+    )]
+    pub wind_gust: Option<WindSpeed>,
+
+    pub weather: Vec<OWWeather>,
+
+    /// Probability of precipitation, from 0 to 1
+    pub pop: f32,
+
+    pub rain: Option<OWRain>,
+
+    pub snow: Option<OWSnow>,
+}
+
+impl OWOneCallHourly {
+    /// Labels the temperatures and wind speeds with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        for temp in [&mut self.temp, &mut self.feels_like, &mut self.dew_point] {
+            *temp = Temperature::new(temp.value(), units);
+        }
+        self.wind_speed = WindSpeed::new(self.wind_speed.value(), units);
+        self.wind_gust = self
+            .wind_gust
+            .map(|gust| WindSpeed::new(gust.value(), units));
+    }
+}
+
+/// A single day of the daily forecast
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWOneCallDaily {
+    /// Time of the forecasted data, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    /// Sunrise time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunrise: Option<DateTime<Utc>>,
+
+    /// Sunset time, absent during polar day and night
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub sunset: Option<DateTime<Utc>>,
+
+    /// Moonrise time, absent on days the moon doesn't rise
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub moonrise: Option<DateTime<Utc>>,
+
+    /// Moonset time, absent on days the moon doesn't set
+    #[serde(
+        default,
+        deserialize_with = "from_optional_unix_offset",
+        serialize_with = "to_optional_unix_offset"
+    )]
+    pub moonset: Option<DateTime<Utc>>,
+
+    /// Moon phase. 0 and 1 are new moon, 0.25 is first quarter, 0.5 is full moon, and 0.75 is last quarter
+    #[serde(
+        deserialize_with = "from_raw_moon_phase",
+        serialize_with = "to_raw_moon_phase"
+    )]
+    pub moon_phase: MoonPhase,
+
+    /// Human-readable description of the weather conditions for the day
+    pub summary: Option<String>,
+
+    pub temp: OWDailyTemp,
+
+    pub feels_like: OWDailyFeelsLike,
+
+    /// Atmospheric pressure on the sea level, hPa
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub pressure: Pressure,
+
+    /// Humidity, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
+
+    /// Atmospheric temperature below which water droplets begin to condense. Unit Default: Kelvin, Metric: Celsius,
+    /// Imperial: Fahrenheit
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub dew_point: Temperature,
+
+    /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub wind_speed: WindSpeed,
+
+    /// Wind direction, degrees (meteorological)
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
+    pub wind_deg: WindDirection,
+
+    /// Wind gust. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
+    #[serde(
+        default,
+        deserialize_with = "from_optional_raw_wind_speed",
+        serialize_with = "to_optional_raw_wind_speed"
+    )]
+    pub wind_gust: Option<WindSpeed>,
+
+    pub weather: Vec<OWWeather>,
+
+    /// Cloudiness, %
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub clouds: Percentage,
+
+    /// Probability of precipitation, from 0 to 1
+    pub pop: f32,
+
+    /// Precipitation volume, mm
+    pub rain: Option<f32>,
+
+    /// Snow volume, mm
+    pub snow: Option<f32>,
+
+    /// The maximum value of UV index for the day
+    #[serde(
+        deserialize_with = "from_raw_uv_index",
+        serialize_with = "to_raw_uv_index"
+    )]
+    pub uvi: UvIndex,
+}
+
+impl OWOneCallDaily {
+    /// Labels the temperatures and wind speeds with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        self.temp.set_units(units);
+        self.feels_like.set_units(units);
+        self.dew_point = Temperature::new(self.dew_point.value(), units);
+        self.wind_speed = WindSpeed::new(self.wind_speed.value(), units);
+        self.wind_gust = self
+            .wind_gust
+            .map(|gust| WindSpeed::new(gust.value(), units));
+    }
+}
+
+/// Temperatures over the course of a day. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDailyTemp {
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub morn: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub day: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub eve: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub night: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub min: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub max: Temperature,
+}
+
+impl OWDailyTemp {
+    /// Labels the temperatures with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        for temp in [
+            &mut self.morn,
+            &mut self.day,
+            &mut self.eve,
+            &mut self.night,
+            &mut self.min,
+            &mut self.max,
+        ] {
+            *temp = Temperature::new(temp.value(), units);
+        }
+    }
+}
+
+/// Temperatures accounting for the human perception of weather over the course of a day. Unit Default: Kelvin,
+/// Metric: Celsius, Imperial: Fahrenheit
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWDailyFeelsLike {
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub morn: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub day: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub eve: Temperature,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub night: Temperature,
+}
+
+impl OWDailyFeelsLike {
+    /// Labels the temperatures with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        for temp in [
+            &mut self.morn,
+            &mut self.day,
+            &mut self.eve,
+            &mut self.night,
+        ] {
+            *temp = Temperature::new(temp.value(), units);
+        }
+    }
+}
+
+/// A national weather alert
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAlert {
+    /// Name of the alert source
+    pub sender_name: String,
+
+    /// Alert event name
+    pub event: String,
+
+    /// Start of the alert
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
+    pub start: DateTime<Utc>,
+
+    /// End of the alert
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
+    pub end: DateTime<Utc>,
+
+    /// Description of the alert
+    pub description: String,
+
+    /// Type of severe weather
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A trimmed down One Call response with one entry in each block
+    pub(crate) const ONE_CALL_RESPONSE: &str = r#"
+        {
+        "lat": 33.545,
+        "lon": -117.771,
+        "timezone": "America/Los_Angeles",
+        "timezone_offset": -28800,
+        "current": {
+            "dt": 1763077522, "sunrise": 1763044543, "sunset": 1763082100,
+            "temp": 64.2, "feels_like": 63.5, "pressure": 1016, "humidity": 72, "dew_point": 54.9,
+            "uvi": 0.4, "clouds": 40, "visibility": 10000, "wind_speed": 6.9, "wind_deg": 250,
+            "weather": [{ "id": 802, "main": "Clouds", "description": "scattered clouds", "icon": "03d" }]
+        },
+        "minutely": [{ "dt": 1763077560, "precipitation": 0 }],
+        "hourly": [{
+            "dt": 1763074800, "temp": 64.2, "feels_like": 63.5, "pressure": 1016, "humidity": 72,
+            "dew_point": 54.9, "uvi": 0.4, "clouds": 40, "visibility": 10000, "wind_speed": 6.9,
+            "wind_deg": 250, "wind_gust": 9.1,
+            "weather": [{ "id": 802, "main": "Clouds", "description": "scattered clouds", "icon": "03d" }],
+            "pop": 0.1
+        }],
+        "daily": [{
+            "dt": 1763060400, "sunrise": 1763044543, "sunset": 1763082100, "moonrise": 1763020000,
+            "moonset": 0, "moon_phase": 0.79, "summary": "Expect a day of partly cloudy with clear spells",
+            "temp": { "day": 66.1, "min": 55.0, "max": 68.4, "night": 57.2, "eve": 61.3, "morn": 55.9 },
+            "feels_like": { "day": 65.0, "night": 56.1, "eve": 60.2, "morn": 54.8 },
+            "pressure": 1016, "humidity": 60, "dew_point": 51.2, "wind_speed": 8.1, "wind_deg": 260,
+            "wind_gust": 12.3,
+            "weather": [{ "id": 500, "main": "Rain", "description": "light rain", "icon": "10d" }],
+            "clouds": 40, "pop": 0.35, "rain": 0.6, "uvi": 3.9
+        }],
+        "alerts": [{
+            "sender_name": "NWS San Diego CA",
+            "event": "Wind Advisory",
+            "start": 1763078400,
+            "end": 1763121600,
+            "description": "Northeast winds 20 to 30 mph with gusts up to 50 mph.",
+            "tags": ["Wind"]
+        }]
+        }
+        "#;
+
+    #[test]
+    fn parse_one_call() {
+        let response: OWOneCallResponse = serde_json::from_str(ONE_CALL_RESPONSE).unwrap();
+
+        let current = response.current.as_ref().unwrap();
+        assert_eq!(current.humidity.value(), 72.0);
+        assert!(current.sunrise.is_some());
+
+        assert_eq!(response.minutely.len(), 1);
+        assert_eq!(
+            response.hourly[0].wind_gust.map(|gust| gust.value()),
+            Some(9.1)
+        );
+
+        let daily = &response.daily[0];
+        assert_eq!(daily.temp.max.value(), 68.4);
+        assert!(daily.moonrise.is_some());
+        assert!(daily.moonset.is_none());
+        assert_eq!(daily.moon_phase.name(), MoonPhaseName::LastQuarter);
+        assert_eq!(daily.uvi.category(), UvCategory::Moderate);
+
+        assert_eq!(response.alerts[0].event, "Wind Advisory");
+        assert_eq!(response.timezone_offset.local_minus_utc(), -28800);
+    }
+
+    #[test]
+    fn one_call_with_units() {
+        let response: OWOneCallResponse = serde_json::from_str(ONE_CALL_RESPONSE).unwrap();
+        let response = response.with_units(WeatherUnits::Imperial);
+        assert_eq!(response.units, WeatherUnits::Imperial);
+
+        let current = response.current.as_ref().unwrap();
+        assert_eq!(current.temp, Temperature::new(64.2, WeatherUnits::Imperial));
+        assert!((current.temp.to_celsius() - 17.889).abs() < 1e-3);
+        assert_eq!(current.dew_point.units(), WeatherUnits::Imperial);
+        assert!((current.wind_speed.to_mps() - 3.085).abs() < 1e-3);
+
+        let hourly = &response.hourly[0];
+        assert_eq!(hourly.feels_like.units(), WeatherUnits::Imperial);
+        assert_eq!(
+            hourly.wind_gust,
+            Some(WindSpeed::new(9.1, WeatherUnits::Imperial))
+        );
+
+        let daily = &response.daily[0];
+        assert_eq!(
+            daily.temp.max,
+            Temperature::new(68.4, WeatherUnits::Imperial)
+        );
+        assert_eq!(daily.feels_like.morn.units(), WeatherUnits::Imperial);
+        assert_eq!(daily.wind_speed.units(), WeatherUnits::Imperial);
+        assert_eq!(daily.pressure.hpa(), 1016.0);
+    }
+
+    #[test]
+    fn moon_phases() {
+        let phase = |fraction| MoonPhase::new_checked(fraction).unwrap();
+
+        assert_eq!(phase(0.0).name(), MoonPhaseName::New);
+        assert_eq!(phase(0.97).name(), MoonPhaseName::New);
+        assert_eq!(phase(0.1).name(), MoonPhaseName::WaxingCrescent);
+        assert_eq!(phase(0.5).name(), MoonPhaseName::Full);
+        assert_eq!(phase(0.8).name(), MoonPhaseName::LastQuarter);
+
+        assert!(phase(0.0).illumination().abs() < 1e-6);
+        assert!((phase(0.25).illumination() - 0.5).abs() < 1e-6);
+        assert!((phase(0.5).illumination() - 1.0).abs() < 1e-6);
+        assert!(phase(0.3).is_waxing() && !phase(0.7).is_waxing());
+
+        assert_eq!(
+            MoonPhase::new_checked(1.5).unwrap_err(),
+            MoonPhaseError::OutOfRange(1.5)
+        );
+    }
+
+    #[test]
+    fn uv_categories() {
+        let category = |value| UvIndex::new(value).category();
+
+        assert_eq!(category(0.4), UvCategory::Low);
+        assert_eq!(category(2.6), UvCategory::Moderate);
+        assert_eq!(category(7.0), UvCategory::High);
+        assert_eq!(category(9.9), UvCategory::VeryHigh);
+        assert_eq!(category(12.0), UvCategory::Extreme);
+        assert_eq!(UvCategory::VeryHigh.to_string(), "Very High");
+    }
+
+    #[test]
+    fn parse_timemachine() {
+        let json = r#"
+            {
+            "lat": 33.545,
+            "lon": -117.771,
+            "timezone": "America/Los_Angeles",
+            "timezone_offset": -25200,
+            "data": [{
+                "dt": 1719000000, "sunrise": 1718973000, "sunset": 1719025000,
+                "temp": 71.3, "feels_like": 71.0, "pressure": 1012, "humidity": 64, "dew_point": 58.5,
+                "uvi": 8.1, "clouds": 0, "visibility": 10000, "wind_speed": 8.0, "wind_deg": 270,
+                "weather": [{ "id": 800, "main": "Clear", "description": "clear sky", "icon": "01d" }]
+            }]
+            }
+            "#;
+
+        let response: OWTimeMachineResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.data.len(), 1);
+        assert_eq!(response.data[0].dt, 1719000000);
+        assert_eq!(response.data[0].clouds.value(), 0.0);
+
+        let response = response.with_units(WeatherUnits::Imperial);
+        assert_eq!(response.units, WeatherUnits::Imperial);
+        assert_eq!(
+            response.data[0].temp,
+            Temperature::new(71.3, WeatherUnits::Imperial)
+        );
+        assert_eq!(
+            response.data[0].wind_speed,
+            WindSpeed::new(8.0, WeatherUnits::Imperial)
+        );
+    }
+
+    #[test]
+    fn parse_day_summary() {
+        let json = r#"
+            {
+            "lat": 33,
+            "lon": 35,
+            "tz": "+02:00",
+            "date": "2020-03-04",
+            "units": "standard",
+            "cloud_cover": { "afternoon": 0 },
+            "humidity": { "afternoon": 33 },
+            "precipitation": { "total": 0 },
+            "temperature": {
+                "min": 286.48, "max": 299.24, "afternoon": 296.15,
+                "night": 289.56, "evening": 295.93, "morning": 287.59
+            },
+            "pressure": { "afternoon": 1015 },
+            "wind": { "max": { "speed": 8.7, "direction": 120 } }
+            }
+            "#;
+
+        let summary: OWDaySummary = serde_json::from_str(json).unwrap();
+        assert_eq!(summary.date, NaiveDate::from_ymd_opt(2020, 3, 4).unwrap());
+        assert_eq!(summary.tz.local_minus_utc(), 7200);
+        assert_eq!(summary.temperature.max.value(), 299.24);
+        assert_eq!(summary.wind.max.speed.value(), 8.7);
+
+        // Dates and offsets serialize back to the strings OpenWeather sent
+        let reserialized = serde_json::to_value(&summary).unwrap();
+        assert_eq!(reserialized["tz"], "+02:00");
+        assert_eq!(reserialized["date"], "2020-03-04");
+        assert_eq!(reserialized["units"], "standard");
+
+        let summary = summary.with_units(WeatherUnits::Metric);
+        assert_eq!(summary.units, WeatherUnits::Metric);
+        assert_eq!(
+            summary.temperature.morning,
+            Temperature::new(287.59, WeatherUnits::Metric)
+        );
+        assert_eq!(summary.wind.max.speed.units(), WeatherUnits::Metric);
+    }
+
+    #[test]
+    fn parse_overview() {
+        let json = r#"
+            {
+            "lat": 51.509865,
+            "lon": -0.118092,
+            "tz": "+01:00",
+            "date": "2024-05-13",
+            "units": "metric",
+            "weather_overview": "The current weather is overcast with a temperature of 16°C."
+            }
+            "#;
+
+        let overview: OWWeatherOverview = serde_json::from_str(json).unwrap();
+        assert_eq!(overview.date, NaiveDate::from_ymd_opt(2024, 5, 13).unwrap());
+        assert!(
+            overview
+                .weather_overview
+                .starts_with("The current weather is overcast")
+        );
+    }
+
+    #[test]
+    fn parse_one_call_with_exclusions() {
+        let json = r#"{ "lat": 33.545, "lon": -117.771, "timezone": "America/Los_Angeles", "timezone_offset": -28800 }"#;
+        let response: OWOneCallResponse = serde_json::from_str(json).unwrap();
+
+        assert!(response.current.is_none());
+        assert!(response.hourly.is_empty());
+        assert!(response.alerts.is_empty());
+    }
+}
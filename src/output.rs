@@ -0,0 +1,458 @@
+//! Output formats for the CLI, a readable summary or structured formats which can be consumed by scripts. Structured
+//! formats are rendered from the serialized, OpenWeather wire format, so field names match the API docs.
+
+use clap::ValueEnum;
+use openwx::{
+    GeoLocation, OWAirPollutionResponse, OWCurrentWeatherResponse, OWForecastResponse,
+    SummaryStyle, export_csv_header,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Formats responses can be printed in. Tables and CSV have a row per entry of a list, tables of a single response
+/// have a line per field instead.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Summary,
+    Json,
+    Yaml,
+    Table,
+    Csv,
+}
+
+/// A response the CLI prints
+pub trait Printable: Serialize {
+    /// Field holding the entries table and CSV rows are made of, rows are otherwise the items of a list or the
+    /// response itself
+    const ROWS_FIELD: Option<&'static str> = None;
+
+    /// A readable summary decorated by `style`, without a trailing line break
+    fn summary(&self, style: &dyn SummaryStyle) -> String;
+
+    /// CSV with fixed columns, instead of columns flattened from the response's fields
+    fn csv(&self) -> Option<String> {
+        None
+    }
+}
+
+impl Printable for OWCurrentWeatherResponse {
+    fn summary(&self, style: &dyn SummaryStyle) -> String {
+        self.styled_summary(style)
+    }
+
+    // Current weather and forecast CSV have the same columns as exported observations and History Bulk archives
+    fn csv(&self) -> Option<String> {
+        Some(format!("{}\n{}\n", export_csv_header(), self.to_csv_row()))
+    }
+}
+
+impl Printable for OWForecastResponse {
+    const ROWS_FIELD: Option<&'static str> = Some("list");
+
+    fn summary(&self, style: &dyn SummaryStyle) -> String {
+        self.styled_summary(style)
+    }
+
+    fn csv(&self) -> Option<String> {
+        Some(self.to_csv())
+    }
+}
+
+impl Printable for OWAirPollutionResponse {
+    const ROWS_FIELD: Option<&'static str> = Some("list");
+
+    fn summary(&self, style: &dyn SummaryStyle) -> String {
+        self.styled_summary(style)
+    }
+}
+
+impl Printable for Vec<GeoLocation> {
+    fn summary(&self, _style: &dyn SummaryStyle) -> String {
+        self.iter()
+            .map(|place| format!("{} ({}, {})", describe_place(place), place.lat, place.lon))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// A place's name, state, and country, e.g. `Laguna Beach, California, US`
+pub fn describe_place(place: &GeoLocation) -> String {
+    match &place.state {
+        Some(state) => format!("{}, {state}, {}", place.name, place.country),
+        None => format!("{}, {}", place.name, place.country),
+    }
+}
+
+/// Renders a response in a format, with a trailing line break. Summaries are decorated by `style`, table and CSV rows
+/// have nested fields flattened into dot-separated columns like `main.temp`.
+pub fn render<T: Printable>(
+    format: OutputFormat,
+    response: &T,
+    style: &dyn SummaryStyle,
+) -> String {
+    match format {
+        OutputFormat::Summary => format!("{}\n", response.summary(style)),
+        OutputFormat::Json => {
+            let mut json =
+                serde_json::to_string_pretty(&document(response)).expect("values serialize");
+            json.push('\n');
+            json
+        }
+        OutputFormat::Yaml => to_yaml(&document(response)),
+        OutputFormat::Table => to_table(&rows(document(response), T::ROWS_FIELD)),
+        OutputFormat::Csv => response
+            .csv()
+            .unwrap_or_else(|| to_csv(&rows(document(response), T::ROWS_FIELD))),
+    }
+}
+
+fn document(response: &impl Serialize) -> Value {
+    // Round trip through text rather than `to_value`, which widens f32 fields and prints 44.34 as 44.34000015258789
+    serde_json::to_string(response)
+        .and_then(|json| serde_json::from_str(&json))
+        .expect("responses always serialize to JSON")
+}
+
+/// The items of `field` if given, the items of the document if it's a list, or otherwise the document itself
+fn rows(document: Value, field: Option<&str>) -> Vec<Value> {
+    match (document, field) {
+        (mut document, Some(field)) => match document[field].take() {
+            Value::Array(items) => items,
+            _ => Vec::new(),
+        },
+        (Value::Array(items), None) => items,
+        (document, None) => vec![document],
+    }
+}
+
+/// Flattens a value into `(path, text)` pairs, numeric path segments index into arrays
+fn flatten(prefix: &str, value: &Value, fields: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, fields);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                flatten(&join(&i.to_string()), value, fields);
+            }
+        }
+        Value::Null => fields.push((prefix.to_string(), String::new())),
+        Value::String(s) => fields.push((prefix.to_string(), s.clone())),
+        value => fields.push((prefix.to_string(), value.to_string())),
+    }
+}
+
+/// Flattens every row, returning the union of their columns in the order they're first seen
+fn flatten_rows(rows: &[Value]) -> (Vec<String>, Vec<Vec<(String, String)>>) {
+    let mut columns: Vec<String> = Vec::new();
+    let rows: Vec<_> = rows
+        .iter()
+        .map(|row| {
+            let mut fields = Vec::new();
+            flatten("", row, &mut fields);
+
+            for (column, _) in &fields {
+                if !columns.contains(column) {
+                    columns.push(column.clone());
+                }
+            }
+
+            fields
+        })
+        .collect();
+
+    (columns, rows)
+}
+
+/// The value of `column` in a flattened row, empty if the row doesn't have it
+fn cell<'a>(row: &'a [(String, String)], column: &str) -> &'a str {
+    row.iter()
+        .find(|(name, _)| name == column)
+        .map(|(_, text)| text.as_str())
+        .unwrap_or_default()
+}
+
+fn to_table(rows: &[Value]) -> String {
+    let (columns, rows) = flatten_rows(rows);
+
+    // A single response reads better as a field per line than as one very wide row
+    let lines: Vec<Vec<&str>> = if let [row] = rows.as_slice() {
+        row.iter()
+            .map(|(name, text)| vec![name.as_str(), text.as_str()])
+            .collect()
+    } else {
+        std::iter::once(columns.iter().map(String::as_str).collect())
+            .chain(rows.iter().map(|row| {
+                columns
+                    .iter()
+                    .map(|column| cell(row, column))
+                    .collect::<Vec<_>>()
+            }))
+            .collect()
+    };
+
+    let widths = lines.iter().fold(Vec::new(), |mut widths, line| {
+        widths.resize(widths.len().max(line.len()), 0);
+        for (width, text) in widths.iter_mut().zip(line) {
+            *width = (*width).max(text.chars().count());
+        }
+        widths
+    });
+
+    let mut table = String::new();
+    for line in lines {
+        let padded: Vec<_> = line
+            .iter()
+            .zip(&widths)
+            .map(|(text, width)| format!("{text:<width$}"))
+            .collect();
+        table.push_str(padded.join("  ").trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+fn to_csv(rows: &[Value]) -> String {
+    let (columns, rows) = flatten_rows(rows);
+
+    let mut csv = csv_line(columns.iter().map(String::as_str));
+    for row in &rows {
+        csv.push_str(&csv_line(columns.iter().map(|column| cell(row, column))));
+    }
+
+    csv
+}
+
+/// Joins fields into a CSV line, quoting any containing a delimiter, quote, or line break
+fn csv_line<'a>(fields: impl Iterator<Item = &'a str>) -> String {
+    let fields: Vec<_> = fields
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+
+    format!("{}\n", fields.join(","))
+}
+
+fn to_yaml(value: &Value) -> String {
+    let mut yaml = String::new();
+    match value {
+        Value::Object(map) if !map.is_empty() => write_yaml_map(map, 0, &mut yaml),
+        Value::Array(items) if !items.is_empty() => write_yaml_list(items, 0, &mut yaml),
+        value => {
+            yaml.push_str(&yaml_scalar(value));
+            yaml.push('\n');
+        }
+    }
+
+    yaml
+}
+
+fn write_yaml_map(map: &serde_json::Map<String, Value>, indent: usize, yaml: &mut String) {
+    for (key, value) in map {
+        yaml.push_str(&" ".repeat(indent));
+        yaml.push_str(&yaml_string(key));
+        yaml.push(':');
+        write_yaml_value(value, indent, yaml);
+    }
+}
+
+fn write_yaml_list(items: &[Value], indent: usize, yaml: &mut String) {
+    for value in items {
+        yaml.push_str(&" ".repeat(indent));
+        yaml.push('-');
+
+        match value {
+            // Maps start on the same line as their dash
+            Value::Object(map) if !map.is_empty() => {
+                let mut nested = String::new();
+                write_yaml_map(map, indent + 2, &mut nested);
+                yaml.push(' ');
+                yaml.push_str(&nested[indent + 2..]);
+            }
+            value => write_yaml_value(value, indent, yaml),
+        }
+    }
+}
+
+/// Writes the value following a `key:` or `-`, nesting non-empty collections on the lines below
+fn write_yaml_value(value: &Value, indent: usize, yaml: &mut String) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            yaml.push('\n');
+            write_yaml_map(map, indent + 2, yaml);
+        }
+        Value::Array(items) if !items.is_empty() => {
+            yaml.push('\n');
+            write_yaml_list(items, indent + 2, yaml);
+        }
+        value => {
+            yaml.push(' ');
+            yaml.push_str(&yaml_scalar(value));
+            yaml.push('\n');
+        }
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".into(),
+        Value::String(s) => yaml_string(s),
+        Value::Object(_) => "{}".into(),
+        Value::Array(_) => "[]".into(),
+        value => value.to_string(),
+    }
+}
+
+/// Leaves a string plain if YAML would read it back as the same string, otherwise double quotes it. JSON string
+/// escapes are valid in YAML double quoted strings.
+fn yaml_string(s: &str) -> String {
+    let plain = s
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && !s.ends_with(' ')
+        && s.chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '_' | '-' | '.' | '/'))
+        && !matches!(
+            s.to_ascii_lowercase().as_str(),
+            "true" | "false" | "yes" | "no" | "on" | "off" | "null" | "y" | "n"
+        );
+
+    if plain {
+        s.to_string()
+    } else {
+        Value::from(s).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!({
+            "name": "Laguna Beach",
+            "main": { "temp": 68.2, "humidity": 72 },
+            "weather": [{ "description": "broken clouds", "icon": "04d" }],
+            "rain": null,
+            "tags": []
+        })
+    }
+
+    #[test]
+    fn yaml() {
+        assert_eq!(
+            to_yaml(&sample()),
+            "main:\n  humidity: 72\n  temp: 68.2\nname: Laguna Beach\nrain: null\ntags: []\nweather:\n  - \
+            description: broken clouds\n    icon: \"04d\"\n"
+        );
+
+        assert_eq!(yaml_string("yes"), "\"yes\"");
+        assert_eq!(yaml_string("a: b"), "\"a: b\"");
+        assert_eq!(yaml_string(""), "\"\"");
+        assert_eq!(yaml_string("pt_br"), "pt_br");
+    }
+
+    #[test]
+    fn yaml_round_trip() {
+        // Strings YAML would read as something else, or fail to read, unless quoted
+        let quoted = [
+            "a: b",
+            "#x",
+            "a #b",
+            "-x",
+            "- x",
+            "yes",
+            "No",
+            "off",
+            "",
+            "null",
+            "~",
+            "12",
+            "1.5e3",
+            " x",
+            "x ",
+            "multi\nline",
+            "[1]",
+            "*ref",
+            "&a",
+            "!tag",
+            "@at",
+            "|",
+            "> f",
+            "?q",
+        ];
+        for s in quoted {
+            let yaml = to_yaml(&json!([s]));
+            let scalar = yaml.strip_prefix("- ").unwrap().trim_end_matches('\n');
+            assert!(
+                scalar.starts_with('"'),
+                "`{s}` should be quoted, got {yaml:?}"
+            );
+
+            // Double-quoted YAML scalars are written with JSON escapes, so a JSON parser reads them back
+            assert_eq!(serde_json::from_str::<String>(scalar).unwrap(), s);
+        }
+
+        for s in [
+            "Laguna Beach",
+            "broken clouds",
+            "pt_br",
+            "Zürich",
+            "a-b.c/d",
+        ] {
+            assert_eq!(to_yaml(&json!([s])), format!("- {s}\n"));
+        }
+
+        assert_eq!(
+            to_yaml(&json!({ "k: ey": { "#": "" } })),
+            "\"k: ey\":\n  \"#\": \"\"\n"
+        );
+    }
+
+    #[test]
+    fn single_row_table() {
+        assert_eq!(
+            to_table(&rows(sample(), None)),
+            "main.humidity          72\nmain.temp              68.2\nname                   Laguna Beach\n\
+            rain\nweather.0.description  broken clouds\nweather.0.icon         04d\n"
+        );
+    }
+
+    #[test]
+    fn multiple_rows() {
+        let places = json!([
+            { "name": "Springfield", "state": "Illinois", "lat": 39.8 },
+            { "name": "Springfield, \"MO\"", "lat": 37.2 }
+        ]);
+
+        assert_eq!(
+            to_csv(&rows(places.clone(), None)),
+            "lat,name,state\n39.8,Springfield,Illinois\n37.2,\"Springfield, \"\"MO\"\"\",\n"
+        );
+        assert_eq!(
+            to_table(&rows(places, None)),
+            "lat   name               state\n39.8  Springfield        Illinois\n37.2  Springfield, \"MO\"\n"
+        );
+
+        let forecast = json!({ "cnt": 2, "list": [{ "dt": 1 }, { "dt": 2 }] });
+        assert_eq!(to_csv(&rows(forecast, Some("list"))), "dt\n1\n2\n");
+    }
+}
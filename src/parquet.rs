@@ -0,0 +1,747 @@
+//! Parquet export of observations and forecasts, so years of collected logs can be loaded straight into dataframe
+//! tooling. Files have a single row group of uncompressed, plain encoded columns with the typed schema in
+//! [`PARQUET_COLUMNS`], the simplest layout the format allows, so no Parquet library is needed to write them.
+
+use crate::{
+    GeodeticCoords, OWClouds, OWCurrentWeatherResponse, OWForecastResponse,
+    OWHourlyForecastResponse, OWMain, OWRain, OWSnow, OWWeather, OWWind, WeatherUnits,
+};
+use std::io::{self, Write};
+
+/// Magic bytes at the start and end of every Parquet file
+const MAGIC: &[u8; 4] = b"PAR1";
+
+/// Physical types from the Parquet format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetType {
+    Int32,
+    Int64,
+    Float,
+    ByteArray,
+}
+
+impl ParquetType {
+    fn code(self) -> i32 {
+        match self {
+            ParquetType::Int32 => 1,
+            ParquetType::Int64 => 2,
+            ParquetType::Float => 4,
+            ParquetType::ByteArray => 6,
+        }
+    }
+}
+
+/// How a column's physical values should be interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParquetLogicalType {
+    /// UTF-8 text
+    String,
+
+    /// Milliseconds since the UNIX epoch, UTC
+    TimestampMillis,
+}
+
+impl ParquetLogicalType {
+    /// The legacy `ConvertedType`, which readers of every age understand
+    fn converted_type(self) -> i32 {
+        match self {
+            ParquetLogicalType::String => 0,
+            ParquetLogicalType::TimestampMillis => 9,
+        }
+    }
+
+    /// Writes the `LogicalType` union which current readers, Arrow's included, take the column's type from
+    fn write_logical_type(self, meta: &mut CompactWriter, id: i16) {
+        meta.begin_struct(id);
+        match self {
+            ParquetLogicalType::String => {
+                meta.begin_struct(1); // STRING
+                meta.end_struct();
+            }
+            ParquetLogicalType::TimestampMillis => {
+                meta.begin_struct(8); // TIMESTAMP
+                meta.bool(1, true); // isAdjustedToUTC
+                meta.begin_struct(2);
+                meta.begin_struct(1); // MILLIS
+                meta.end_struct();
+                meta.end_struct();
+                meta.end_struct();
+            }
+        }
+        meta.end_struct();
+    }
+}
+
+/// A column of the exported schema
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParquetColumn {
+    pub name: &'static str,
+    pub physical_type: ParquetType,
+    pub logical_type: Option<ParquetLogicalType>,
+
+    /// Whether the column can be null
+    pub nullable: bool,
+}
+
+const fn column(
+    name: &'static str,
+    physical_type: ParquetType,
+    logical_type: Option<ParquetLogicalType>,
+    nullable: bool,
+) -> ParquetColumn {
+    ParquetColumn {
+        name,
+        physical_type,
+        logical_type,
+        nullable,
+    }
+}
+
+/// Schema of exported files. Values are in the units the responses were requested in, recorded in `units`.
+/// Forecast rows have a `pop` and no `visibility` for entries OpenWeather didn't report it for, observations have no
+/// `pop`.
+pub const PARQUET_COLUMNS: [ParquetColumn; 23] = {
+    use ParquetLogicalType::{String, TimestampMillis};
+    use ParquetType::{ByteArray, Float, Int32, Int64};
+
+    [
+        column("location", ByteArray, Some(String), false),
+        column("time", Int64, Some(TimestampMillis), false),
+        column("units", ByteArray, Some(String), false),
+        column("lat", Float, None, false),
+        column("lon", Float, None, false),
+        column("temp", Float, None, false),
+        column("feels_like", Float, None, false),
+        column("temp_min", Float, None, false),
+        column("temp_max", Float, None, false),
+        column("pressure", Float, None, false),
+        column("humidity", Float, None, false),
+        column("visibility", Float, None, true),
+        column("wind_speed", Float, None, true),
+        column("wind_deg", Float, None, true),
+        column("wind_gust", Float, None, true),
+        column("clouds_all", Float, None, false),
+        column("rain_1h", Float, None, true),
+        column("rain_3h", Float, None, true),
+        column("snow_1h", Float, None, true),
+        column("snow_3h", Float, None, true),
+        column("weather_id", Int32, None, true),
+        column("weather_description", ByteArray, Some(String), true),
+        column("pop", Float, None, true),
+    ]
+};
+
+/// A single value of a row, in [`PARQUET_COLUMNS`] order
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Int32(i32),
+    Int64(i64),
+    Float(f32),
+    Text(String),
+}
+
+impl From<Option<f32>> for Value {
+    fn from(value: Option<f32>) -> Self {
+        value.map_or(Value::Null, Value::Float)
+    }
+}
+
+/// Everything a row is built from, shared between observations and forecast entries
+struct RowSource<'a> {
+    location: &'a str,
+    dt: u64,
+    units: WeatherUnits,
+    coord: GeodeticCoords,
+    main: &'a OWMain,
+    visibility: Option<f32>,
+    wind: Option<&'a OWWind>,
+    clouds: &'a OWClouds,
+    weather: Option<&'a OWWeather>,
+    rain: Option<&'a OWRain>,
+    snow: Option<&'a OWSnow>,
+    pop: Option<f32>,
+}
+
+impl RowSource<'_> {
+    fn to_row(&self) -> [Value; PARQUET_COLUMNS.len()] {
+        let main = self.main;
+
+        [
+            Value::Text(self.location.to_string()),
+            Value::Int64(self.dt as i64 * 1000),
+            Value::Text(self.units.to_string()),
+            Value::Float(self.coord.lat),
+            Value::Float(self.coord.lon),
+            Value::Float(main.temp.value()),
+            Value::Float(main.feels_like.value()),
+            Value::Float(main.temp_min.value()),
+            Value::Float(main.temp_max.value()),
+            Value::Float(main.pressure.hpa()),
+            Value::Float(main.humidity.value()),
+            self.visibility.into(),
+            self.wind.map(|wind| wind.speed.value()).into(),
+            self.wind.map(|wind| wind.deg.value()).into(),
+            self.wind
+                .and_then(|wind| wind.gust)
+                .map(|gust| gust.value())
+                .into(),
+            Value::Float(self.clouds.all.value()),
+            self.rain
+                .and_then(|rain| rain.one_hour)
+                .map(|rate| rate.mm_per_hour())
+                .into(),
+            self.rain.and_then(|rain| rain.three_hours).into(),
+            self.snow
+                .and_then(|snow| snow.one_hour)
+                .map(|rate| rate.mm_per_hour())
+                .into(),
+            self.snow.and_then(|snow| snow.three_hours).into(),
+            self.weather.map_or(
+                Value::Null,
+                |weather| Value::Int32(weather.id.code() as i32),
+            ),
+            self.weather.map_or(Value::Null, |weather| {
+                Value::Text(weather.description.clone())
+            }),
+            self.pop.into(),
+        ]
+    }
+}
+
+/// Writes observations as a Parquet file with the schema in [`PARQUET_COLUMNS`], a row per observation labelled with
+/// its location name. Only the primary weather condition is included.
+pub fn write_observations_parquet<'a>(
+    writer: impl Write,
+    observations: impl IntoIterator<Item = (&'a str, &'a OWCurrentWeatherResponse)>,
+) -> io::Result<()> {
+    let rows = observations
+        .into_iter()
+        .map(|(location, response)| {
+            RowSource {
+                location,
+                dt: response.dt,
+                units: response.units,
+                coord: response.coord,
+                main: &response.main,
+                visibility: Some(response.visibility),
+                wind: response.wind(),
+                clouds: &response.clouds,
+                weather: response.primary_condition(),
+                rain: response.rain.as_ref(),
+                snow: response.snow.as_ref(),
+                pop: None,
+            }
+            .to_row()
+        })
+        .collect();
+
+    write_parquet(writer, rows)
+}
+
+impl OWForecastResponse {
+    /// Writes the forecast as a Parquet file with the schema in [`PARQUET_COLUMNS`], a row per entry labelled with
+    /// the forecast city's name.
+    pub fn write_parquet(&self, writer: impl Write) -> io::Result<()> {
+        let rows = self
+            .list
+            .iter()
+            .map(|entry| {
+                RowSource {
+                    location: &self.city.name,
+                    dt: entry.dt,
+                    units: self.units,
+                    coord: self.city.coord,
+                    main: &entry.main,
+                    visibility: entry.visibility,
+                    wind: entry.wind.as_ref(),
+                    clouds: &entry.clouds,
+                    weather: entry.weather.first(),
+                    rain: entry.rain.as_ref(),
+                    snow: entry.snow.as_ref(),
+                    pop: Some(entry.pop),
+                }
+                .to_row()
+            })
+            .collect();
+
+        write_parquet(writer, rows)
+    }
+}
+
+impl OWHourlyForecastResponse {
+    /// Writes the forecast as a Parquet file with the schema in [`PARQUET_COLUMNS`], a row per entry labelled with
+    /// the forecast city's name.
+    pub fn write_parquet(&self, writer: impl Write) -> io::Result<()> {
+        let rows = self
+            .list
+            .iter()
+            .map(|entry| {
+                RowSource {
+                    location: &self.city.name,
+                    dt: entry.dt,
+                    units: self.units,
+                    coord: self.city.coord,
+                    main: &entry.main,
+                    visibility: entry.visibility,
+                    wind: entry.wind.as_ref(),
+                    clouds: &entry.clouds,
+                    weather: entry.weather.first(),
+                    rain: entry.rain.as_ref(),
+                    snow: entry.snow.as_ref(),
+                    pop: Some(entry.pop),
+                }
+                .to_row()
+            })
+            .collect();
+
+        write_parquet(writer, rows)
+    }
+}
+
+/// Lays out the rows as one row group with a single data page per column, followed by the footer.
+fn write_parquet(
+    mut writer: impl Write,
+    rows: Vec<[Value; PARQUET_COLUMNS.len()]>,
+) -> io::Result<()> {
+    let mut file = MAGIC.to_vec();
+    let mut chunks = Vec::new();
+
+    if !rows.is_empty() {
+        for (i, column) in PARQUET_COLUMNS.iter().enumerate() {
+            let offset = file.len() as i64;
+            let page = data_page(column, rows.iter().map(|row| &row[i]));
+            file.extend_from_slice(&page);
+
+            chunks.push((offset, page.len() as i64));
+        }
+    }
+
+    let footer = file_metadata(rows.len() as i64, &chunks);
+    file.extend_from_slice(&footer);
+    file.extend_from_slice(&(footer.len() as u32).to_le_bytes());
+    file.extend_from_slice(MAGIC);
+
+    writer.write_all(&file)?;
+    writer.flush()
+}
+
+/// A data page holding every value of a column, header included
+fn data_page<'a>(column: &ParquetColumn, values: impl Iterator<Item = &'a Value>) -> Vec<u8> {
+    let values: Vec<_> = values.collect();
+    let mut data = Vec::new();
+
+    if column.nullable {
+        let levels = definition_levels(values.iter().map(|value| **value != Value::Null));
+        data.extend_from_slice(&(levels.len() as u32).to_le_bytes());
+        data.extend_from_slice(&levels);
+    }
+
+    for value in values.iter() {
+        match value {
+            Value::Null => {}
+            Value::Int32(v) => data.extend_from_slice(&v.to_le_bytes()),
+            Value::Int64(v) => data.extend_from_slice(&v.to_le_bytes()),
+            Value::Float(v) => data.extend_from_slice(&v.to_le_bytes()),
+            Value::Text(v) => {
+                data.extend_from_slice(&(v.len() as u32).to_le_bytes());
+                data.extend_from_slice(v.as_bytes());
+            }
+        }
+    }
+
+    let mut header = CompactWriter::default();
+    header.i32(1, 0); // DATA_PAGE
+    header.i32(2, data.len() as i32);
+    header.i32(3, data.len() as i32);
+    header.begin_struct(5);
+    header.i32(1, values.len() as i32);
+    header.i32(2, 0); // PLAIN
+    header.i32(3, 3); // RLE
+    header.i32(4, 3); // RLE
+    header.end_struct();
+    let mut page = header.finish();
+
+    page.extend_from_slice(&data);
+    page
+}
+
+/// Definition levels of an optional column, 1 for present and 0 for null, as runs of the RLE/bit-packing hybrid
+/// encoding with a bit width of 1
+fn definition_levels(present: impl Iterator<Item = bool>) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    let mut runs: Vec<(bool, u64)> = Vec::new();
+
+    for present in present {
+        match runs.last_mut() {
+            Some((level, count)) if *level == present => *count += 1,
+            _ => runs.push((present, 1)),
+        }
+    }
+
+    for (level, count) in runs {
+        write_varint(&mut encoded, count << 1);
+        encoded.push(u8::from(level));
+    }
+
+    encoded
+}
+
+/// The Thrift encoded `FileMetaData` footer, `chunks` are the offset and length of each column chunk
+fn file_metadata(num_rows: i64, chunks: &[(i64, i64)]) -> Vec<u8> {
+    let mut meta = CompactWriter::default();
+    meta.i32(1, 1); // version
+
+    meta.begin_list(2, STRUCT, PARQUET_COLUMNS.len() + 1);
+    meta.begin_element();
+    meta.binary(4, b"schema");
+    meta.i32(5, PARQUET_COLUMNS.len() as i32);
+    meta.end_struct();
+    for column in &PARQUET_COLUMNS {
+        meta.begin_element();
+        meta.i32(1, column.physical_type.code());
+        meta.i32(3, i32::from(column.nullable)); // REQUIRED or OPTIONAL
+        meta.binary(4, column.name.as_bytes());
+        if let Some(logical_type) = column.logical_type {
+            meta.i32(6, logical_type.converted_type());
+            logical_type.write_logical_type(&mut meta, 10);
+        }
+        meta.end_struct();
+    }
+
+    meta.i64(3, num_rows);
+
+    let row_groups = if chunks.is_empty() { 0 } else { 1 };
+    meta.begin_list(4, STRUCT, row_groups);
+    if row_groups > 0 {
+        meta.begin_element();
+        meta.begin_list(1, STRUCT, chunks.len());
+        for (column, &(offset, len)) in PARQUET_COLUMNS.iter().zip(chunks) {
+            meta.begin_element();
+            meta.i64(2, offset);
+            meta.begin_struct(3);
+            meta.i32(1, column.physical_type.code());
+            meta.begin_list(2, I32, 2);
+            meta.list_i32(0); // PLAIN
+            meta.list_i32(3); // RLE
+            meta.begin_list(3, BINARY, 1);
+            meta.list_binary(column.name.as_bytes());
+            meta.i32(4, 0); // UNCOMPRESSED
+            meta.i64(5, num_rows);
+            meta.i64(6, len);
+            meta.i64(7, len);
+            meta.i64(9, offset);
+            meta.end_struct();
+            meta.end_struct();
+        }
+        meta.i64(2, chunks.iter().map(|(_, len)| len).sum());
+        meta.i64(3, num_rows);
+        meta.end_struct();
+    }
+
+    meta.binary(
+        6,
+        concat!("openwx version ", env!("CARGO_PKG_VERSION")).as_bytes(),
+    );
+
+    meta.finish()
+}
+
+/// Compact protocol type ids
+const TRUE: u8 = 1;
+const FALSE: u8 = 2;
+const I32: u8 = 5;
+const I64: u8 = 6;
+const BINARY: u8 = 8;
+const LIST: u8 = 9;
+const STRUCT: u8 = 12;
+
+/// Writes a Thrift struct in the compact protocol, which is how Parquet encodes its metadata. Fields must be written
+/// in increasing id order within each struct.
+#[derive(Default)]
+struct CompactWriter {
+    out: Vec<u8>,
+    last_id: i16,
+    enclosing_ids: Vec<i16>,
+}
+
+impl CompactWriter {
+    fn field(&mut self, id: i16, field_type: u8) {
+        let delta = id - self.last_id;
+        if (1..=15).contains(&delta) {
+            self.out.push((delta as u8) << 4 | field_type);
+        } else {
+            self.out.push(field_type);
+            write_varint(&mut self.out, zigzag(id.into()));
+        }
+        self.last_id = id;
+    }
+
+    /// Booleans in a struct are carried in the type of their field header
+    fn bool(&mut self, id: i16, value: bool) {
+        self.field(id, if value { TRUE } else { FALSE });
+    }
+
+    fn i32(&mut self, id: i16, value: i32) {
+        self.field(id, I32);
+        write_varint(&mut self.out, zigzag(value.into()));
+    }
+
+    fn i64(&mut self, id: i16, value: i64) {
+        self.field(id, I64);
+        write_varint(&mut self.out, zigzag(value));
+    }
+
+    fn binary(&mut self, id: i16, value: &[u8]) {
+        self.field(id, BINARY);
+        self.list_binary(value);
+    }
+
+    fn begin_struct(&mut self, id: i16) {
+        self.field(id, STRUCT);
+        self.begin_element();
+    }
+
+    /// Starts a struct inside a list, which has no field header
+    fn begin_element(&mut self) {
+        self.enclosing_ids.push(self.last_id);
+        self.last_id = 0;
+    }
+
+    fn end_struct(&mut self) {
+        self.out.push(0);
+        self.last_id = self.enclosing_ids.pop().unwrap_or_default();
+    }
+
+    fn begin_list(&mut self, id: i16, element_type: u8, len: usize) {
+        self.field(id, LIST);
+        if len < 15 {
+            self.out.push((len as u8) << 4 | element_type);
+        } else {
+            self.out.push(0xF0 | element_type);
+            write_varint(&mut self.out, len as u64);
+        }
+    }
+
+    fn list_i32(&mut self, value: i32) {
+        write_varint(&mut self.out, zigzag(value.into()));
+    }
+
+    fn list_binary(&mut self, value: &[u8]) {
+        write_varint(&mut self.out, value.len() as u64);
+        self.out.extend_from_slice(value);
+    }
+
+    /// Ends the outermost struct and returns its encoding
+    fn finish(mut self) -> Vec<u8> {
+        self.out.push(0);
+        self.out
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forecast::tests::FORECAST_RESPONSE;
+    use crate::types::tests::zocca;
+    use std::collections::BTreeMap;
+
+    /// A decoded compact protocol value, just enough to walk the footer
+    #[derive(Debug, Clone, PartialEq)]
+    enum Thrift {
+        Bool(bool),
+        Int(i64),
+        Binary(Vec<u8>),
+        List(Vec<Thrift>),
+        Struct(BTreeMap<i16, Thrift>),
+    }
+
+    impl Thrift {
+        fn field(&self, id: i16) -> &Thrift {
+            match self {
+                Thrift::Struct(fields) => &fields[&id],
+                other => panic!("{other:?} is not a struct"),
+            }
+        }
+
+        fn int(&self) -> i64 {
+            match self {
+                Thrift::Int(v) => *v,
+                other => panic!("{other:?} is not an integer"),
+            }
+        }
+
+        fn text(&self) -> &str {
+            match self {
+                Thrift::Binary(v) => std::str::from_utf8(v).unwrap(),
+                other => panic!("{other:?} is not binary"),
+            }
+        }
+
+        fn list(&self) -> &[Thrift] {
+            match self {
+                Thrift::List(v) => v,
+                other => panic!("{other:?} is not a list"),
+            }
+        }
+    }
+
+    fn read_varint(input: &mut &[u8]) -> u64 {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let byte = input[0];
+            *input = &input[1..];
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        value
+    }
+
+    fn read_int(input: &mut &[u8]) -> i64 {
+        let raw = read_varint(input);
+        (raw >> 1) as i64 ^ -((raw & 1) as i64)
+    }
+
+    fn read_value(input: &mut &[u8], value_type: u8) -> Thrift {
+        match value_type {
+            TRUE | FALSE => Thrift::Bool(value_type == TRUE),
+            I32 | I64 => Thrift::Int(read_int(input)),
+            BINARY => {
+                let len = read_varint(input) as usize;
+                let (bytes, rest) = input.split_at(len);
+                *input = rest;
+                Thrift::Binary(bytes.to_vec())
+            }
+            LIST => {
+                let header = input[0];
+                *input = &input[1..];
+                let len = match header >> 4 {
+                    0xF => read_varint(input) as usize,
+                    len => len as usize,
+                };
+                Thrift::List((0..len).map(|_| read_value(input, header & 0x0F)).collect())
+            }
+            STRUCT => {
+                let mut fields = BTreeMap::new();
+                let mut last_id = 0;
+                loop {
+                    let header = input[0];
+                    *input = &input[1..];
+                    if header == 0 {
+                        return Thrift::Struct(fields);
+                    }
+
+                    let id = match header >> 4 {
+                        0 => read_int(input) as i16,
+                        delta => last_id + delta as i16,
+                    };
+                    fields.insert(id, read_value(input, header & 0x0F));
+                    last_id = id;
+                }
+            }
+            other => panic!("unexpected type {other}"),
+        }
+    }
+
+    /// Checks the framing and decodes the footer
+    fn footer(file: &[u8]) -> Thrift {
+        assert_eq!(&file[..4], MAGIC);
+        assert_eq!(&file[file.len() - 4..], MAGIC);
+
+        let len_at = file.len() - 8;
+        let len = u32::from_le_bytes(file[len_at..len_at + 4].try_into().unwrap()) as usize;
+        let mut footer = &file[len_at - len..len_at];
+
+        let meta = read_value(&mut footer, STRUCT);
+        assert!(footer.is_empty());
+        meta
+    }
+
+    #[test]
+    fn observations_file() {
+        let response = zocca();
+        let mut file = Vec::new();
+        write_observations_parquet(&mut file, [("zocca", &response), ("home", &response)]).unwrap();
+
+        let meta = footer(&file);
+        assert_eq!(meta.field(3).int(), 2);
+
+        let schema = meta.field(2).list();
+        assert_eq!(schema.len(), PARQUET_COLUMNS.len() + 1);
+        assert_eq!(schema[0].field(5).int(), PARQUET_COLUMNS.len() as i64);
+        assert_eq!(schema[1].field(4).text(), "location");
+        assert_eq!(schema[2].field(6).int(), 9);
+
+        // Logical types alongside the converted types, a string and a UTC timestamp in milliseconds
+        let empty = Thrift::Struct(BTreeMap::new());
+        assert_eq!(schema[1].field(10).field(1), &empty);
+        let timestamp = schema[2].field(10).field(8);
+        assert_eq!(timestamp.field(1), &Thrift::Bool(true));
+        assert_eq!(timestamp.field(2).field(1), &empty);
+        assert!(!matches!(&schema[4], Thrift::Struct(fields) if fields.contains_key(&10)));
+
+        let columns = meta.field(4).list()[0].field(1).list();
+        assert_eq!(columns.len(), PARQUET_COLUMNS.len());
+
+        // The `time` column holds the two timestamps in milliseconds, right after its page header
+        let time = columns[1].field(3);
+        assert_eq!(time.field(3).list()[0].text(), "time");
+        assert_eq!(time.field(5).int(), 2);
+
+        let offset = time.field(9).int() as usize;
+        let mut page = &file[offset..offset + time.field(7).int() as usize];
+        let header = read_value(&mut page, STRUCT);
+        assert_eq!(header.field(5).field(1).int(), 2);
+        assert_eq!(page.len(), 16);
+        assert_eq!(
+            i64::from_le_bytes(page[..8].try_into().unwrap()),
+            1_763_077_522_000
+        );
+    }
+
+    #[test]
+    fn forecast_file() {
+        let forecast: OWForecastResponse = serde_json::from_str(FORECAST_RESPONSE).unwrap();
+        let mut file = Vec::new();
+        forecast.write_parquet(&mut file).unwrap();
+
+        let meta = footer(&file);
+        assert_eq!(meta.field(3).int(), forecast.list.len() as i64);
+        assert_eq!(
+            meta.field(4).list()[0].field(3).int(),
+            forecast.list.len() as i64
+        );
+    }
+
+    #[test]
+    fn empty_file() {
+        let mut file = Vec::new();
+        write_observations_parquet(&mut file, []).unwrap();
+
+        let meta = footer(&file);
+        assert_eq!(meta.field(3).int(), 0);
+        assert!(meta.field(4).list().is_empty());
+    }
+
+    #[test]
+    fn definition_level_runs() {
+        assert_eq!(
+            definition_levels([true, true, false, true].into_iter()),
+            [4, 1, 2, 0, 2, 1]
+        );
+    }
+}
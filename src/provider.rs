@@ -0,0 +1,158 @@
+//! Abstracting over where current weather comes from, so weather-dependent code can be tested without a network
+
+use crate::{GeodeticCoords, OWCurrentWeatherResponse, OpenWeatherClient, OpenWxError};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Anything that can report the current weather at a position. [`OpenWeatherClient`] is the real implementation,
+/// accept a `&impl CurrentWeatherProvider` instead to swap in a [`MockWeatherProvider`] under test.
+pub trait CurrentWeatherProvider {
+    /// The current weather at a position.
+    fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError>;
+}
+
+impl CurrentWeatherProvider for OpenWeatherClient {
+    fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        OpenWeatherClient::current_weather(self, coords)
+    }
+}
+
+impl<P: CurrentWeatherProvider + ?Sized> CurrentWeatherProvider for &P {
+    fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        (**self).current_weather(coords)
+    }
+}
+
+impl<P: CurrentWeatherProvider + ?Sized> CurrentWeatherProvider for Box<P> {
+    fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        (**self).current_weather(coords)
+    }
+}
+
+/// A [`CurrentWeatherProvider`] which serves canned responses in the order they were queued and records the
+/// positions it was asked about. Panics if asked for more responses than were queued, like any unexpected call to a
+/// mock.
+#[derive(Debug, Default)]
+pub struct MockWeatherProvider {
+    responses: Mutex<VecDeque<Result<OWCurrentWeatherResponse, OpenWxError>>>,
+    requested: Mutex<Vec<GeodeticCoords>>,
+}
+
+impl MockWeatherProvider {
+    /// Creates a mock with no responses queued.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a response to serve.
+    pub fn with_response(self, response: OWCurrentWeatherResponse) -> Self {
+        self.push(Ok(response));
+        self
+    }
+
+    /// Queues a response parsed from a current weather JSON body, e.g. one saved from the real API.
+    pub fn with_json(self, body: &str) -> Result<Self, OpenWxError> {
+        let response = crate::parse_current_weather(body)?;
+
+        Ok(self.with_response(response))
+    }
+
+    /// Queues a failure to serve, for testing how callers handle errors.
+    pub fn with_error(self, error: OpenWxError) -> Self {
+        self.push(Err(error));
+        self
+    }
+
+    /// Every position the mock has been asked about, in order
+    pub fn requests(&self) -> Vec<GeodeticCoords> {
+        self.requested
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn push(&self, response: Result<OWCurrentWeatherResponse, OpenWxError>) {
+        self.responses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push_back(response);
+    }
+}
+
+impl CurrentWeatherProvider for MockWeatherProvider {
+    fn current_weather(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        self.requested
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(coords);
+
+        self.responses
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+            .unwrap_or_else(|| panic!("MockWeatherProvider has no response queued for {coords:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::tests::FakeTransport;
+    use crate::types::tests::{ZOCCA_RESPONSE, zocca};
+
+    /// Stand-in for downstream logic which depends on the weather
+    fn describe(provider: &impl CurrentWeatherProvider, coords: GeodeticCoords) -> String {
+        match provider.current_weather(coords) {
+            Ok(weather) => format!("{} in {}", weather.weather[0].description, weather.name),
+            Err(e) if e.is_rate_limited() => "try again later".to_string(),
+            Err(e) => e.to_string(),
+        }
+    }
+
+    #[test]
+    fn mock_provider() {
+        let mock = MockWeatherProvider::new()
+            .with_response(zocca())
+            .with_json(ZOCCA_RESPONSE)
+            .unwrap()
+            .with_error(OpenWxError::ApiError {
+                code: 429,
+                message: "slow down".into(),
+                retry_after: None,
+            });
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        assert_eq!(describe(&mock, coords), "broken clouds in Zocca");
+        assert_eq!(describe(&mock, coords), "broken clouds in Zocca");
+        assert_eq!(describe(&mock, coords), "try again later");
+        assert_eq!(mock.requests(), vec![coords; 3]);
+    }
+
+    #[test]
+    fn client_provider() {
+        let client = OpenWeatherClient::with_transport(
+            "key".into(),
+            crate::WeatherUnits::Metric,
+            FakeTransport::serving(ZOCCA_RESPONSE),
+        );
+        let provider: Box<dyn CurrentWeatherProvider> = Box::new(client);
+        let coords = GeodeticCoords::new_checked(44.34, 10.99).unwrap();
+
+        assert_eq!(describe(&provider, coords), "broken clouds in Zocca");
+    }
+}
@@ -0,0 +1,521 @@
+//! Unit-aware quantities, so values reported in one unit system can't be mistaken for another
+
+use crate::{WeatherUnits, conversions};
+use serde::Deserialize;
+use std::fmt;
+use strum::Display;
+
+/// A temperature along with the units it was reported in, Kelvin for [`WeatherUnits::Standard`], Celsius for
+/// [`WeatherUnits::Metric`], and Fahrenheit for [`WeatherUnits::Imperial`]. Temperatures compare equal only if they
+/// have the same value in the same units, convert first to compare across units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    value: f32,
+    units: WeatherUnits,
+}
+
+impl Temperature {
+    /// Creates a temperature of `value` in `units`.
+    pub fn new(value: f32, units: WeatherUnits) -> Self {
+        Temperature { value, units }
+    }
+
+    /// The temperature in the units it was reported in
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// The units the temperature was reported in
+    pub fn units(&self) -> WeatherUnits {
+        self.units
+    }
+
+    /// The temperature in Kelvin
+    pub fn to_kelvin(&self) -> f32 {
+        conversions::celsius_to_kelvin(self.to_celsius())
+    }
+
+    /// The temperature in degrees Celsius
+    pub fn to_celsius(&self) -> f32 {
+        conversions::to_celsius(self.value, self.units)
+    }
+
+    /// The temperature in degrees Fahrenheit
+    pub fn to_fahrenheit(&self) -> f32 {
+        conversions::celsius_to_fahrenheit(self.to_celsius())
+    }
+
+    /// The same temperature converted to `units`
+    pub fn in_units(&self, units: WeatherUnits) -> Self {
+        let value = match units {
+            WeatherUnits::Standard => self.to_kelvin(),
+            WeatherUnits::Metric => self.to_celsius(),
+            WeatherUnits::Imperial => self.to_fahrenheit(),
+        };
+
+        Temperature { value, units }
+    }
+}
+
+/// Writes the value and unit symbol, e.g. `281.29 K`, respecting any precision like `{:.1}`.
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, self.value)?,
+            None => write!(f, "{}", self.value)?,
+        }
+
+        write!(f, " {}", self.units.temperature_symbol())
+    }
+}
+
+/// OpenWeather returns temperatures as bare numbers without their units, so they're deserialized as Kelvin (what
+/// OpenWeather reports when no units are requested) and re-labeled once the requested units are known.
+pub(crate) fn from_raw_temperature<'de, D>(deserializer: D) -> Result<Temperature, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = f32::deserialize(deserializer)?;
+
+    Ok(Temperature::new(value, WeatherUnits::Standard))
+}
+
+/// Serializes a [`Temperature`] back to a bare number in the units it was reported in.
+pub(crate) fn to_raw_temperature<S>(temp: &Temperature, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(temp.value)
+}
+
+/// Upper bounds of each Beaufort number in m/s
+const BEAUFORT_LIMITS_MPS: [f32; 12] = [
+    0.5, 1.6, 3.4, 5.5, 8.0, 10.8, 13.9, 17.2, 20.8, 24.5, 28.5, 32.7,
+];
+
+/// Kilometers per hour in one meter per second
+const KMH_PER_MPS: f32 = 3.6;
+
+/// A wind speed along with the units it was reported in, meters per second for [`WeatherUnits::Standard`] and
+/// [`WeatherUnits::Metric`], and miles per hour for [`WeatherUnits::Imperial`]. Like [`Temperature`], speeds
+/// compare equal only if they have the same value in the same units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindSpeed {
+    value: f32,
+    units: WeatherUnits,
+}
+
+impl WindSpeed {
+    /// Creates a wind speed of `value` in `units`.
+    pub fn new(value: f32, units: WeatherUnits) -> Self {
+        WindSpeed { value, units }
+    }
+
+    /// The speed in the units it was reported in
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// The units the speed was reported in
+    pub fn units(&self) -> WeatherUnits {
+        self.units
+    }
+
+    /// The speed in meters per second
+    pub fn to_mps(&self) -> f32 {
+        match self.units {
+            WeatherUnits::Standard | WeatherUnits::Metric => self.value,
+            WeatherUnits::Imperial => conversions::mph_to_mps(self.value),
+        }
+    }
+
+    /// The speed in kilometers per hour
+    pub fn to_kmh(&self) -> f32 {
+        self.to_mps() * KMH_PER_MPS
+    }
+
+    /// The speed in miles per hour
+    pub fn to_mph(&self) -> f32 {
+        match self.units {
+            WeatherUnits::Standard | WeatherUnits::Metric => conversions::mps_to_mph(self.value),
+            WeatherUnits::Imperial => self.value,
+        }
+    }
+
+    /// The speed in knots
+    pub fn to_knots(&self) -> f32 {
+        conversions::mps_to_knots(self.to_mps())
+    }
+
+    /// The speed on the Beaufort scale, 0 (calm) through 12 (hurricane force)
+    pub fn beaufort(&self) -> u8 {
+        let speed_mps = self.to_mps();
+
+        BEAUFORT_LIMITS_MPS
+            .iter()
+            .position(|limit| speed_mps < *limit)
+            .unwrap_or(BEAUFORT_LIMITS_MPS.len()) as u8
+    }
+
+    /// The same speed converted to `units`
+    pub fn in_units(&self, units: WeatherUnits) -> Self {
+        let value = match units {
+            WeatherUnits::Standard | WeatherUnits::Metric => self.to_mps(),
+            WeatherUnits::Imperial => self.to_mph(),
+        };
+
+        WindSpeed { value, units }
+    }
+}
+
+/// Writes the value and unit symbol, e.g. `2.69 m/s`, respecting any precision like `{:.1}`.
+impl fmt::Display for WindSpeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}", precision, self.value)?,
+            None => write!(f, "{}", self.value)?,
+        }
+
+        write!(f, " {}", self.units.wind_speed_symbol())
+    }
+}
+
+/// OpenWeather returns wind speeds as bare numbers without their units, so like temperatures they're deserialized
+/// in [`WeatherUnits::Standard`] and re-labeled once the requested units are known.
+pub(crate) fn from_raw_wind_speed<'de, D>(deserializer: D) -> Result<WindSpeed, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = f32::deserialize(deserializer)?;
+
+    Ok(WindSpeed::new(value, WeatherUnits::Standard))
+}
+
+/// Serializes a [`WindSpeed`] back to a bare number in the units it was reported in.
+pub(crate) fn to_raw_wind_speed<S>(speed: &WindSpeed, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(speed.value)
+}
+
+/// Same as [`from_raw_wind_speed`] for speeds OpenWeather sometimes omits, like gusts, use with `#[serde(default)]`.
+pub(crate) fn from_optional_raw_wind_speed<'de, D>(
+    deserializer: D,
+) -> Result<Option<WindSpeed>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<f32>::deserialize(deserializer)?;
+
+    Ok(value.map(|value| WindSpeed::new(value, WeatherUnits::Standard)))
+}
+
+/// Serializes an optional [`WindSpeed`] back to a bare number, or `null` if there isn't one.
+pub(crate) fn to_optional_raw_wind_speed<S>(
+    speed: &Option<WindSpeed>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match speed {
+        Some(speed) => serializer.serialize_some(&speed.value),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Inches of mercury in one hectopascal
+const INHG_PER_HPA: f32 = 0.029_53;
+
+/// Millimeters of mercury in one hectopascal
+const MMHG_PER_HPA: f32 = 0.750_062;
+
+/// An atmospheric pressure. OpenWeather always reports pressure in hectopascals regardless of the requested units,
+/// so unlike [`Temperature`] and [`WindSpeed`] there are no units to keep track of.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Pressure(f32);
+
+impl Pressure {
+    /// Creates a pressure of `hpa` hectopascals (equivalently, millibars).
+    pub fn from_hpa(hpa: f32) -> Self {
+        Pressure(hpa)
+    }
+
+    /// The pressure in hectopascals
+    pub fn hpa(&self) -> f32 {
+        self.0
+    }
+
+    /// The pressure in inches of mercury, as used in aviation altimeter settings
+    pub fn to_inhg(&self) -> f32 {
+        self.0 * INHG_PER_HPA
+    }
+
+    /// The pressure in millimeters of mercury
+    pub fn to_mmhg(&self) -> f32 {
+        self.0 * MMHG_PER_HPA
+    }
+}
+
+/// Writes the value in hectopascals, e.g. `1024 hPa`, respecting any precision like `{:.1}`.
+impl fmt::Display for Pressure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} hPa", precision, self.0),
+            None => write!(f, "{} hPa", self.0),
+        }
+    }
+}
+
+/// OpenWeather returns pressures as bare numbers of hectopascals, we wrap them in a [`Pressure`].
+pub(crate) fn from_raw_pressure<'de, D>(deserializer: D) -> Result<Pressure, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let hpa = f32::deserialize(deserializer)?;
+
+    Ok(Pressure::from_hpa(hpa))
+}
+
+/// Serializes a [`Pressure`] back to a bare number of hectopascals, the inverse of [`from_raw_pressure`].
+pub(crate) fn to_raw_pressure<S>(pressure: &Pressure, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(pressure.0)
+}
+
+/// Same as [`from_raw_pressure`] for pressures OpenWeather sometimes omits, like `grnd_level`, use with
+/// `#[serde(default)]`.
+pub(crate) fn from_optional_raw_pressure<'de, D>(
+    deserializer: D,
+) -> Result<Option<Pressure>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let hpa = Option::<f32>::deserialize(deserializer)?;
+
+    Ok(hpa.map(Pressure::from_hpa))
+}
+
+/// Serializes an optional [`Pressure`] back to a bare number of hectopascals, or `null` if there isn't one.
+pub(crate) fn to_optional_raw_pressure<S>(
+    pressure: &Option<Pressure>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match pressure {
+        Some(pressure) => serializer.serialize_some(&pressure.0),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Rate in mm/h below which precipitation is light, per the American Meteorological Society's rain classification
+const MODERATE_PRECIP_MM_PER_HOUR: f32 = 2.5;
+
+/// Rate in mm/h at or above which precipitation is heavy
+const HEAVY_PRECIP_MM_PER_HOUR: f32 = 7.6;
+
+/// How hard it's raining or snowing, see [`PrecipRate::intensity`]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PrecipIntensity {
+    Light,
+    Moderate,
+    Heavy,
+}
+
+/// A precipitation rate in millimeters per hour of liquid water equivalent. OpenWeather reports these in mm/h
+/// regardless of the requested units.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct PrecipRate(f32);
+
+impl PrecipRate {
+    /// Creates a rate of `mm` millimeters per hour.
+    pub fn from_mm_per_hour(mm: f32) -> Self {
+        PrecipRate(mm)
+    }
+
+    /// The rate in millimeters per hour
+    pub fn mm_per_hour(&self) -> f32 {
+        self.0
+    }
+
+    /// The rate in inches per hour
+    pub fn to_inches_per_hour(&self) -> f32 {
+        conversions::mm_to_inches(self.0)
+    }
+
+    /// Classifies the rate as light (below 2.5 mm/h), moderate, or heavy (7.6 mm/h and above). The thresholds are
+    /// for rain, snow is applied the same thresholds by its liquid equivalent.
+    pub fn intensity(&self) -> PrecipIntensity {
+        if self.0 >= HEAVY_PRECIP_MM_PER_HOUR {
+            PrecipIntensity::Heavy
+        } else if self.0 >= MODERATE_PRECIP_MM_PER_HOUR {
+            PrecipIntensity::Moderate
+        } else {
+            PrecipIntensity::Light
+        }
+    }
+}
+
+/// Writes the value in millimeters per hour, e.g. `2.73 mm/h`, respecting any precision like `{:.1}`.
+impl fmt::Display for PrecipRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} mm/h", precision, self.0),
+            None => write!(f, "{} mm/h", self.0),
+        }
+    }
+}
+
+/// OpenWeather returns precipitation rates as bare numbers of mm/h, which it omits entirely when there are none, use
+/// with `#[serde(default)]`.
+pub(crate) fn from_optional_raw_precip_rate<'de, D>(
+    deserializer: D,
+) -> Result<Option<PrecipRate>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mm = Option::<f32>::deserialize(deserializer)?;
+
+    Ok(mm.map(PrecipRate::from_mm_per_hour))
+}
+
+/// Serializes an optional [`PrecipRate`] back to a bare number of mm/h, or `null` if there isn't one.
+pub(crate) fn to_optional_raw_precip_rate<S>(
+    rate: &Option<PrecipRate>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match rate {
+        Some(rate) => serializer.serialize_some(&rate.0),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// An altitude or height, stored in meters and readable in either meters or feet
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Altitude(f32);
+
+impl Altitude {
+    /// Creates an altitude of `m` meters.
+    pub fn from_meters(m: f32) -> Self {
+        Altitude(m)
+    }
+
+    /// Creates an altitude of `ft` feet.
+    pub fn from_feet(ft: f32) -> Self {
+        Altitude(conversions::feet_to_meters(ft))
+    }
+
+    /// The altitude in meters
+    pub fn meters(&self) -> f32 {
+        self.0
+    }
+
+    /// The altitude in feet
+    pub fn feet(&self) -> f32 {
+        conversions::meters_to_feet(self.0)
+    }
+}
+
+/// Writes the value in meters, e.g. `1524 m`, respecting any precision like `{:.1}`.
+impl fmt::Display for Altitude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*} m", precision, self.0),
+            None => write!(f, "{} m", self.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn temperature_conversions() {
+        let freezing = Temperature::new(32.0, WeatherUnits::Imperial);
+        assert_close(freezing.to_celsius(), 0.0);
+        assert_close(freezing.to_kelvin(), 273.15);
+        assert_close(freezing.to_fahrenheit(), 32.0);
+
+        let boiling =
+            Temperature::new(100.0, WeatherUnits::Metric).in_units(WeatherUnits::Imperial);
+        assert_eq!(boiling.units(), WeatherUnits::Imperial);
+        assert_close(boiling.value(), 212.0);
+
+        // Equality doesn't convert between units
+        assert_ne!(
+            Temperature::new(0.0, WeatherUnits::Metric),
+            Temperature::new(273.15, WeatherUnits::Standard)
+        );
+    }
+
+    #[test]
+    fn wind_speed_conversions() {
+        let breeze = WindSpeed::new(10.0, WeatherUnits::Metric);
+        assert_close(breeze.to_kmh(), 36.0);
+        assert_close(breeze.to_mph(), 22.369);
+        assert!((breeze.to_knots() - 19.44).abs() < 0.01);
+        assert_eq!(breeze.beaufort(), 5);
+
+        let imperial = breeze.in_units(WeatherUnits::Imperial);
+        assert_close(imperial.value(), 22.369);
+        assert_close(imperial.to_mps(), 10.0);
+        assert_eq!(imperial.to_string(), format!("{} mph", imperial.value()));
+
+        assert_eq!(WindSpeed::new(0.2, WeatherUnits::Metric).beaufort(), 0);
+        assert_eq!(WindSpeed::new(40.0, WeatherUnits::Metric).beaufort(), 12);
+        assert_eq!(WindSpeed::new(40.0, WeatherUnits::Imperial).beaufort(), 8);
+    }
+
+    #[test]
+    fn pressure_conversions() {
+        let pressure = Pressure::from_hpa(1013.25);
+        assert!((pressure.to_inhg() - 29.92).abs() < 0.01);
+        assert!((pressure.to_mmhg() - 760.0).abs() < 0.01);
+        assert_eq!(format!("{pressure:.0}"), "1013 hPa");
+    }
+
+    #[test]
+    fn precip_rate_intensity() {
+        let rate = PrecipRate::from_mm_per_hour(25.4);
+        assert_close(rate.to_inches_per_hour(), 1.0);
+        assert_eq!(rate.intensity(), PrecipIntensity::Heavy);
+
+        assert_eq!(
+            PrecipRate::from_mm_per_hour(0.2).intensity(),
+            PrecipIntensity::Light
+        );
+        assert_eq!(
+            PrecipRate::from_mm_per_hour(2.5).intensity(),
+            PrecipIntensity::Moderate
+        );
+        assert_eq!(
+            PrecipRate::from_mm_per_hour(7.6).intensity(),
+            PrecipIntensity::Heavy
+        );
+    }
+
+    #[test]
+    fn display_temperature() {
+        assert_eq!(
+            Temperature::new(281.29, WeatherUnits::Standard).to_string(),
+            "281.29 K"
+        );
+        assert_eq!(
+            format!("{:.1}", Temperature::new(8.14, WeatherUnits::Metric)),
+            "8.1 °C"
+        );
+    }
+}
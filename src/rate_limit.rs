@@ -0,0 +1,120 @@
+//! Client-side throttling to stay within OpenWeather's call quotas
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Token bucket rate limiter. Allows bursts of up to `calls` requests, then throttles to an average of `calls` per
+/// `per`. Callers which exceed the rate are put to sleep rather than rejected, and are served in the order they
+/// arrived.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    tokens_per_sec: f64,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Negative when callers are already queued waiting for tokens
+    tokens: f64,
+    last_refill: Instant,
+}
+
+#[derive(Error, Debug)]
+pub enum RateLimiterError {
+    #[error("a rate limit must allow at least one call over a nonzero period")]
+    InvalidRate,
+}
+
+/// Calls per minute allowed on OpenWeather's free tier
+const FREE_TIER_CALLS_PER_MINUTE: u32 = 60;
+
+impl RateLimiter {
+    /// Creates a limiter allowing `calls` requests per `per`, starting with a full bucket.
+    pub fn new_checked(calls: u32, per: Duration) -> Result<Self, RateLimiterError> {
+        if calls == 0 || per.is_zero() {
+            return Err(RateLimiterError::InvalidRate);
+        }
+
+        Ok(RateLimiter {
+            capacity: calls as f64,
+            tokens_per_sec: calls as f64 / per.as_secs_f64(),
+            bucket: Mutex::new(Bucket {
+                tokens: calls as f64,
+                last_refill: Instant::now(),
+            }),
+        })
+    }
+
+    /// A limiter matching the free tier's 60 calls per minute.
+    pub fn free_tier() -> Self {
+        Self::new_checked(FREE_TIER_CALLS_PER_MINUTE, Duration::from_secs(60))
+            .expect("the free tier rate is valid")
+    }
+
+    /// Blocks until a call is allowed.
+    pub fn acquire(&self) {
+        let wait = self.reserve(Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Takes a token at `now` and returns how long the caller must wait before it's theirs
+    fn reserve(&self, now: Instant) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+
+        let elapsed = now.saturating_duration_since(bucket.last_refill);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * self.tokens_per_sec).min(self.capacity);
+        bucket.last_refill = now;
+
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            return Duration::ZERO;
+        }
+
+        Duration::from_secs_f64(-bucket.tokens / self.tokens_per_sec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bursts_then_throttles() {
+        let limiter = RateLimiter::new_checked(2, Duration::from_secs(1)).unwrap();
+        let start = Instant::now();
+
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+        assert_eq!(limiter.reserve(start), Duration::ZERO);
+
+        // The bucket is empty, so callers queue up half a second apart
+        assert_eq!(limiter.reserve(start), Duration::from_millis(500));
+        assert_eq!(limiter.reserve(start), Duration::from_millis(1000));
+
+        // After waiting out the queue, the rate holds steady
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(later), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn refills_up_to_capacity() {
+        let limiter = RateLimiter::new_checked(2, Duration::from_secs(1)).unwrap();
+        let start = Instant::now();
+
+        let much_later = start + Duration::from_secs(60);
+        for _ in 0..2 {
+            assert_eq!(limiter.reserve(much_later), Duration::ZERO);
+        }
+        assert!(!limiter.reserve(much_later).is_zero());
+    }
+
+    #[test]
+    fn invalid_rates() {
+        assert!(RateLimiter::new_checked(0, Duration::from_secs(1)).is_err());
+        assert!(RateLimiter::new_checked(1, Duration::ZERO).is_err());
+    }
+}
@@ -0,0 +1,357 @@
+//! Human-oriented renderings of weather responses
+
+use crate::{
+    AirQualityIndex, OWAirPollutionResponse, OWCurrentWeatherResponse, OWForecastResponse,
+    OWWeather, OWWind, Temperature, WeatherUnits, hazard::GALE_BEAUFORT,
+};
+use chrono::{DateTime, FixedOffset, Utc};
+
+impl OWCurrentWeatherResponse {
+    /// Describes the current weather as a short spoken-style paragraph, suitable for a voice assistant.
+    ///
+    /// For example: "In Zocca it's currently 8 degrees Celsius with broken clouds. Winds are light at 3 meters per
+    /// second from the south. Humidity is 95 percent and the sun sets at 4:50 PM local time."
+    pub fn narrate(&self) -> String {
+        let mut narration = format!(
+            "In {} it's currently {:.0} {}",
+            self.name,
+            self.main.temp.value(),
+            temperature_words(self.units)
+        );
+
+        if let Some(condition) = self.primary_condition() {
+            narration.push_str(&format!(" with {}", condition.description));
+        }
+        narration.push_str(". ");
+
+        if let Some(wind) = self.wind() {
+            match wind.beaufort() {
+                0 => narration.push_str("Winds are calm. "),
+                beaufort => narration.push_str(&format!(
+                    "Winds are {} at {:.0} {} from the {}. ",
+                    wind_strength_words(beaufort),
+                    wind.speed.value(),
+                    wind_speed_words(self.units),
+                    wind.deg.compass_point().name()
+                )),
+            }
+        }
+
+        narration.push_str(&format!(
+            "Humidity is {:.0} percent and the sun sets at {} local time.",
+            self.main.humidity.value(),
+            self.sunset_local().format("%-I:%M %p")
+        ));
+
+        narration
+    }
+
+    /// Summarizes the current weather on a single line, with the next sunrise or sunset.
+    ///
+    /// For example: "Zocca: 8°C (feels 6°C), broken clouds, wind 3 m/s from S, humidity 95%, sunrise 7:10 AM"
+    pub fn summary(&self) -> String {
+        self.styled_summary(&PlainSummary)
+    }
+
+    /// Like [`Self::summary`], with its parts decorated by `style`
+    pub fn styled_summary(&self, style: &dyn SummaryStyle) -> String {
+        let mut summary = format!(
+            "{}: {} (feels {})",
+            self.name,
+            styled_temperature(self.main.temp, self.units, style),
+            styled_temperature(self.main.feels_like, self.units, style)
+        );
+
+        if let Some(condition) = self.primary_condition() {
+            summary.push_str(&format!(", {}", styled_condition(condition, style)));
+        }
+        if let Some(wind) = self.wind() {
+            summary.push_str(&format!(", {}", wind_summary(wind, self.units, style)));
+        }
+        summary.push_str(&format!(", humidity {:.0}%", self.main.humidity.value()));
+
+        // Until sunset the sunset is the next event, after it this is the sunrise of the observation's day
+        if self.is_daytime() {
+            summary.push_str(&format!(
+                ", sunset {}",
+                self.sunset_local().format("%-I:%M %p")
+            ));
+            summary
+        } else {
+            summary.push_str(&format!(
+                ", sunrise {}",
+                self.sunrise_local().format("%-I:%M %p")
+            ));
+            style.night(summary)
+        }
+    }
+}
+
+impl OWForecastResponse {
+    /// Summarizes the forecast with a line per entry under the city's name, times are local to the city.
+    ///
+    /// For example: "Thu 9 AM: 54°F, light rain, wind 12 mph from WSW, 80% chance of precipitation"
+    pub fn summary(&self) -> String {
+        self.styled_summary(&PlainSummary)
+    }
+
+    /// Like [`Self::summary`], with its parts decorated by `style`. Entries count as nighttime if their time of day
+    /// is outside the city's sunrise and sunset.
+    pub fn styled_summary(&self, style: &dyn SummaryStyle) -> String {
+        let timezone = self.city.timezone;
+        let daylight = self.city.sunrise.with_timezone(&timezone).time()
+            ..self.city.sunset.with_timezone(&timezone).time();
+
+        let mut summary = format!("{}, {}", self.city.name, self.city.country);
+        for entry in &self.list {
+            let time = local_time(entry.dt, timezone);
+            let mut line = format!(
+                "{}: {}",
+                time.format("%a %-I %p"),
+                styled_temperature(entry.main.temp, self.units, style)
+            );
+
+            if let Some(condition) = entry.weather.first() {
+                line.push_str(&format!(", {}", styled_condition(condition, style)));
+            }
+            if let Some(wind) = &entry.wind {
+                line.push_str(&format!(", {}", wind_summary(wind, self.units, style)));
+            }
+            if entry.pop > 0.0 {
+                line.push_str(&format!(
+                    ", {:.0}% chance of precipitation",
+                    entry.pop * 100.0
+                ));
+            }
+
+            if !daylight.contains(&time.time()) {
+                line = style.night(line);
+            }
+            summary.push('\n');
+            summary.push_str(&line);
+        }
+
+        summary
+    }
+}
+
+impl OWAirPollutionResponse {
+    /// Summarizes the air quality with a line per reading, giving the index and the particulate and ozone
+    /// concentrations. The response has no timezone, so times are in UTC.
+    ///
+    /// For example: "Thu 9 AM UTC: Fair, PM2.5 12 μg/m³, PM10 18 μg/m³, O3 68 μg/m³"
+    pub fn summary(&self) -> String {
+        self.styled_summary(&PlainSummary)
+    }
+
+    /// Like [`Self::summary`], with its parts decorated by `style`. Poor and very poor air quality count as severe.
+    pub fn styled_summary(&self, style: &dyn SummaryStyle) -> String {
+        let utc = FixedOffset::east_opt(0).expect("UTC is a valid offset");
+
+        self.list
+            .iter()
+            .map(|entry| {
+                let aqi = entry.main.aqi.to_string();
+                let aqi = if entry.main.aqi >= AirQualityIndex::Poor {
+                    style.severe(aqi)
+                } else {
+                    aqi
+                };
+
+                format!(
+                    "{} UTC: {aqi}, PM2.5 {:.0} μg/m³, PM10 {:.0} μg/m³, O3 {:.0} μg/m³",
+                    local_time(entry.dt, utc).format("%a %-I %p"),
+                    entry.components.pm2_5,
+                    entry.components.pm10,
+                    entry.components.o3
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Decorates the parts of a summary, e.g. with terminal colors. Every method leaves its text unchanged by default.
+pub trait SummaryStyle {
+    /// A temperature, along with its value in Celsius whatever units it's displayed in
+    fn temperature(&self, text: String, _celsius: f32) -> String {
+        text
+    }
+
+    /// A condition notable enough to stand out, e.g. a thunderstorm, gale, or poor air quality
+    fn severe(&self, text: String) -> String {
+        text
+    }
+
+    /// A whole line describing a time when the sun is down
+    fn night(&self, text: String) -> String {
+        text
+    }
+}
+
+/// Leaves summaries undecorated
+pub struct PlainSummary;
+
+impl SummaryStyle for PlainSummary {}
+
+/// A temperature rounded to a whole number with its unit symbol, e.g. "68°F"
+fn styled_temperature(temp: Temperature, units: WeatherUnits, style: &dyn SummaryStyle) -> String {
+    style.temperature(
+        format!("{:.0}{}", temp.value(), units.temperature_symbol()),
+        temp.to_celsius(),
+    )
+}
+
+/// A condition's description, severe if it's a hazard
+fn styled_condition(condition: &OWWeather, style: &dyn SummaryStyle) -> String {
+    match condition.id.hazard() {
+        Some(_) => style.severe(condition.description.clone()),
+        None => condition.description.clone(),
+    }
+}
+
+/// Wind speed and the compass point it's coming from, e.g. "wind 6 mph from SW", or "calm". Gales are severe.
+fn wind_summary(wind: &OWWind, units: WeatherUnits, style: &dyn SummaryStyle) -> String {
+    let beaufort = wind.beaufort();
+    if beaufort == 0 {
+        return "calm".to_string();
+    }
+
+    let summary = format!(
+        "wind {:.0} {} from {}",
+        wind.speed.value(),
+        units.wind_speed_symbol(),
+        wind.deg.compass_point().abbreviation()
+    );
+
+    if beaufort >= GALE_BEAUFORT {
+        style.severe(summary)
+    } else {
+        summary
+    }
+}
+
+/// A UNIX time in a timezone
+fn local_time(dt: u64, timezone: FixedOffset) -> DateTime<FixedOffset> {
+    DateTime::<Utc>::from_timestamp(dt as i64, 0)
+        .unwrap_or_default()
+        .with_timezone(&timezone)
+}
+
+/// Spoken name of the temperature unit
+fn temperature_words(units: WeatherUnits) -> &'static str {
+    match units {
+        WeatherUnits::Standard => "kelvin",
+        WeatherUnits::Metric => "degrees Celsius",
+        WeatherUnits::Imperial => "degrees Fahrenheit",
+    }
+}
+
+/// Spoken name of the wind speed unit
+fn wind_speed_words(units: WeatherUnits) -> &'static str {
+    match units {
+        WeatherUnits::Standard | WeatherUnits::Metric => "meters per second",
+        WeatherUnits::Imperial => "miles per hour",
+    }
+}
+
+/// Loose description of a nonzero Beaufort number
+fn wind_strength_words(beaufort: u8) -> &'static str {
+    match beaufort {
+        0..=3 => "light",
+        4..=5 => "moderate",
+        6..=7 => "strong",
+        _ => "gale force",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WeatherConditionId;
+    use crate::forecast::tests::FORECAST_RESPONSE;
+    use crate::types::tests::zocca;
+
+    #[test]
+    fn narrate_sample() {
+        let mut response = zocca().with_units(WeatherUnits::Metric);
+        response.main.temp = Temperature::new(8.14, WeatherUnits::Metric);
+
+        assert_eq!(
+            response.narrate(),
+            "In Zocca it's currently 8 degrees Celsius with broken clouds. Winds are light at 3 meters per second \
+            from the south. Humidity is 95 percent and the sun sets at 4:50 PM local time."
+        );
+    }
+
+    #[test]
+    fn narrate_without_wind() {
+        let mut response = zocca();
+        response.wind = None;
+
+        assert_eq!(
+            response.narrate(),
+            "In Zocca it's currently 281 kelvin with broken clouds. Humidity is 95 percent and the sun sets at \
+            4:50 PM local time."
+        );
+    }
+
+    #[test]
+    fn summary_sample() {
+        let mut response = zocca().with_units(WeatherUnits::Metric);
+        response.main.temp = Temperature::new(8.14, WeatherUnits::Metric);
+        response.main.feels_like = Temperature::new(6.48, WeatherUnits::Metric);
+
+        assert_eq!(
+            response.summary(),
+            "Zocca: 8°C (feels 6°C), broken clouds, wind 3 m/s from S, humidity 95%, sunrise 7:10 AM"
+        );
+
+        response.dt = response.sys.sunset.timestamp() as u64 - 60;
+        assert!(response.summary().ends_with(", sunset 4:50 PM"));
+    }
+
+    /// Marks up each part of a summary so tests can see which were styled
+    struct Brackets;
+
+    impl SummaryStyle for Brackets {
+        fn temperature(&self, text: String, celsius: f32) -> String {
+            format!("<{text}={celsius:.0}C>")
+        }
+
+        fn severe(&self, text: String) -> String {
+            format!("!{text}!")
+        }
+
+        fn night(&self, text: String) -> String {
+            format!("({text})")
+        }
+    }
+
+    #[test]
+    fn styled_summary_parts() {
+        let mut response = zocca();
+        response.weather[0].id = WeatherConditionId::HeavyThunderstorm;
+        response.weather[0].description = "heavy thunderstorm".into();
+
+        assert_eq!(
+            response.styled_summary(&Brackets),
+            "(Zocca: <281K=8C> (feels <280K=6C>), !heavy thunderstorm!, wind 3 m/s from S, humidity 95%, \
+            sunrise 7:10 AM)"
+        );
+    }
+
+    #[test]
+    fn forecast_summary() {
+        let forecast: OWForecastResponse = serde_json::from_str(FORECAST_RESPONSE).unwrap();
+
+        let summary = forecast.summary();
+        let lines: Vec<_> = summary.lines().collect();
+        assert_eq!(lines.len(), forecast.list.len() + 1);
+        assert_eq!(
+            lines[0],
+            format!("{}, {}", forecast.city.name, forecast.city.country)
+        );
+        assert!(lines[1].contains(&forecast.list[0].weather[0].description));
+    }
+}
@@ -0,0 +1,178 @@
+//! Retrying transient failures with exponential backoff
+
+use crate::OpenWxError;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How an [`crate::OpenWeatherClient`] retries failed requests. Network failures, rate limiting (429), and server
+/// errors (5xx) are retried with exponential backoff and jitter, or after the server's `Retry-After` when it sends
+/// one. Requests which could have side effects, like creating a trigger, are only retried when rate limited since
+/// OpenWeather won't have acted on them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first, 1 disables retries
+    pub max_attempts: u32,
+
+    /// Backoff before the first retry, doubling on each subsequent retry
+    pub base_delay: Duration,
+
+    /// Longest the client will wait between attempts. A `Retry-After` longer than this fails the request instead.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs `request` until it succeeds, fails permanently, or runs out of attempts. `idempotent` requests are retried
+    /// on any transient failure, others only when rate limited.
+    pub(crate) fn run<T>(
+        &self,
+        idempotent: bool,
+        mut request: impl FnMut() -> Result<T, OpenWxError>,
+    ) -> Result<T, OpenWxError> {
+        let mut attempt = 1;
+
+        loop {
+            let error = match request() {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+
+            let retryable = if idempotent {
+                error.is_transient()
+            } else {
+                error.is_rate_limited()
+            };
+            if !retryable || attempt >= self.max_attempts {
+                return Err(error);
+            }
+
+            let delay = match error.retry_after() {
+                Some(retry_after) if retry_after > self.max_delay => return Err(error),
+                Some(retry_after) => retry_after,
+                None => self.backoff(attempt),
+            };
+            std::thread::sleep(delay);
+
+            attempt += 1;
+        }
+    }
+
+    /// Delay before retry number `attempt`, somewhere between half and all of the exponential backoff so that clients
+    /// which failed together don't retry together
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+
+        let jitter = random_fraction() * exponential.as_secs_f64() / 2.0;
+
+        exponential / 2 + Duration::from_secs_f64(jitter)
+    }
+}
+
+/// A random number in [0, 1), good enough for jitter without pulling in a random number generator
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn api_error(code: u16, retry_after: Option<Duration>) -> OpenWxError {
+        OpenWxError::ApiError {
+            code,
+            message: String::new(),
+            retry_after,
+        }
+    }
+
+    fn quick_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let attempts = Cell::new(0);
+        let result = quick_policy().run(true, || {
+            attempts.set(attempts.get() + 1);
+            match attempts.get() {
+                1 => Err(api_error(503, None)),
+                2 => Err(api_error(429, Some(Duration::from_millis(1)))),
+                _ => Ok("ok"),
+            }
+        });
+
+        assert_eq!(result.unwrap(), "ok");
+        assert_eq!(attempts.get(), 3);
+    }
+
+    #[test]
+    fn gives_up() {
+        // Out of attempts
+        let attempts = Cell::new(0);
+        let result: Result<(), _> = quick_policy().run(true, || {
+            attempts.set(attempts.get() + 1);
+            Err(api_error(500, None))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts.get(), 3);
+
+        // Permanent failures aren't retried
+        let attempts = Cell::new(0);
+        let _: Result<(), _> = quick_policy().run(true, || {
+            attempts.set(attempts.get() + 1);
+            Err(api_error(401, None))
+        });
+        assert_eq!(attempts.get(), 1);
+
+        // Nor are server errors on requests with side effects
+        let attempts = Cell::new(0);
+        let _: Result<(), _> = quick_policy().run(false, || {
+            attempts.set(attempts.get() + 1);
+            Err(api_error(502, None))
+        });
+        assert_eq!(attempts.get(), 1);
+
+        // Nor a Retry-After longer than we're willing to wait
+        let attempts = Cell::new(0);
+        let _: Result<(), _> = quick_policy().run(true, || {
+            attempts.set(attempts.get() + 1);
+            Err(api_error(429, Some(Duration::from_secs(3600))))
+        });
+        assert_eq!(attempts.get(), 1);
+    }
+
+    #[test]
+    fn backoff_grows_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+
+        for (attempt, full) in [(1, 100), (2, 200), (3, 400), (8, 1000)] {
+            let delay = policy.backoff(attempt);
+            let full = Duration::from_millis(full);
+            assert!(delay >= full / 2 && delay <= full, "{delay:?} for {full:?}");
+        }
+    }
+}
@@ -0,0 +1,295 @@
+//! Types for OpenWeather's Road Risk API, weather and road conditions along a route
+
+use crate::{GeodeticCoords, from_unix_offset, to_unix_offset};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::Display;
+
+/// A point along a route along with when the vehicle is expected to reach it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoadRiskWaypoint {
+    pub coords: GeodeticCoords,
+    pub dt: DateTime<Utc>,
+}
+
+/// JSON body of a Road Risk request for a track of waypoints
+pub(crate) fn road_risk_body(track: &[RoadRiskWaypoint]) -> String {
+    let track: Vec<_> = track
+        .iter()
+        .map(|waypoint| {
+            json!({
+                "lat": waypoint.coords.lat,
+                "lon": waypoint.coords.lon,
+                "dt": waypoint.dt.timestamp(),
+            })
+        })
+        .collect();
+
+    json!({ "track": track }).to_string()
+}
+
+/// Conditions at a single waypoint of a Road Risk response, more details
+/// [here](https://openweathermap.org/api/road-risk).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWRoadRiskEntry {
+    /// Time of the forecast for this waypoint
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
+    pub dt: DateTime<Utc>,
+
+    /// Latitude and longitude of the waypoint, in that order
+    pub coord: [f32; 2],
+
+    pub weather: Option<OWRoadRiskWeather>,
+
+    /// National weather alerts in effect at the waypoint
+    #[serde(default)]
+    pub alerts: Vec<OWRoadRiskAlert>,
+
+    pub road: Option<OWRoad>,
+}
+
+impl OWRoadRiskEntry {
+    /// Coordinates of the waypoint
+    pub fn coords(&self) -> GeodeticCoords {
+        GeodeticCoords {
+            lat: self.coord[0],
+            lon: self.coord[1],
+        }
+    }
+}
+
+/// Weather at a waypoint, Road Risk always reports in standard units
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWRoadRiskWeather {
+    /// Air temperature, Kelvin
+    pub temp: f32,
+
+    /// Wind speed, meter/sec
+    pub wind_speed: f32,
+
+    /// Wind direction, degrees (meteorological)
+    pub wind_deg: f32,
+
+    /// Precipitation intensity, mm/h
+    pub precipitation_intensity: Option<f32>,
+
+    /// Visibility, meter
+    pub visibility: Option<f32>,
+
+    /// Dew point, Kelvin
+    pub dew_point: f32,
+}
+
+/// A national weather alert in effect at a waypoint
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWRoadRiskAlert {
+    /// Name of the alert source
+    pub sender_name: String,
+
+    /// Alert event name
+    pub event: String,
+
+    /// Severity of the alert, 0 is unknown, then 1 (green) through 4 (red)
+    pub event_level: u8,
+}
+
+/// Road surface conditions at a waypoint
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWRoad {
+    #[serde(
+        deserialize_with = "from_raw_road_state",
+        serialize_with = "to_raw_road_state"
+    )]
+    pub state: RoadState,
+
+    /// Road surface temperature, Kelvin
+    pub temp: f32,
+}
+
+/// State of the road surface as reported by Road Risk
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum RoadState {
+    #[strum(serialize = "no report")]
+    NoReport,
+    #[strum(serialize = "dry")]
+    Dry,
+    #[strum(serialize = "moist")]
+    Moist,
+    #[strum(serialize = "moist and chemically treated")]
+    MoistChemicallyTreated,
+    #[strum(serialize = "wet")]
+    Wet,
+    #[strum(serialize = "wet and chemically treated")]
+    WetChemicallyTreated,
+    #[strum(serialize = "ice")]
+    Ice,
+    #[strum(serialize = "frost")]
+    Frost,
+    #[strum(serialize = "snow")]
+    Snow,
+    #[strum(serialize = "snow/ice watch")]
+    SnowIceWatch,
+    #[strum(serialize = "snow/ice warning")]
+    SnowIceWarning,
+    #[strum(serialize = "wet above freezing")]
+    WetAboveFreezing,
+    #[strum(serialize = "wet below freezing")]
+    WetBelowFreezing,
+    #[strum(serialize = "absorption")]
+    Absorption,
+    #[strum(serialize = "absorption at dewpoint")]
+    AbsorptionAtDewpoint,
+    #[strum(serialize = "dew")]
+    Dew,
+    #[strum(serialize = "black ice warning")]
+    BlackIceWarning,
+    /// Reported as "other", also used for codes this crate doesn't know about
+    #[strum(serialize = "other")]
+    Other,
+    #[strum(serialize = "slush")]
+    Slush,
+}
+
+impl RoadState {
+    /// Maps Road Risk's numeric state codes onto a [`RoadState`], unrecognized codes map to [`RoadState::Other`].
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            0 => RoadState::NoReport,
+            1 => RoadState::Dry,
+            2 => RoadState::Moist,
+            3 => RoadState::MoistChemicallyTreated,
+            4 => RoadState::Wet,
+            5 => RoadState::WetChemicallyTreated,
+            6 => RoadState::Ice,
+            7 => RoadState::Frost,
+            8 => RoadState::Snow,
+            9 => RoadState::SnowIceWatch,
+            10 => RoadState::SnowIceWarning,
+            11 => RoadState::WetAboveFreezing,
+            12 => RoadState::WetBelowFreezing,
+            13 => RoadState::Absorption,
+            14 => RoadState::AbsorptionAtDewpoint,
+            15 => RoadState::Dew,
+            16 => RoadState::BlackIceWarning,
+            18 => RoadState::Slush,
+            _ => RoadState::Other,
+        }
+    }
+
+    /// Road Risk's numeric code for this state, the inverse of [`RoadState::from_code`]
+    pub fn code(&self) -> u8 {
+        match self {
+            RoadState::NoReport => 0,
+            RoadState::Dry => 1,
+            RoadState::Moist => 2,
+            RoadState::MoistChemicallyTreated => 3,
+            RoadState::Wet => 4,
+            RoadState::WetChemicallyTreated => 5,
+            RoadState::Ice => 6,
+            RoadState::Frost => 7,
+            RoadState::Snow => 8,
+            RoadState::SnowIceWatch => 9,
+            RoadState::SnowIceWarning => 10,
+            RoadState::WetAboveFreezing => 11,
+            RoadState::WetBelowFreezing => 12,
+            RoadState::Absorption => 13,
+            RoadState::AbsorptionAtDewpoint => 14,
+            RoadState::Dew => 15,
+            RoadState::BlackIceWarning => 16,
+            RoadState::Other => 17,
+            RoadState::Slush => 18,
+        }
+    }
+
+    /// Whether the surface is likely slippery
+    pub fn is_hazardous(&self) -> bool {
+        matches!(
+            self,
+            RoadState::Ice
+                | RoadState::Frost
+                | RoadState::Snow
+                | RoadState::SnowIceWarning
+                | RoadState::WetBelowFreezing
+                | RoadState::BlackIceWarning
+                | RoadState::Slush
+        )
+    }
+}
+
+/// Custom deserializer for Road Risk's numeric road state codes
+fn from_raw_road_state<'de, D>(deserializer: D) -> Result<RoadState, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let code = u8::deserialize(deserializer)?;
+
+    Ok(RoadState::from_code(code))
+}
+
+/// Custom serializer for road states, writing them back as Road Risk's numeric codes
+fn to_raw_road_state<S>(state: &RoadState, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_u8(state.code())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const ROAD_RISK_RESPONSE: &str = r#"
+        [
+            {
+            "dt": 1602702000,
+            "coord": [7.27, 44.04],
+            "weather": {
+                "temp": 278.44, "wind_speed": 2.27, "wind_deg": 7,
+                "precipitation_intensity": 0.38, "dew_point": 276.13
+            },
+            "alerts": [
+                { "sender_name": "METEO-FRANCE", "event": "Moderate thunderstorm warning", "event_level": 2 }
+            ],
+            "road": { "state": 6, "temp": 272.9 }
+            }
+        ]
+        "#;
+
+    #[test]
+    fn parse_road_risk() {
+        let entries: Vec<OWRoadRiskEntry> = serde_json::from_str(ROAD_RISK_RESPONSE).unwrap();
+
+        let entry = &entries[0];
+        assert_eq!(entry.coords().lat, 7.27);
+        assert_eq!(entry.alerts[0].event_level, 2);
+
+        let road = entry.road.as_ref().unwrap();
+        assert_eq!(road.state, RoadState::Ice);
+        assert!(road.state.is_hazardous());
+        assert_eq!(RoadState::from_code(200), RoadState::Other);
+    }
+
+    #[test]
+    fn road_state_codes() {
+        for code in 0..=18 {
+            assert_eq!(RoadState::from_code(code).code(), code);
+        }
+        assert_eq!(RoadState::from_code(200).code(), 17);
+    }
+
+    #[test]
+    fn request_body() {
+        let waypoint = RoadRiskWaypoint {
+            coords: GeodeticCoords::new_checked(7.27, 44.04).unwrap(),
+            dt: DateTime::from_timestamp(1602702000, 0).unwrap(),
+        };
+
+        let body: serde_json::Value = serde_json::from_str(&road_risk_body(&[waypoint])).unwrap();
+        assert_eq!(body["track"][0]["dt"], 1602702000);
+        assert_eq!(body["track"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,241 @@
+//! Deduplicating concurrent identical requests from the async client
+
+use crate::OpenWxError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Requests currently in flight, keyed by URL. The first caller for a URL sends the request and every caller which
+/// arrives before it completes waits for and shares its response.
+#[derive(Debug, Default)]
+pub(crate) struct SingleFlight {
+    flights: Mutex<HashMap<String, Arc<Flight>>>,
+}
+
+#[derive(Debug, Default)]
+struct Flight {
+    state: Mutex<FlightState>,
+}
+
+#[derive(Debug, Default)]
+struct FlightState {
+    outcome: Option<Result<String, OpenWxError>>,
+
+    /// The caller sending the request was dropped before it finished, so someone else has to send it
+    abandoned: bool,
+
+    waiters: Vec<Waker>,
+}
+
+impl SingleFlight {
+    /// Runs `request` unless an identical one is already in flight, in which case its response is shared instead.
+    pub(crate) async fn run<F>(
+        &self,
+        key: String,
+        request: impl FnOnce() -> F,
+    ) -> Result<String, OpenWxError>
+    where
+        F: Future<Output = Result<String, OpenWxError>>,
+    {
+        let mut request = Some(request);
+
+        loop {
+            let (flight, leading) = {
+                let mut flights = lock(&self.flights);
+                match flights.get(&key) {
+                    Some(flight) => (flight.clone(), false),
+                    None => {
+                        let flight = Arc::new(Flight::default());
+                        flights.insert(key.clone(), flight.clone());
+                        (flight, true)
+                    }
+                }
+            };
+
+            if leading && let Some(request) = request.take() {
+                let mut lead = Lead {
+                    flights: self,
+                    key: &key,
+                    flight: &flight,
+                    finished: false,
+                };
+                let result = request().await;
+                lead.finish(&result);
+
+                return result;
+            }
+
+            if let Some(result) = (Wait { flight: &flight }).await {
+                return result;
+            }
+        }
+    }
+}
+
+/// Held by the caller sending a request, lands the flight for everyone waiting on it even if that caller is dropped
+struct Lead<'a> {
+    flights: &'a SingleFlight,
+    key: &'a str,
+    flight: &'a Arc<Flight>,
+    finished: bool,
+}
+
+impl Lead<'_> {
+    fn finish(&mut self, result: &Result<String, OpenWxError>) {
+        let mut state = lock(&self.flight.state);
+        state.outcome = Some(share(result));
+        state.waiters.drain(..).for_each(Waker::wake);
+        self.finished = true;
+    }
+}
+
+impl Drop for Lead<'_> {
+    fn drop(&mut self) {
+        let mut flights = lock(&self.flights.flights);
+        if flights
+            .get(self.key)
+            .is_some_and(|flight| Arc::ptr_eq(flight, self.flight))
+        {
+            flights.remove(self.key);
+        }
+
+        if !self.finished {
+            let mut state = lock(&self.flight.state);
+            state.abandoned = true;
+            state.waiters.drain(..).for_each(Waker::wake);
+        }
+    }
+}
+
+/// Waits for a flight to land, `None` if it was abandoned
+struct Wait<'a> {
+    flight: &'a Flight,
+}
+
+impl Future for Wait<'_> {
+    type Output = Option<Result<String, OpenWxError>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = lock(&self.flight.state);
+
+        if let Some(outcome) = &state.outcome {
+            return Poll::Ready(Some(share(outcome)));
+        }
+        if state.abandoned {
+            return Poll::Ready(None);
+        }
+
+        state.waiters.push(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+/// A copy of a response for another caller. Most errors can't be cloned, so API errors are copied and anything else
+/// is described.
+fn share(result: &Result<String, OpenWxError>) -> Result<String, OpenWxError> {
+    match result {
+        Ok(body) => Ok(body.clone()),
+        Err(OpenWxError::ApiError {
+            code,
+            message,
+            retry_after,
+        }) => Err(OpenWxError::ApiError {
+            code: *code,
+            message: message.clone(),
+            retry_after: *retry_after,
+        }),
+        Err(OpenWxError::CoalescedRequestFailed(reason)) => {
+            Err(OpenWxError::CoalescedRequestFailed(reason.clone()))
+        }
+        Err(error) => Err(OpenWxError::CoalescedRequestFailed(error.to_string())),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Future which is pending until `gate` holds a response
+    struct Gated<'a> {
+        gate: &'a Mutex<Option<Result<String, OpenWxError>>>,
+    }
+
+    impl Future for Gated<'_> {
+        type Output = Result<String, OpenWxError>;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Self::Output> {
+            match lock(self.gate).take() {
+                Some(result) => Poll::Ready(result),
+                None => Poll::Pending,
+            }
+        }
+    }
+
+    fn poll<F: Future>(future: Pin<&mut F>) -> Poll<F::Output> {
+        future.poll(&mut Context::from_waker(Waker::noop()))
+    }
+
+    #[test]
+    fn concurrent_requests_share_a_response() {
+        let flights = SingleFlight::default();
+        let gate = Mutex::new(None);
+        let sent = AtomicUsize::new(0);
+        let request = || {
+            sent.fetch_add(1, Ordering::SeqCst);
+            Gated { gate: &gate }
+        };
+
+        let mut first = Box::pin(flights.run("/weather".into(), request));
+        let mut second = Box::pin(flights.run("/weather".into(), request));
+        assert!(poll(first.as_mut()).is_pending());
+        assert!(poll(second.as_mut()).is_pending());
+
+        *lock(&gate) = Some(Err(OpenWxError::ApiError {
+            code: 503,
+            message: "Service Unavailable".into(),
+            retry_after: None,
+        }));
+        let Poll::Ready(Err(first)) = poll(first.as_mut()) else {
+            panic!("the first request should have failed");
+        };
+        let Poll::Ready(Err(second)) = poll(second.as_mut()) else {
+            panic!("the second request should have shared the failure");
+        };
+        assert_eq!(first.to_string(), second.to_string());
+        assert_eq!(sent.load(Ordering::SeqCst), 1);
+
+        // Once landed, the next request is sent afresh
+        *lock(&gate) = Some(Ok("body".into()));
+        let mut third = Box::pin(flights.run("/weather".into(), request));
+        assert!(matches!(poll(third.as_mut()), Poll::Ready(Ok(body)) if body == "body"));
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn abandoned_requests_are_resent() {
+        let flights = SingleFlight::default();
+        let gate = Mutex::new(None);
+        let sent = AtomicUsize::new(0);
+        let request = || {
+            sent.fetch_add(1, Ordering::SeqCst);
+            Gated { gate: &gate }
+        };
+
+        let mut first = Box::pin(flights.run("/weather".into(), request));
+        let mut second = Box::pin(flights.run("/weather".into(), request));
+        assert!(poll(first.as_mut()).is_pending());
+        assert!(poll(second.as_mut()).is_pending());
+
+        drop(first);
+        *lock(&gate) = Some(Ok("body".into()));
+        assert!(matches!(poll(second.as_mut()), Poll::Ready(Ok(body)) if body == "body"));
+        assert_eq!(sent.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,155 @@
+//! Smoothing readings across repeated polls of the API
+
+use crate::{OWMain, OWWind, Temperature, WeatherUnits, WindSpeed};
+use thiserror::Error;
+
+/// Exponential moving average over successive readings of temperature and wind speed, useful for damping jitter and
+/// outliers when polling repeatedly. Readings are averaged in Celsius and m/s, so they may be requested in any units.
+#[derive(Debug)]
+pub struct Smoother {
+    alpha: f32,
+    temp_celsius: Option<f32>,
+    wind_speed_mps: Option<f32>,
+
+    /// Units of the latest reading, which the smoothed values are reported in
+    units: WeatherUnits,
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SmootherError {
+    #[error("provided smoothing factor of `{0}` is outside the valid range (0, 1]")]
+    InvalidAlpha(f32),
+}
+
+/// The smoothed values after feeding a [`Smoother`] a reading, in the units of that reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Smoothed {
+    pub temp: Temperature,
+
+    /// `None` until a reading which includes wind has been seen
+    pub wind_speed: Option<WindSpeed>,
+}
+
+impl Smoother {
+    /// Creates a new [`Smoother`], `alpha` is the weight given to each new reading. Values near 1 track new readings
+    /// closely, values near 0 smooth heavily.
+    pub fn new_checked(alpha: f32) -> Result<Self, SmootherError> {
+        if !(alpha > 0.0 && alpha <= 1.0) {
+            return Err(SmootherError::InvalidAlpha(alpha));
+        }
+
+        Ok(Smoother {
+            alpha,
+            temp_celsius: None,
+            wind_speed_mps: None,
+            units: WeatherUnits::default(),
+        })
+    }
+
+    /// Feeds the next reading into the average and returns the updated smoothed values. The first reading seeds the
+    /// average as-is.
+    pub fn update(&mut self, main: &OWMain, wind: Option<&OWWind>) -> Smoothed {
+        self.units = main.temp.units();
+        let temp_celsius = ema(self.temp_celsius, main.temp.to_celsius(), self.alpha);
+        self.temp_celsius = Some(temp_celsius);
+
+        if let Some(wind) = wind {
+            self.wind_speed_mps = Some(ema(self.wind_speed_mps, wind.speed.to_mps(), self.alpha));
+        }
+
+        self.smoothed(temp_celsius)
+    }
+
+    /// The current smoothed values, `None` until the first reading
+    pub fn current(&self) -> Option<Smoothed> {
+        self.temp_celsius
+            .map(|temp_celsius| self.smoothed(temp_celsius))
+    }
+
+    /// Converts the running averages back to the units of the latest reading
+    fn smoothed(&self, temp_celsius: f32) -> Smoothed {
+        Smoothed {
+            temp: Temperature::new(temp_celsius, WeatherUnits::Metric).in_units(self.units),
+            wind_speed: self
+                .wind_speed_mps
+                .map(|mps| WindSpeed::new(mps, WeatherUnits::Metric).in_units(self.units)),
+        }
+    }
+}
+
+/// One step of an exponential moving average
+fn ema(previous: Option<f32>, reading: f32, alpha: f32) -> f32 {
+    match previous {
+        Some(prev) => alpha * reading + (1.0 - alpha) * prev,
+        None => reading,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+    use crate::{Temperature, WeatherUnits};
+
+    #[test]
+    fn converges_on_step_change() {
+        let mut response = zocca();
+        let mut smoother = Smoother::new_checked(0.5).unwrap();
+        assert!(smoother.current().is_none());
+
+        let celsius = |value| Temperature::new(value, WeatherUnits::Metric);
+        response.main.temp = celsius(0.0);
+        assert_eq!(smoother.update(&response.main, None).temp, celsius(0.0));
+
+        // Each reading closes half of the remaining gap to the new value
+        response.main.temp = celsius(10.0);
+        let expected = [5.0, 7.5, 8.75, 9.375];
+        for temp in expected {
+            let smoothed = smoother.update(&response.main, None);
+            assert_eq!(smoothed.temp, celsius(temp));
+            assert_eq!(smoothed.wind_speed, None);
+        }
+
+        let mut wind = response.wind().unwrap().clone();
+        wind.speed = WindSpeed::new(2.69, WeatherUnits::Metric);
+        let smoothed = smoother.update(&response.main, Some(&wind));
+        assert_eq!(
+            smoothed.wind_speed,
+            Some(WindSpeed::new(2.69, WeatherUnits::Metric))
+        );
+    }
+
+    #[test]
+    fn averages_across_units() {
+        let mut response = zocca();
+        let mut smoother = Smoother::new_checked(0.5).unwrap();
+
+        response.main.temp = Temperature::new(283.15, WeatherUnits::Standard);
+        smoother.update(&response.main, None);
+
+        // 68 °F is 20 °C, so the average lands halfway at 15 °C, reported in the latest reading's units
+        response.main.temp = Temperature::new(68.0, WeatherUnits::Imperial);
+        let mut wind = response.wind().unwrap().clone();
+        wind.speed = WindSpeed::new(10.0, WeatherUnits::Imperial);
+        let smoothed = smoother.update(&response.main, Some(&wind));
+
+        assert_eq!(smoothed.temp.units(), WeatherUnits::Imperial);
+        assert!((smoothed.temp.to_celsius() - 15.0).abs() < 1e-3);
+        let wind_speed = smoothed.wind_speed.unwrap();
+        assert_eq!(wind_speed.units(), WeatherUnits::Imperial);
+        assert!((wind_speed.value() - 10.0).abs() < 1e-3);
+
+        let current = smoother.current().unwrap();
+        assert_eq!(current, smoothed);
+    }
+
+    #[test]
+    fn invalid_alpha() {
+        assert_eq!(
+            Smoother::new_checked(0.0).unwrap_err(),
+            SmootherError::InvalidAlpha(0.0)
+        );
+        assert!(Smoother::new_checked(1.5).is_err());
+        assert!(Smoother::new_checked(f32::NAN).is_err());
+    }
+}
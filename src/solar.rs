@@ -0,0 +1,86 @@
+//! Types for OpenWeather's solar radiation API
+
+use crate::GeodeticCoords;
+use serde::{Deserialize, Serialize};
+
+/// OpenWeather response from the solar radiation API, more details
+/// [here](https://openweathermap.org/api/solar-radiation).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWSolarRadiationResponse {
+    pub coord: GeodeticCoords,
+
+    /// Readings in chronological order, a single entry for current conditions or one per hour for forecasts and
+    /// history
+    pub list: Vec<OWSolarRadiationEntry>,
+}
+
+/// Solar radiation at a single moment
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWSolarRadiationEntry {
+    /// Time of the reading, UNIX time in seconds, UTC
+    pub dt: u64,
+
+    pub radiation: OWRadiation,
+}
+
+/// Irradiance on the ground, all in W/m2. The `_cs` variants are for clear sky conditions
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWRadiation {
+    /// Global Horizontal Irradiance
+    pub ghi: f32,
+
+    /// Direct Normal Irradiance
+    pub dni: f32,
+
+    /// Diffuse Horizontal Irradiance
+    pub dhi: f32,
+
+    /// Global Horizontal Irradiance under clear sky
+    pub ghi_cs: f32,
+
+    /// Direct Normal Irradiance under clear sky
+    pub dni_cs: f32,
+
+    /// Diffuse Horizontal Irradiance under clear sky
+    pub dhi_cs: f32,
+}
+
+impl OWRadiation {
+    /// Share of the clear sky global horizontal irradiance actually reaching the ground, a rough measure of how much
+    /// cloud is getting in the way. `None` at night when there's no clear sky irradiance to compare against.
+    pub fn clear_sky_index(&self) -> Option<f32> {
+        if self.ghi_cs <= 0.0 {
+            return None;
+        }
+
+        Some(self.ghi / self.ghi_cs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_solar_radiation() {
+        let json = r#"
+            {
+            "coord": { "lon": -114.6244, "lat": 32.7243 },
+            "list": [
+                {
+                "radiation": {
+                    "ghi": 206.68, "dni": 2.27, "dhi": 204.83,
+                    "ghi_cs": 826.89, "dni_cs": 885.19, "dhi_cs": 122.68
+                },
+                "dt": 1618232400
+                }
+            ]
+            }
+            "#;
+
+        let response: OWSolarRadiationResponse = serde_json::from_str(json).unwrap();
+        let radiation = &response.list[0].radiation;
+        assert_eq!(radiation.dni, 2.27);
+        assert!((radiation.clear_sky_index().unwrap() - 0.25).abs() < 0.01);
+    }
+}
@@ -0,0 +1,269 @@
+//! Types for OpenWeather's Weather Stations API, for registering personal weather stations and pushing their
+//! measurements. Stations are managed through [`crate::OpenWeatherClient`].
+
+use crate::GeodeticCoords;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use strum::Display;
+
+/// A station to register
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewStation {
+    /// Your own identifier for the station
+    pub external_id: String,
+
+    pub name: String,
+
+    pub coords: GeodeticCoords,
+
+    /// Altitude of the station above sea level, meter
+    pub altitude: Option<f32>,
+}
+
+impl NewStation {
+    /// JSON body for registering this station
+    pub(crate) fn to_body(&self) -> String {
+        json!({
+            "external_id": self.external_id,
+            "name": self.name,
+            "latitude": self.coords.lat,
+            "longitude": self.coords.lon,
+            "altitude": self.altitude,
+        })
+        .to_string()
+    }
+}
+
+/// A registered station, more details [here](https://openweathermap.org/stations)
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWStation {
+    /// Station ID assigned by OpenWeather, used when pushing and fetching measurements
+    #[serde(alias = "ID")]
+    pub id: String,
+
+    pub external_id: String,
+
+    pub name: String,
+
+    pub latitude: f32,
+
+    pub longitude: f32,
+
+    /// Altitude of the station above sea level, meter
+    pub altitude: Option<f32>,
+
+    /// Quality rank OpenWeather assigns to the station
+    pub rank: Option<u32>,
+}
+
+impl OWStation {
+    /// Coordinates of the station
+    pub fn coords(&self) -> GeodeticCoords {
+        GeodeticCoords {
+            lat: self.latitude,
+            lon: self.longitude,
+        }
+    }
+}
+
+/// A single observation from a station, in metric units. Only the fields which are set are sent.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct StationMeasurement {
+    /// Station ID assigned by OpenWeather
+    pub station_id: String,
+
+    /// Time of the observation
+    pub dt: DateTime<Utc>,
+
+    /// Air temperature, Celsius
+    pub temperature: Option<f32>,
+
+    /// Wind speed, meter/sec
+    pub wind_speed: Option<f32>,
+
+    /// Wind gust, meter/sec
+    pub wind_gust: Option<f32>,
+
+    /// Wind direction, degrees (meteorological)
+    pub wind_deg: Option<f32>,
+
+    /// Atmospheric pressure, hPa
+    pub pressure: Option<f32>,
+
+    /// Humidity, %
+    pub humidity: Option<f32>,
+
+    /// Rain over the last hour, mm
+    pub rain_1h: Option<f32>,
+
+    /// Rain over the last 24 hours, mm
+    pub rain_24h: Option<f32>,
+
+    /// Snow over the last hour, mm
+    pub snow_1h: Option<f32>,
+}
+
+impl StationMeasurement {
+    fn to_json(&self) -> Value {
+        let mut measurement = Map::new();
+        measurement.insert("station_id".into(), json!(self.station_id));
+        measurement.insert("dt".into(), json!(self.dt.timestamp()));
+
+        let optional_fields = [
+            ("temperature", self.temperature),
+            ("wind_speed", self.wind_speed),
+            ("wind_gust", self.wind_gust),
+            ("wind_deg", self.wind_deg),
+            ("pressure", self.pressure),
+            ("humidity", self.humidity),
+            ("rain_1h", self.rain_1h),
+            ("rain_24h", self.rain_24h),
+            ("snow_1h", self.snow_1h),
+        ];
+        for (name, value) in optional_fields {
+            if let Some(value) = value {
+                measurement.insert(name.into(), json!(value));
+            }
+        }
+
+        Value::Object(measurement)
+    }
+}
+
+/// JSON body for pushing a batch of measurements
+pub(crate) fn measurements_body(measurements: &[StationMeasurement]) -> String {
+    let measurements: Vec<_> = measurements
+        .iter()
+        .map(StationMeasurement::to_json)
+        .collect();
+
+    Value::Array(measurements).to_string()
+}
+
+/// Time period measurements are aggregated over
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationPeriod {
+    #[strum(serialize = "m")]
+    Minute,
+    #[strum(serialize = "h")]
+    Hour,
+    #[strum(serialize = "d")]
+    Day,
+}
+
+/// Measurements from a station aggregated over an [`AggregationPeriod`]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAggregatedMeasurement {
+    /// Aggregation period, "m", "h", or "d"
+    #[serde(rename = "type")]
+    pub period: String,
+
+    /// Start of the period, UNIX time in seconds, UTC
+    pub date: u64,
+
+    pub station_id: String,
+
+    /// Air temperature, Celsius
+    pub temp: Option<OWAggregate>,
+
+    /// Humidity, %
+    pub humidity: Option<OWAggregate>,
+
+    /// Atmospheric pressure, hPa
+    pub pressure: Option<OWAggregate>,
+
+    pub wind: Option<OWAggregatedWind>,
+
+    pub precipitation: Option<OWAggregatedPrecipitation>,
+}
+
+/// Statistics of a value over an aggregation period
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAggregate {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub average: Option<f32>,
+
+    /// Number of measurements aggregated
+    pub weight: Option<u32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAggregatedWind {
+    /// Wind direction, degrees (meteorological)
+    pub deg: Option<f32>,
+
+    /// Wind speed, meter/sec
+    pub speed: Option<f32>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWAggregatedPrecipitation {
+    /// Rain, mm
+    pub rain: Option<f32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_station() {
+        let created = r#"
+            {
+            "ID": "583436dd9643a9000196b8d6", "updated_at": "2016-11-22T12:15:25.96727176Z",
+            "created_at": "2016-11-22T12:15:25.967271732Z", "user_id": "557066d0ff7a7e3897531d94",
+            "external_id": "SF_TEST001", "name": "San Francisco Test Station",
+            "longitude": -122.43, "latitude": 37.76, "altitude": 150, "rank": 10, "source_type": 5
+            }
+            "#;
+
+        let station: OWStation = serde_json::from_str(created).unwrap();
+        assert_eq!(station.id, "583436dd9643a9000196b8d6");
+        assert_eq!(station.coords().lat, 37.76);
+    }
+
+    #[test]
+    fn parse_aggregated_measurements() {
+        let json = r#"
+            [
+                {
+                "type": "h", "date": 1479817200, "station_id": "583436dd9643a9000196b8d6",
+                "temp": { "max": 7, "min": 7, "average": 7, "weight": 1 },
+                "humidity": { "average": 87, "weight": 1 },
+                "wind": { "deg": 270, "speed": 1.2 },
+                "pressure": { "min": 1020, "max": 1020, "average": 1020, "weight": 1 },
+                "precipitation": {}
+                }
+            ]
+            "#;
+
+        let measurements: Vec<OWAggregatedMeasurement> = serde_json::from_str(json).unwrap();
+        assert_eq!(measurements[0].temp.as_ref().unwrap().average, Some(7.0));
+        assert!(measurements[0].humidity.as_ref().unwrap().min.is_none());
+        assert!(
+            measurements[0]
+                .precipitation
+                .as_ref()
+                .unwrap()
+                .rain
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn measurement_body_skips_unset_fields() {
+        let measurement = StationMeasurement {
+            station_id: "583436dd9643a9000196b8d6".into(),
+            dt: DateTime::from_timestamp(1479817340, 0).unwrap(),
+            temperature: Some(18.7),
+            ..Default::default()
+        };
+
+        let body: Value = serde_json::from_str(&measurements_body(&[measurement])).unwrap();
+        assert_eq!(body[0]["dt"], 1479817340);
+        assert!(body[0].get("temperature").is_some());
+        assert!(body[0].get("humidity").is_none());
+    }
+}
@@ -0,0 +1,517 @@
+//! Logging observations to a local SQLite database, turning the crate into a small personal weather logger. This
+//! links the system `libsqlite3` directly and binds only the handful of functions the store needs.
+
+use crate::{OWCurrentWeatherResponse, WeatherUnits};
+use chrono::{DateTime, Utc};
+use std::ffi::{CStr, CString, c_int};
+use std::path::Path;
+use thiserror::Error;
+
+/// Version of the database layout this build reads and writes, stored in the database's `user_version`
+pub const SCHEMA_VERSION: i32 = 1;
+
+/// Statements bringing a database from version `i` to `i + 1`, applied in order on open
+const MIGRATIONS: [&str; SCHEMA_VERSION as usize] = ["
+    CREATE TABLE observations (
+        id INTEGER PRIMARY KEY,
+        location TEXT NOT NULL,
+        observed_at INTEGER NOT NULL,
+        units TEXT NOT NULL,
+        response TEXT NOT NULL
+    );
+    CREATE INDEX observations_by_location ON observations (location, observed_at);
+    CREATE INDEX observations_by_time ON observations (observed_at);
+"];
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("SQLite error {code}: {message}")]
+    Sqlite { code: i32, message: String },
+
+    #[error("database schema version {0} is newer than this build supports ({SCHEMA_VERSION})")]
+    UnsupportedSchema(i32),
+
+    #[error("`{0}` is not a usable database path")]
+    InvalidPath(String),
+
+    #[error("stored observation is not a valid response")]
+    CorruptObservation(#[from] serde_json::Error),
+}
+
+/// An observation read back out of an [`ObservationStore`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredObservation {
+    /// Name the observation was appended under
+    pub location: String,
+
+    /// Time of data calculation, from the response's `dt`
+    pub observed_at: DateTime<Utc>,
+
+    pub response: OWCurrentWeatherResponse,
+}
+
+/// Appends observations to a SQLite database and queries them back by location and time. Opening a database created
+/// by an older build migrates it to [`SCHEMA_VERSION`].
+pub struct ObservationStore {
+    db: Connection,
+}
+
+impl ObservationStore {
+    /// Opens the database at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, StorageError> {
+        let path = path.as_ref();
+        let path = path
+            .to_str()
+            .and_then(|p| CString::new(p).ok())
+            .ok_or_else(|| StorageError::InvalidPath(path.display().to_string()))?;
+
+        Self::migrated(Connection::open(&path)?)
+    }
+
+    /// A store which lives only as long as this value, mostly useful for testing.
+    pub fn in_memory() -> Result<Self, StorageError> {
+        Self::migrated(Connection::open(c":memory:")?)
+    }
+
+    fn migrated(db: Connection) -> Result<Self, StorageError> {
+        let store = ObservationStore { db };
+
+        let version = store.schema_version()?;
+        if version > SCHEMA_VERSION {
+            return Err(StorageError::UnsupportedSchema(version));
+        }
+        for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            store
+                .db
+                .transaction(&format!("{migration} PRAGMA user_version = {};", from + 1))?;
+        }
+
+        Ok(store)
+    }
+
+    /// The schema version recorded in the database
+    pub fn schema_version(&self) -> Result<i32, StorageError> {
+        let mut statement = self.db.prepare("PRAGMA user_version")?;
+        statement.step()?;
+
+        Ok(statement.column_int64(0) as i32)
+    }
+
+    /// Records an observation under a location name of the caller's choosing.
+    pub fn append(
+        &self,
+        location: &str,
+        response: &OWCurrentWeatherResponse,
+    ) -> Result<(), StorageError> {
+        let json = serde_json::to_string(response)?;
+
+        let mut statement = self.db.prepare(
+            "INSERT INTO observations (location, observed_at, units, response) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        statement.bind_text(1, location)?;
+        statement.bind_int64(2, response.dt as i64)?;
+        statement.bind_text(3, &response.units.to_string())?;
+        statement.bind_text(4, &json)?;
+        statement.step()?;
+
+        Ok(())
+    }
+
+    /// Distinct location names with at least one observation, sorted
+    pub fn locations(&self) -> Result<Vec<String>, StorageError> {
+        let mut statement = self
+            .db
+            .prepare("SELECT DISTINCT location FROM observations ORDER BY location")?;
+
+        let mut locations = Vec::new();
+        while statement.step()? {
+            locations.push(statement.column_text(0));
+        }
+
+        Ok(locations)
+    }
+
+    /// Every observation for a location, oldest first
+    pub fn for_location(&self, location: &str) -> Result<Vec<StoredObservation>, StorageError> {
+        self.select("location = ?1", Some(location), None)
+    }
+
+    /// Observations for every location made in `[start, end)`, oldest first
+    pub fn between(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<StoredObservation>, StorageError> {
+        self.select(
+            "observed_at >= ?2 AND observed_at < ?3",
+            None,
+            Some((start, end)),
+        )
+    }
+
+    /// Observations for a location made in `[start, end)`, oldest first
+    pub fn for_location_between(
+        &self,
+        location: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<StoredObservation>, StorageError> {
+        self.select(
+            "location = ?1 AND observed_at >= ?2 AND observed_at < ?3",
+            Some(location),
+            Some((start, end)),
+        )
+    }
+
+    fn select(
+        &self,
+        filter: &str,
+        location: Option<&str>,
+        range: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ) -> Result<Vec<StoredObservation>, StorageError> {
+        let mut statement = self.db.prepare(&format!(
+            "SELECT location, units, response FROM observations WHERE {filter} ORDER BY observed_at, id"
+        ))?;
+        if let Some(location) = location {
+            statement.bind_text(1, location)?;
+        }
+        if let Some((start, end)) = range {
+            statement.bind_int64(2, start.timestamp())?;
+            statement.bind_int64(3, end.timestamp())?;
+        }
+
+        let mut observations = Vec::new();
+        while statement.step()? {
+            let units: WeatherUnits = statement.column_text(1).parse().unwrap_or_default();
+            let response: OWCurrentWeatherResponse =
+                serde_json::from_str(&statement.column_text(2))?;
+
+            observations.push(StoredObservation {
+                location: statement.column_text(0),
+                observed_at: DateTime::from_timestamp(response.dt as i64, 0).unwrap_or_default(),
+                response: response.with_units(units),
+            });
+        }
+
+        Ok(observations)
+    }
+}
+
+/// An open database handle, closed on drop
+struct Connection(*mut ffi::sqlite3);
+
+impl Connection {
+    fn open(path: &CStr) -> Result<Self, StorageError> {
+        let mut db = std::ptr::null_mut();
+        // SAFETY: `path` is NUL terminated and `db` is written before it's read. SQLite allocates a handle even when
+        // opening fails, so it's closed on every path through the `Connection` drop.
+        let code = unsafe {
+            ffi::sqlite3_open_v2(
+                path.as_ptr(),
+                &mut db,
+                ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+                std::ptr::null(),
+            )
+        };
+        let connection = Connection(db);
+        connection.check(code)?;
+
+        Ok(connection)
+    }
+
+    fn execute_batch(&self, sql: &str) -> Result<(), StorageError> {
+        let sql = CString::new(sql).expect("SQL never contains NUL");
+        // SAFETY: the handle is open and `sql` is NUL terminated, no callback or error message out-pointer is used.
+        let code = unsafe {
+            ffi::sqlite3_exec(
+                self.0,
+                sql.as_ptr(),
+                None,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+
+        self.check(code)
+    }
+
+    /// Runs `sql` in a transaction, rolling it back if any statement fails so nothing is left half applied
+    fn transaction(&self, sql: &str) -> Result<(), StorageError> {
+        self.execute_batch("BEGIN")?;
+
+        let result = self
+            .execute_batch(sql)
+            .and_then(|()| self.execute_batch("COMMIT"));
+        if result.is_err() {
+            // The error that caused the rollback is the one worth reporting
+            let _ = self.execute_batch("ROLLBACK");
+        }
+
+        result
+    }
+
+    fn prepare(&self, sql: &str) -> Result<Statement<'_>, StorageError> {
+        let sql = CString::new(sql).expect("SQL never contains NUL");
+        let mut stmt = std::ptr::null_mut();
+        // SAFETY: the handle is open and `sql` is NUL terminated, `stmt` is only used if preparing succeeded.
+        let code = unsafe {
+            ffi::sqlite3_prepare_v2(self.0, sql.as_ptr(), -1, &mut stmt, std::ptr::null_mut())
+        };
+        self.check(code)?;
+
+        Ok(Statement { db: self, stmt })
+    }
+
+    /// Maps a result code to an error carrying SQLite's message for it
+    fn check(&self, code: c_int) -> Result<(), StorageError> {
+        if code == ffi::SQLITE_OK {
+            return Ok(());
+        }
+
+        // SAFETY: SQLite always returns a valid NUL terminated message for a handle, even a failed one.
+        let message = unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.0)) };
+        Err(StorageError::Sqlite {
+            code,
+            message: message.to_string_lossy().into_owned(),
+        })
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // SAFETY: every statement borrows the connection so they've all been finalized by now.
+        unsafe { ffi::sqlite3_close(self.0) };
+    }
+}
+
+/// A prepared statement, finalized on drop
+struct Statement<'a> {
+    db: &'a Connection,
+    stmt: *mut ffi::sqlite3_stmt,
+}
+
+impl Statement<'_> {
+    fn bind_text(&mut self, index: c_int, value: &str) -> Result<(), StorageError> {
+        // SAFETY: SQLITE_TRANSIENT makes SQLite copy the text before this returns.
+        let code = unsafe {
+            ffi::sqlite3_bind_text(
+                self.stmt,
+                index,
+                value.as_ptr().cast(),
+                value.len() as c_int,
+                ffi::SQLITE_TRANSIENT,
+            )
+        };
+
+        self.db.check(code)
+    }
+
+    fn bind_int64(&mut self, index: c_int, value: i64) -> Result<(), StorageError> {
+        // SAFETY: the statement is prepared and not yet finalized.
+        let code = unsafe { ffi::sqlite3_bind_int64(self.stmt, index, value) };
+
+        self.db.check(code)
+    }
+
+    /// Advances the statement, returning whether a row is available to read
+    fn step(&mut self) -> Result<bool, StorageError> {
+        // SAFETY: the statement is prepared and not yet finalized.
+        match unsafe { ffi::sqlite3_step(self.stmt) } {
+            ffi::SQLITE_ROW => Ok(true),
+            ffi::SQLITE_DONE => Ok(false),
+            code => self.db.check(code).map(|_| false),
+        }
+    }
+
+    fn column_int64(&self, column: c_int) -> i64 {
+        // SAFETY: only called after `step` produced a row.
+        unsafe { ffi::sqlite3_column_int64(self.stmt, column) }
+    }
+
+    fn column_text(&self, column: c_int) -> String {
+        // SAFETY: only called after `step` produced a row. The text pointer stays valid until the next step, and
+        // the length is read after the text as SQLite requires.
+        unsafe {
+            let text = ffi::sqlite3_column_text(self.stmt, column);
+            if text.is_null() {
+                return String::new();
+            }
+            let len = ffi::sqlite3_column_bytes(self.stmt, column) as usize;
+
+            String::from_utf8_lossy(std::slice::from_raw_parts(text, len)).into_owned()
+        }
+    }
+}
+
+impl Drop for Statement<'_> {
+    fn drop(&mut self) {
+        // SAFETY: the statement came from a successful prepare and is finalized exactly once.
+        unsafe { ffi::sqlite3_finalize(self.stmt) };
+    }
+}
+
+/// The subset of the SQLite C API the store uses
+#[allow(non_camel_case_types)]
+mod ffi {
+    use std::ffi::{c_char, c_int, c_uchar, c_void};
+
+    pub enum sqlite3 {}
+    pub enum sqlite3_stmt {}
+
+    pub const SQLITE_OK: c_int = 0;
+    pub const SQLITE_ROW: c_int = 100;
+    pub const SQLITE_DONE: c_int = 101;
+
+    pub const SQLITE_OPEN_READWRITE: c_int = 0x02;
+    pub const SQLITE_OPEN_CREATE: c_int = 0x04;
+
+    /// Destructor sentinel asking SQLite to copy bound values
+    pub const SQLITE_TRANSIENT: isize = -1;
+
+    #[link(name = "sqlite3")]
+    unsafe extern "C" {
+        pub fn sqlite3_open_v2(
+            filename: *const c_char,
+            db: *mut *mut sqlite3,
+            flags: c_int,
+            vfs: *const c_char,
+        ) -> c_int;
+        pub fn sqlite3_close(db: *mut sqlite3) -> c_int;
+        pub fn sqlite3_errmsg(db: *mut sqlite3) -> *const c_char;
+        pub fn sqlite3_exec(
+            db: *mut sqlite3,
+            sql: *const c_char,
+            callback: Option<
+                unsafe extern "C" fn(
+                    *mut c_void,
+                    c_int,
+                    *mut *mut c_char,
+                    *mut *mut c_char,
+                ) -> c_int,
+            >,
+            arg: *mut c_void,
+            errmsg: *mut *mut c_char,
+        ) -> c_int;
+        pub fn sqlite3_prepare_v2(
+            db: *mut sqlite3,
+            sql: *const c_char,
+            len: c_int,
+            stmt: *mut *mut sqlite3_stmt,
+            tail: *mut *const c_char,
+        ) -> c_int;
+        pub fn sqlite3_bind_text(
+            stmt: *mut sqlite3_stmt,
+            index: c_int,
+            value: *const c_char,
+            len: c_int,
+            destructor: isize,
+        ) -> c_int;
+        pub fn sqlite3_bind_int64(stmt: *mut sqlite3_stmt, index: c_int, value: i64) -> c_int;
+        pub fn sqlite3_step(stmt: *mut sqlite3_stmt) -> c_int;
+        pub fn sqlite3_column_int64(stmt: *mut sqlite3_stmt, column: c_int) -> i64;
+        pub fn sqlite3_column_text(stmt: *mut sqlite3_stmt, column: c_int) -> *const c_uchar;
+        pub fn sqlite3_column_bytes(stmt: *mut sqlite3_stmt, column: c_int) -> c_int;
+        pub fn sqlite3_finalize(stmt: *mut sqlite3_stmt) -> c_int;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+
+    fn observation_at(dt: u64) -> OWCurrentWeatherResponse {
+        let mut response = zocca().with_units(WeatherUnits::Metric);
+        response.dt = dt;
+        response
+    }
+
+    fn time(secs: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(secs, 0).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let store = ObservationStore::in_memory().unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+
+        store.append("zocca", &observation_at(100)).unwrap();
+
+        let stored = store.for_location("zocca").unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].location, "zocca");
+        assert_eq!(stored[0].observed_at, time(100));
+        assert_eq!(stored[0].response, observation_at(100));
+    }
+
+    #[test]
+    fn queries() {
+        let store = ObservationStore::in_memory().unwrap();
+        store.append("zocca", &observation_at(300)).unwrap();
+        store.append("zocca", &observation_at(100)).unwrap();
+        store.append("laguna", &observation_at(200)).unwrap();
+
+        assert_eq!(store.locations().unwrap(), ["laguna", "zocca"]);
+
+        let dts = |observations: Vec<StoredObservation>| -> Vec<u64> {
+            observations.iter().map(|o| o.response.dt).collect()
+        };
+        assert_eq!(dts(store.for_location("zocca").unwrap()), [100, 300]);
+        assert_eq!(
+            dts(store.between(time(100), time(300)).unwrap()),
+            [100, 200]
+        );
+        assert_eq!(
+            dts(store
+                .for_location_between("zocca", time(150), time(400))
+                .unwrap()),
+            [300]
+        );
+        assert!(store.for_location("nowhere").unwrap().is_empty());
+    }
+
+    #[test]
+    fn reopen_keeps_observations() {
+        let path = std::env::temp_dir().join(format!("openwx-storage-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        ObservationStore::open(&path)
+            .unwrap()
+            .append("zocca", &observation_at(100))
+            .unwrap();
+        let store = ObservationStore::open(&path).unwrap();
+        assert_eq!(store.for_location("zocca").unwrap().len(), 1);
+
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn newer_schema_rejected() {
+        let store = ObservationStore::in_memory().unwrap();
+        store.db.execute_batch("PRAGMA user_version = 99").unwrap();
+
+        assert!(matches!(
+            ObservationStore::migrated(store.db),
+            Err(StorageError::UnsupportedSchema(99))
+        ));
+    }
+
+    #[test]
+    fn failed_migration_rolled_back() {
+        let store = ObservationStore::in_memory().unwrap();
+        let migration =
+            "CREATE TABLE extra (x); INSERT INTO missing VALUES (1); PRAGMA user_version = 2;";
+
+        assert!(matches!(
+            store.db.transaction(migration),
+            Err(StorageError::Sqlite { .. })
+        ));
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+
+        // Nothing from the failed migration was kept, and no transaction was left open
+        store.db.transaction("CREATE TABLE extra (x);").unwrap();
+        store.append("zocca", &observation_at(1)).unwrap();
+        assert_eq!(store.locations().unwrap(), ["zocca"]);
+    }
+}
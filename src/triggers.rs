@@ -0,0 +1,266 @@
+//! Types for OpenWeather's Weather Triggers API, server-side alerts which fire when forecast conditions are met in an
+//! area. Triggers are managed through [`crate::OpenWeatherClient`].
+
+use crate::GeodeticCoords;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::time::Duration;
+use strum::Display;
+
+/// Weather parameters a trigger condition can watch
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerParameter {
+    /// Temperature, Kelvin
+    Temp,
+    /// Atmospheric pressure, hPa
+    Pressure,
+    /// Humidity, %
+    Humidity,
+    /// Wind speed, meter/sec
+    WindSpeed,
+    /// Wind direction, degrees (meteorological)
+    WindDirection,
+    /// Cloudiness, %
+    Clouds,
+}
+
+/// Comparisons a trigger condition can make against its threshold
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum TriggerComparison {
+    #[strum(serialize = "$gt")]
+    #[serde(rename = "$gt")]
+    GreaterThan,
+    #[strum(serialize = "$gte")]
+    #[serde(rename = "$gte")]
+    GreaterThanOrEqual,
+    #[strum(serialize = "$lt")]
+    #[serde(rename = "$lt")]
+    LessThan,
+    #[strum(serialize = "$lte")]
+    #[serde(rename = "$lte")]
+    LessThanOrEqual,
+    #[strum(serialize = "$eq")]
+    #[serde(rename = "$eq")]
+    Equal,
+    #[strum(serialize = "$ne")]
+    #[serde(rename = "$ne")]
+    NotEqual,
+}
+
+/// A single condition of a trigger, e.g. temperature above 300 K. Build with [`TriggerCondition::on`].
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct TriggerCondition {
+    #[serde(rename = "name")]
+    pub parameter: TriggerParameter,
+
+    #[serde(rename = "expression")]
+    pub comparison: TriggerComparison,
+
+    /// Threshold the parameter is compared against, always in standard units
+    pub amount: f32,
+}
+
+/// First half of a [`TriggerCondition`], waiting for a comparison
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TriggerConditionBuilder {
+    parameter: TriggerParameter,
+}
+
+impl TriggerCondition {
+    /// Starts a condition on `parameter`, e.g. `TriggerCondition::on(TriggerParameter::Temp).gt(300.0)`.
+    pub fn on(parameter: TriggerParameter) -> TriggerConditionBuilder {
+        TriggerConditionBuilder { parameter }
+    }
+}
+
+impl TriggerConditionBuilder {
+    fn compare(self, comparison: TriggerComparison, amount: f32) -> TriggerCondition {
+        TriggerCondition {
+            parameter: self.parameter,
+            comparison,
+            amount,
+        }
+    }
+
+    pub fn gt(self, amount: f32) -> TriggerCondition {
+        self.compare(TriggerComparison::GreaterThan, amount)
+    }
+
+    pub fn gte(self, amount: f32) -> TriggerCondition {
+        self.compare(TriggerComparison::GreaterThanOrEqual, amount)
+    }
+
+    pub fn lt(self, amount: f32) -> TriggerCondition {
+        self.compare(TriggerComparison::LessThan, amount)
+    }
+
+    pub fn lte(self, amount: f32) -> TriggerCondition {
+        self.compare(TriggerComparison::LessThanOrEqual, amount)
+    }
+
+    pub fn eq(self, amount: f32) -> TriggerCondition {
+        self.compare(TriggerComparison::Equal, amount)
+    }
+
+    pub fn ne(self, amount: f32) -> TriggerCondition {
+        self.compare(TriggerComparison::NotEqual, amount)
+    }
+}
+
+/// An area a trigger watches
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerArea {
+    Point(GeodeticCoords),
+
+    /// A closed ring of vertices, the first and last vertex must be the same
+    Polygon(Vec<GeodeticCoords>),
+}
+
+impl TriggerArea {
+    /// GeoJSON geometry for the area, which orders coordinates longitude first
+    fn to_geojson(&self) -> Value {
+        match self {
+            TriggerArea::Point(coords) => json!({
+                "type": "Point",
+                "coordinates": [coords.lon, coords.lat],
+            }),
+            TriggerArea::Polygon(vertices) => {
+                let ring: Vec<_> = vertices.iter().map(|v| [v.lon, v.lat]).collect();
+                json!({
+                    "type": "Polygon",
+                    "coordinates": [ring],
+                })
+            }
+        }
+    }
+}
+
+/// A trigger to create or replace
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewTrigger {
+    /// How long from now the trigger starts watching the forecast
+    pub start_after: Duration,
+
+    /// How long from now the trigger stops watching the forecast
+    pub end_after: Duration,
+
+    /// Conditions which must all be met for the trigger to fire
+    pub conditions: Vec<TriggerCondition>,
+
+    pub area: Vec<TriggerArea>,
+}
+
+impl NewTrigger {
+    /// JSON body for creating or updating this trigger
+    pub(crate) fn to_body(&self) -> String {
+        let conditions: Vec<_> = self
+            .conditions
+            .iter()
+            .map(|c| {
+                json!({
+                    "name": c.parameter.to_string(),
+                    "expression": c.comparison.to_string(),
+                    "amount": c.amount,
+                })
+            })
+            .collect();
+        let area: Vec<_> = self.area.iter().map(TriggerArea::to_geojson).collect();
+
+        json!({
+            "time_period": {
+                "start": { "expression": "after", "amount": self.start_after.as_millis() as u64 },
+                "end": { "expression": "after", "amount": self.end_after.as_millis() as u64 },
+            },
+            "conditions": conditions,
+            "area": area,
+        })
+        .to_string()
+    }
+}
+
+/// A trigger as stored by OpenWeather, more details [here](https://openweathermap.org/triggers)
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWTrigger {
+    /// Trigger ID, used to fetch, update, and delete the trigger
+    #[serde(rename = "_id")]
+    pub id: String,
+
+    pub time_period: OWTriggerTimePeriod,
+
+    pub conditions: Vec<TriggerCondition>,
+
+    /// GeoJSON geometries the trigger watches
+    pub area: Vec<Value>,
+
+    /// Alerts the trigger has fired, keyed by alert ID
+    #[serde(default)]
+    pub alerts: HashMap<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWTriggerTimePeriod {
+    pub start: OWTriggerTime,
+    pub end: OWTriggerTime,
+}
+
+/// A point in time relative to when the trigger is evaluated
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct OWTriggerTime {
+    /// "after" or "exact"
+    pub expression: String,
+
+    /// Milliseconds
+    pub amount: u64,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const TRIGGER_RESPONSE: &str = r#"
+        {
+        "__v": 0,
+        "_id": "5852816a9aaacb00153134a3",
+        "alerts": {},
+        "area": [{ "type": "Point", "_id": "5852816a9aaacb00153134a4", "coordinates": [53, 37] }],
+        "conditions": [{ "name": "temp", "expression": "$gt", "amount": 299, "_id": "5852816a9aaacb00153134a5" }],
+        "time_period": {
+            "end": { "amount": 432000000, "expression": "after" },
+            "start": { "amount": 132000000, "expression": "after" }
+        }
+        }
+        "#;
+
+    #[test]
+    fn parse_trigger() {
+        let trigger: OWTrigger = serde_json::from_str(TRIGGER_RESPONSE).unwrap();
+
+        assert_eq!(trigger.id, "5852816a9aaacb00153134a3");
+        assert_eq!(
+            trigger.conditions[0],
+            TriggerCondition::on(TriggerParameter::Temp).gt(299.0)
+        );
+        assert_eq!(trigger.time_period.start.amount, 132000000);
+    }
+
+    #[test]
+    fn trigger_body() {
+        let trigger = NewTrigger {
+            start_after: Duration::from_secs(132000),
+            end_after: Duration::from_secs(432000),
+            conditions: vec![TriggerCondition::on(TriggerParameter::WindSpeed).gte(20.0)],
+            area: vec![TriggerArea::Point(
+                GeodeticCoords::new_checked(37.0, 53.0).unwrap(),
+            )],
+        };
+
+        let body: Value = serde_json::from_str(&trigger.to_body()).unwrap();
+        assert_eq!(body["conditions"][0]["name"], "wind_speed");
+        assert_eq!(body["conditions"][0]["expression"], "$gte");
+        assert_eq!(body["area"][0]["coordinates"], json!([53.0, 37.0]));
+        assert_eq!(body["time_period"]["start"]["amount"], 132000000);
+    }
+}
@@ -1,20 +1,161 @@
-use chrono::{DateTime, FixedOffset, Utc};
-use serde::Deserialize;
-use strum::Display;
+use crate::{
+    PrecipRate, Pressure, Temperature, WindSpeed, from_optional_raw_precip_rate,
+    from_optional_raw_pressure, from_optional_raw_wind_speed, from_raw_pressure,
+    from_raw_temperature, from_raw_wind_speed, to_optional_raw_precip_rate,
+    to_optional_raw_pressure, to_optional_raw_wind_speed, to_raw_pressure, to_raw_temperature,
+    to_raw_wind_speed,
+};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
 use thiserror::Error;
 
 /// Available units for OpenWeather responses
-#[derive(Debug, Display)]
+#[derive(
+    Debug, Display, EnumString, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize,
+)]
 #[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum WeatherUnits {
     /// Standard is the default if the optional "units" parameter is not included in the request
+    #[default]
     Standard,
     Imperial,
     Metric,
 }
 
+impl WeatherUnits {
+    /// Symbol for temperatures reported in these units
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self {
+            WeatherUnits::Standard => "K",
+            WeatherUnits::Metric => "°C",
+            WeatherUnits::Imperial => "°F",
+        }
+    }
+
+    /// Symbol for wind speeds reported in these units
+    pub fn wind_speed_symbol(&self) -> &'static str {
+        match self {
+            WeatherUnits::Standard | WeatherUnits::Metric => "m/s",
+            WeatherUnits::Imperial => "mph",
+        }
+    }
+}
+
+/// Available response formats from the current weather API
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "lowercase")]
+pub enum ResponseMode {
+    /// JSON is the default, and the only format parsed into typed responses by this crate
+    Json,
+    Xml,
+    /// A pre-rendered HTML weather widget
+    Html,
+}
+
+/// Languages OpenWeather can localize descriptions into, e.g. `weather[].description`. Displays as, and parses from,
+/// the code OpenWeather expects in the `lang` parameter. Note some codes aren't ISO 639-1, e.g. `cz` for Czech and
+/// `kr` for Korean.
+#[derive(Debug, Display, EnumString, Clone, Copy, PartialEq, Eq)]
+#[strum(ascii_case_insensitive)]
+pub enum Language {
+    #[strum(serialize = "af")]
+    Afrikaans,
+    #[strum(serialize = "al")]
+    Albanian,
+    #[strum(serialize = "ar")]
+    Arabic,
+    #[strum(serialize = "az")]
+    Azerbaijani,
+    #[strum(serialize = "bg")]
+    Bulgarian,
+    #[strum(serialize = "ca")]
+    Catalan,
+    #[strum(serialize = "cz")]
+    Czech,
+    #[strum(serialize = "da")]
+    Danish,
+    #[strum(serialize = "de")]
+    German,
+    #[strum(serialize = "el")]
+    Greek,
+    #[strum(serialize = "en")]
+    English,
+    #[strum(serialize = "eu")]
+    Basque,
+    #[strum(serialize = "fa")]
+    Persian,
+    #[strum(serialize = "fi")]
+    Finnish,
+    #[strum(serialize = "fr")]
+    French,
+    #[strum(serialize = "gl")]
+    Galician,
+    #[strum(serialize = "he")]
+    Hebrew,
+    #[strum(serialize = "hi")]
+    Hindi,
+    #[strum(serialize = "hr")]
+    Croatian,
+    #[strum(serialize = "hu")]
+    Hungarian,
+    #[strum(serialize = "id")]
+    Indonesian,
+    #[strum(serialize = "it")]
+    Italian,
+    #[strum(serialize = "ja")]
+    Japanese,
+    #[strum(serialize = "kr")]
+    Korean,
+    #[strum(serialize = "la")]
+    Latvian,
+    #[strum(serialize = "lt")]
+    Lithuanian,
+    #[strum(serialize = "mk")]
+    Macedonian,
+    #[strum(serialize = "no")]
+    Norwegian,
+    #[strum(serialize = "nl")]
+    Dutch,
+    #[strum(serialize = "pl")]
+    Polish,
+    #[strum(serialize = "pt")]
+    Portuguese,
+    #[strum(serialize = "pt_br")]
+    BrazilianPortuguese,
+    #[strum(serialize = "ro")]
+    Romanian,
+    #[strum(serialize = "ru")]
+    Russian,
+    #[strum(serialize = "sv")]
+    Swedish,
+    #[strum(serialize = "sk")]
+    Slovak,
+    #[strum(serialize = "sl")]
+    Slovenian,
+    #[strum(serialize = "sp")]
+    Spanish,
+    #[strum(serialize = "sr")]
+    Serbian,
+    #[strum(serialize = "th")]
+    Thai,
+    #[strum(serialize = "tr")]
+    Turkish,
+    #[strum(serialize = "ua")]
+    Ukrainian,
+    #[strum(serialize = "vi")]
+    Vietnamese,
+    #[strum(serialize = "zh_cn")]
+    SimplifiedChinese,
+    #[strum(serialize = "zh_tw")]
+    TraditionalChinese,
+    #[strum(serialize = "zu")]
+    Zulu,
+}
+
 /// Geodetic coordinates, latitude and longitude
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
 pub struct GeodeticCoords {
     /// Latitude of the location
     pub lat: f32,
@@ -26,16 +167,32 @@ pub struct GeodeticCoords {
 impl GeodeticCoords {
     /// Creates a new [`GeodeticCoords`] and fails if the provided points are invalid.
     pub fn new_checked(lat: f32, lon: f32) -> Result<GeodeticCoords, GeodeticCoordsError> {
-        if lat < -90.0 || lat > 90.0 {
+        if !(-90.0..=90.0).contains(&lat) {
             return Err(GeodeticCoordsError::LatitudeOutOfRange(lat));
         }
 
-        if lon < -180.0 || lon > 180.0 {
+        if !(-180.0..=180.0).contains(&lon) {
             return Err(GeodeticCoordsError::LongitudeOutOfRange(lon));
         }
 
         Ok(GeodeticCoords { lat, lon })
     }
+
+    /// Great-circle distance to `other` in meters, using the haversine formula on a spherical Earth.
+    pub fn distance_to(&self, other: &GeodeticCoords) -> f32 {
+        const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+        let (lat1, lat2) = (
+            (self.lat as f64).to_radians(),
+            (other.lat as f64).to_radians(),
+        );
+        let d_lat = lat2 - lat1;
+        let d_lon = (other.lon as f64 - self.lon as f64).to_radians();
+
+        let a = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+
+        (2.0 * EARTH_RADIUS_M * a.sqrt().asin()) as f32
+    }
 }
 
 #[derive(Error, Debug, PartialEq)]
@@ -47,8 +204,30 @@ pub enum GeodeticCoordsError {
     LongitudeOutOfRange(f32),
 }
 
+/// A position with a name of the caller's choosing, for tools which watch several places at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamedLocation {
+    pub name: String,
+    pub coords: GeodeticCoords,
+}
+
+impl NamedLocation {
+    pub fn new(name: impl Into<String>, coords: GeodeticCoords) -> Self {
+        NamedLocation {
+            name: name.into(),
+            coords,
+        }
+    }
+}
+
 /// OpenWeather response from the current weather API, more details [here](https://openweathermap.org/current).
-#[derive(Deserialize, Debug)]
+///
+/// OpenWeather is inconsistent about whether numeric fields are written as integers (`1024`) or floats (`1024.0`)
+/// across responses, every `f32` field here accepts either representation.
+///
+/// Responses, like every response type in this crate, compare equal field by field. Floats are compared exactly, so
+/// two responses are only equal if OpenWeather reported identical values.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct OWCurrentWeatherResponse {
     pub coord: GeodeticCoords,
 
@@ -61,7 +240,8 @@ pub struct OWCurrentWeatherResponse {
     /// Visibility, meter. The maximum value of the visibility is 10 km
     pub visibility: f32,
 
-    pub wind: OWWind,
+    /// Some stations (certain buoys and ocean stations) omit wind entirely, prefer [`OWCurrentWeatherResponse::wind`].
+    pub wind: Option<OWWind>,
 
     pub clouds: OWClouds,
 
@@ -75,7 +255,7 @@ pub struct OWCurrentWeatherResponse {
     pub sys: OWSys,
 
     /// Shift in seconds from UTC
-    #[serde(deserialize_with = "from_utc_shift")]
+    #[serde(deserialize_with = "from_utc_shift", serialize_with = "to_utc_shift")]
     pub timezone: FixedOffset,
 
     /// City ID
@@ -83,11 +263,15 @@ pub struct OWCurrentWeatherResponse {
 
     /// City name
     pub name: String,
+    /// Units the response was requested in, which OpenWeather doesn't echo back. Set by the clients through
+    /// [`Self::with_units`], and [`WeatherUnits::Standard`] for responses parsed directly.
+    #[serde(skip)]
+    pub units: WeatherUnits,
 }
 
 /// OpenWeather returns the timezone of our query position as a number of seconds shifted from UTC, we want to
 /// deserialize that as a DateTime::FixedOffset for the local time.
-fn from_utc_shift<'de, D>(deserializer: D) -> Result<FixedOffset, D::Error>
+pub(crate) fn from_utc_shift<'de, D>(deserializer: D) -> Result<FixedOffset, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -100,7 +284,44 @@ where
     Ok(fixed_offset)
 }
 
+/// Serializes a timezone back to OpenWeather's shift in seconds from UTC, the inverse of [`from_utc_shift`].
+pub(crate) fn to_utc_shift<S>(offset: &FixedOffset, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_i32(offset.local_minus_utc())
+}
+
 impl OWCurrentWeatherResponse {
+    /// Tags the response, its temperatures, and its wind speeds with the units it was requested in. Parsing can't
+    /// tell which units a response is in, so they're taken to be [`WeatherUnits::Standard`] (OpenWeather's default)
+    /// until this is called, which the clients do for you.
+    pub fn with_units(mut self, units: WeatherUnits) -> Self {
+        self.units = units;
+        self.main.set_units(units);
+        if let Some(wind) = &mut self.wind {
+            wind.set_units(units);
+        }
+        self
+    }
+
+    /// Return the primary weather condition, the first in the response
+    pub fn primary_condition(&self) -> Option<&OWWeather> {
+        self.weather.first()
+    }
+
+    /// Return the most severe weather condition by [`ConditionGroup::severity`], ties go to the earlier condition.
+    pub fn most_severe_condition(&self) -> Option<&OWWeather> {
+        self.weather
+            .iter()
+            .min_by_key(|w| std::cmp::Reverse(w.group().severity()))
+    }
+
+    /// Return the wind conditions, if the reporting station provided them
+    pub fn wind(&self) -> Option<&OWWind> {
+        self.wind.as_ref()
+    }
+
     /// Return the sunrise datetime in the local timezone
     pub fn sunrise_local(&self) -> DateTime<FixedOffset> {
         self.sys.sunrise.with_timezone(&self.timezone)
@@ -110,12 +331,39 @@ impl OWCurrentWeatherResponse {
     pub fn sunset_local(&self) -> DateTime<FixedOffset> {
         self.sys.sunset.with_timezone(&self.timezone)
     }
+
+    /// Whether the observation at `dt` was made between sunrise and sunset
+    pub fn is_daytime(&self) -> bool {
+        let observed = self.dt as i64;
+
+        (self.sys.sunrise.timestamp()..self.sys.sunset.timestamp()).contains(&observed)
+    }
+
+    /// Return the time between sunrise and sunset
+    pub fn day_length(&self) -> Duration {
+        self.sys.sunset - self.sys.sunrise
+    }
+
+    /// Return the time from `now` until sunset, or `None` if the reported sunset has already passed
+    pub fn time_until_sunset(&self, now: DateTime<Utc>) -> Option<Duration> {
+        (self.sys.sunset > now).then(|| self.sys.sunset - now)
+    }
+
+    /// Return the time from `now` until sunrise, or `None` if the reported sunrise has already passed. OpenWeather
+    /// reports the sunrise of the current day, so after it passes the next one isn't known until a later response.
+    pub fn time_until_sunrise(&self, now: DateTime<Utc>) -> Option<Duration> {
+        (self.sys.sunrise > now).then(|| self.sys.sunrise - now)
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct OWWeather {
     /// Weather condition id, more info on condition IDs and icons [here](https://openweathermap.org/weather-conditions).
-    pub id: u32,
+    #[serde(
+        deserialize_with = "from_raw_condition_id",
+        serialize_with = "to_raw_condition_id"
+    )]
+    pub id: WeatherConditionId,
 
     /// Group of weather parameters (Rain, Snow, Clouds etc.)
     pub main: String,
@@ -127,8 +375,369 @@ pub struct OWWeather {
     pub icon: String,
 }
 
+impl OWWeather {
+    /// Returns the broad group this weather condition belongs to
+    pub fn group(&self) -> ConditionGroup {
+        self.id.group()
+    }
+}
+
+/// Broad groups of weather conditions, OpenWeather encodes the group in the hundreds digit of the condition ID.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionGroup {
+    Thunderstorm,
+    Drizzle,
+    Rain,
+    Snow,
+    /// Mist, smoke, haze, dust, fog, sand, ash, squalls, and tornadoes
+    Atmosphere,
+    Clear,
+    Clouds,
+    /// A condition ID outside of the documented ranges
+    Unknown,
+}
+
+impl ConditionGroup {
+    /// Classifies a raw OpenWeather condition ID
+    pub fn from_id(id: u32) -> Self {
+        match id {
+            200..=299 => ConditionGroup::Thunderstorm,
+            300..=399 => ConditionGroup::Drizzle,
+            500..=599 => ConditionGroup::Rain,
+            600..=699 => ConditionGroup::Snow,
+            700..=799 => ConditionGroup::Atmosphere,
+            800 => ConditionGroup::Clear,
+            801..=899 => ConditionGroup::Clouds,
+            _ => ConditionGroup::Unknown,
+        }
+    }
+
+    /// Relative severity of the group, higher is more severe. From most to least severe:
+    /// thunderstorm > snow > rain > drizzle > atmosphere > clouds > clear > unknown.
+    pub fn severity(&self) -> u8 {
+        match self {
+            ConditionGroup::Thunderstorm => 7,
+            ConditionGroup::Snow => 6,
+            ConditionGroup::Rain => 5,
+            ConditionGroup::Drizzle => 4,
+            ConditionGroup::Atmosphere => 3,
+            ConditionGroup::Clouds => 2,
+            ConditionGroup::Clear => 1,
+            ConditionGroup::Unknown => 0,
+        }
+    }
+}
+
+/// Weather conditions OpenWeather reports, more info [here](https://openweathermap.org/weather-conditions). Prefer
+/// matching on these over the `main` and `description` strings, which may be localized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WeatherConditionId {
+    ThunderstormWithLightRain,
+    ThunderstormWithRain,
+    ThunderstormWithHeavyRain,
+    LightThunderstorm,
+    Thunderstorm,
+    HeavyThunderstorm,
+    RaggedThunderstorm,
+    ThunderstormWithLightDrizzle,
+    ThunderstormWithDrizzle,
+    ThunderstormWithHeavyDrizzle,
+    LightDrizzle,
+    Drizzle,
+    HeavyDrizzle,
+    LightDrizzleRain,
+    DrizzleRain,
+    HeavyDrizzleRain,
+    ShowerRainAndDrizzle,
+    HeavyShowerRainAndDrizzle,
+    ShowerDrizzle,
+    LightRain,
+    ModerateRain,
+    HeavyRain,
+    VeryHeavyRain,
+    ExtremeRain,
+    FreezingRain,
+    LightShowerRain,
+    ShowerRain,
+    HeavyShowerRain,
+    RaggedShowerRain,
+    LightSnow,
+    Snow,
+    HeavySnow,
+    Sleet,
+    LightShowerSleet,
+    ShowerSleet,
+    LightRainAndSnow,
+    RainAndSnow,
+    LightShowerSnow,
+    ShowerSnow,
+    HeavyShowerSnow,
+    Mist,
+    Smoke,
+    Haze,
+    SandDustWhirls,
+    Fog,
+    Sand,
+    Dust,
+    VolcanicAsh,
+    Squalls,
+    Tornado,
+    ClearSky,
+    FewClouds,
+    ScatteredClouds,
+    BrokenClouds,
+    OvercastClouds,
+    /// A condition ID outside of those documented
+    Unknown(u32),
+}
+
+impl WeatherConditionId {
+    /// Maps OpenWeather's numeric condition IDs onto a [`WeatherConditionId`]
+    pub fn from_code(code: u32) -> Self {
+        match code {
+            200 => WeatherConditionId::ThunderstormWithLightRain,
+            201 => WeatherConditionId::ThunderstormWithRain,
+            202 => WeatherConditionId::ThunderstormWithHeavyRain,
+            210 => WeatherConditionId::LightThunderstorm,
+            211 => WeatherConditionId::Thunderstorm,
+            212 => WeatherConditionId::HeavyThunderstorm,
+            221 => WeatherConditionId::RaggedThunderstorm,
+            230 => WeatherConditionId::ThunderstormWithLightDrizzle,
+            231 => WeatherConditionId::ThunderstormWithDrizzle,
+            232 => WeatherConditionId::ThunderstormWithHeavyDrizzle,
+            300 => WeatherConditionId::LightDrizzle,
+            301 => WeatherConditionId::Drizzle,
+            302 => WeatherConditionId::HeavyDrizzle,
+            310 => WeatherConditionId::LightDrizzleRain,
+            311 => WeatherConditionId::DrizzleRain,
+            312 => WeatherConditionId::HeavyDrizzleRain,
+            313 => WeatherConditionId::ShowerRainAndDrizzle,
+            314 => WeatherConditionId::HeavyShowerRainAndDrizzle,
+            321 => WeatherConditionId::ShowerDrizzle,
+            500 => WeatherConditionId::LightRain,
+            501 => WeatherConditionId::ModerateRain,
+            502 => WeatherConditionId::HeavyRain,
+            503 => WeatherConditionId::VeryHeavyRain,
+            504 => WeatherConditionId::ExtremeRain,
+            511 => WeatherConditionId::FreezingRain,
+            520 => WeatherConditionId::LightShowerRain,
+            521 => WeatherConditionId::ShowerRain,
+            522 => WeatherConditionId::HeavyShowerRain,
+            531 => WeatherConditionId::RaggedShowerRain,
+            600 => WeatherConditionId::LightSnow,
+            601 => WeatherConditionId::Snow,
+            602 => WeatherConditionId::HeavySnow,
+            611 => WeatherConditionId::Sleet,
+            612 => WeatherConditionId::LightShowerSleet,
+            613 => WeatherConditionId::ShowerSleet,
+            615 => WeatherConditionId::LightRainAndSnow,
+            616 => WeatherConditionId::RainAndSnow,
+            620 => WeatherConditionId::LightShowerSnow,
+            621 => WeatherConditionId::ShowerSnow,
+            622 => WeatherConditionId::HeavyShowerSnow,
+            701 => WeatherConditionId::Mist,
+            711 => WeatherConditionId::Smoke,
+            721 => WeatherConditionId::Haze,
+            731 => WeatherConditionId::SandDustWhirls,
+            741 => WeatherConditionId::Fog,
+            751 => WeatherConditionId::Sand,
+            761 => WeatherConditionId::Dust,
+            762 => WeatherConditionId::VolcanicAsh,
+            771 => WeatherConditionId::Squalls,
+            781 => WeatherConditionId::Tornado,
+            800 => WeatherConditionId::ClearSky,
+            801 => WeatherConditionId::FewClouds,
+            802 => WeatherConditionId::ScatteredClouds,
+            803 => WeatherConditionId::BrokenClouds,
+            804 => WeatherConditionId::OvercastClouds,
+            _ => WeatherConditionId::Unknown(code),
+        }
+    }
+
+    /// OpenWeather's numeric ID for this condition, the inverse of [`WeatherConditionId::from_code`]
+    pub fn code(&self) -> u32 {
+        match self {
+            WeatherConditionId::ThunderstormWithLightRain => 200,
+            WeatherConditionId::ThunderstormWithRain => 201,
+            WeatherConditionId::ThunderstormWithHeavyRain => 202,
+            WeatherConditionId::LightThunderstorm => 210,
+            WeatherConditionId::Thunderstorm => 211,
+            WeatherConditionId::HeavyThunderstorm => 212,
+            WeatherConditionId::RaggedThunderstorm => 221,
+            WeatherConditionId::ThunderstormWithLightDrizzle => 230,
+            WeatherConditionId::ThunderstormWithDrizzle => 231,
+            WeatherConditionId::ThunderstormWithHeavyDrizzle => 232,
+            WeatherConditionId::LightDrizzle => 300,
+            WeatherConditionId::Drizzle => 301,
+            WeatherConditionId::HeavyDrizzle => 302,
+            WeatherConditionId::LightDrizzleRain => 310,
+            WeatherConditionId::DrizzleRain => 311,
+            WeatherConditionId::HeavyDrizzleRain => 312,
+            WeatherConditionId::ShowerRainAndDrizzle => 313,
+            WeatherConditionId::HeavyShowerRainAndDrizzle => 314,
+            WeatherConditionId::ShowerDrizzle => 321,
+            WeatherConditionId::LightRain => 500,
+            WeatherConditionId::ModerateRain => 501,
+            WeatherConditionId::HeavyRain => 502,
+            WeatherConditionId::VeryHeavyRain => 503,
+            WeatherConditionId::ExtremeRain => 504,
+            WeatherConditionId::FreezingRain => 511,
+            WeatherConditionId::LightShowerRain => 520,
+            WeatherConditionId::ShowerRain => 521,
+            WeatherConditionId::HeavyShowerRain => 522,
+            WeatherConditionId::RaggedShowerRain => 531,
+            WeatherConditionId::LightSnow => 600,
+            WeatherConditionId::Snow => 601,
+            WeatherConditionId::HeavySnow => 602,
+            WeatherConditionId::Sleet => 611,
+            WeatherConditionId::LightShowerSleet => 612,
+            WeatherConditionId::ShowerSleet => 613,
+            WeatherConditionId::LightRainAndSnow => 615,
+            WeatherConditionId::RainAndSnow => 616,
+            WeatherConditionId::LightShowerSnow => 620,
+            WeatherConditionId::ShowerSnow => 621,
+            WeatherConditionId::HeavyShowerSnow => 622,
+            WeatherConditionId::Mist => 701,
+            WeatherConditionId::Smoke => 711,
+            WeatherConditionId::Haze => 721,
+            WeatherConditionId::SandDustWhirls => 731,
+            WeatherConditionId::Fog => 741,
+            WeatherConditionId::Sand => 751,
+            WeatherConditionId::Dust => 761,
+            WeatherConditionId::VolcanicAsh => 762,
+            WeatherConditionId::Squalls => 771,
+            WeatherConditionId::Tornado => 781,
+            WeatherConditionId::ClearSky => 800,
+            WeatherConditionId::FewClouds => 801,
+            WeatherConditionId::ScatteredClouds => 802,
+            WeatherConditionId::BrokenClouds => 803,
+            WeatherConditionId::OvercastClouds => 804,
+            WeatherConditionId::Unknown(code) => *code,
+        }
+    }
+
+    /// The broad group this condition belongs to
+    pub fn group(&self) -> ConditionGroup {
+        ConditionGroup::from_id(self.code())
+    }
+
+    /// OpenWeather's English description of this condition, e.g. "broken clouds", or `None` for unknown conditions
+    pub fn description(&self) -> Option<&'static str> {
+        let description = match self {
+            WeatherConditionId::ThunderstormWithLightRain => "thunderstorm with light rain",
+            WeatherConditionId::ThunderstormWithRain => "thunderstorm with rain",
+            WeatherConditionId::ThunderstormWithHeavyRain => "thunderstorm with heavy rain",
+            WeatherConditionId::LightThunderstorm => "light thunderstorm",
+            WeatherConditionId::Thunderstorm => "thunderstorm",
+            WeatherConditionId::HeavyThunderstorm => "heavy thunderstorm",
+            WeatherConditionId::RaggedThunderstorm => "ragged thunderstorm",
+            WeatherConditionId::ThunderstormWithLightDrizzle => "thunderstorm with light drizzle",
+            WeatherConditionId::ThunderstormWithDrizzle => "thunderstorm with drizzle",
+            WeatherConditionId::ThunderstormWithHeavyDrizzle => "thunderstorm with heavy drizzle",
+            WeatherConditionId::LightDrizzle => "light intensity drizzle",
+            WeatherConditionId::Drizzle => "drizzle",
+            WeatherConditionId::HeavyDrizzle => "heavy intensity drizzle",
+            WeatherConditionId::LightDrizzleRain => "light intensity drizzle rain",
+            WeatherConditionId::DrizzleRain => "drizzle rain",
+            WeatherConditionId::HeavyDrizzleRain => "heavy intensity drizzle rain",
+            WeatherConditionId::ShowerRainAndDrizzle => "shower rain and drizzle",
+            WeatherConditionId::HeavyShowerRainAndDrizzle => "heavy shower rain and drizzle",
+            WeatherConditionId::ShowerDrizzle => "shower drizzle",
+            WeatherConditionId::LightRain => "light rain",
+            WeatherConditionId::ModerateRain => "moderate rain",
+            WeatherConditionId::HeavyRain => "heavy intensity rain",
+            WeatherConditionId::VeryHeavyRain => "very heavy rain",
+            WeatherConditionId::ExtremeRain => "extreme rain",
+            WeatherConditionId::FreezingRain => "freezing rain",
+            WeatherConditionId::LightShowerRain => "light intensity shower rain",
+            WeatherConditionId::ShowerRain => "shower rain",
+            WeatherConditionId::HeavyShowerRain => "heavy intensity shower rain",
+            WeatherConditionId::RaggedShowerRain => "ragged shower rain",
+            WeatherConditionId::LightSnow => "light snow",
+            WeatherConditionId::Snow => "snow",
+            WeatherConditionId::HeavySnow => "heavy snow",
+            WeatherConditionId::Sleet => "sleet",
+            WeatherConditionId::LightShowerSleet => "light shower sleet",
+            WeatherConditionId::ShowerSleet => "shower sleet",
+            WeatherConditionId::LightRainAndSnow => "light rain and snow",
+            WeatherConditionId::RainAndSnow => "rain and snow",
+            WeatherConditionId::LightShowerSnow => "light shower snow",
+            WeatherConditionId::ShowerSnow => "shower snow",
+            WeatherConditionId::HeavyShowerSnow => "heavy shower snow",
+            WeatherConditionId::Mist => "mist",
+            WeatherConditionId::Smoke => "smoke",
+            WeatherConditionId::Haze => "haze",
+            WeatherConditionId::SandDustWhirls => "sand/dust whirls",
+            WeatherConditionId::Fog => "fog",
+            WeatherConditionId::Sand => "sand",
+            WeatherConditionId::Dust => "dust",
+            WeatherConditionId::VolcanicAsh => "volcanic ash",
+            WeatherConditionId::Squalls => "squalls",
+            WeatherConditionId::Tornado => "tornado",
+            WeatherConditionId::ClearSky => "clear sky",
+            WeatherConditionId::FewClouds => "few clouds",
+            WeatherConditionId::ScatteredClouds => "scattered clouds",
+            WeatherConditionId::BrokenClouds => "broken clouds",
+            WeatherConditionId::OvercastClouds => "overcast clouds",
+            WeatherConditionId::Unknown(_) => return None,
+        };
+
+        Some(description)
+    }
+
+    /// OpenWeather's `main` for this condition, e.g. "Clouds", or `None` for unknown conditions. This is the name of
+    /// the [`ConditionGroup`], except for atmosphere conditions which each have their own, e.g. "Mist" or "Squall".
+    pub fn main(&self) -> Option<&'static str> {
+        let main = match self {
+            WeatherConditionId::Mist => "Mist",
+            WeatherConditionId::Smoke => "Smoke",
+            WeatherConditionId::Haze => "Haze",
+            WeatherConditionId::SandDustWhirls | WeatherConditionId::Dust => "Dust",
+            WeatherConditionId::Fog => "Fog",
+            WeatherConditionId::Sand => "Sand",
+            WeatherConditionId::VolcanicAsh => "Ash",
+            WeatherConditionId::Squalls => "Squall",
+            WeatherConditionId::Tornado => "Tornado",
+            WeatherConditionId::Unknown(_) => return None,
+            condition => match condition.group() {
+                ConditionGroup::Thunderstorm => "Thunderstorm",
+                ConditionGroup::Drizzle => "Drizzle",
+                ConditionGroup::Rain => "Rain",
+                ConditionGroup::Snow => "Snow",
+                ConditionGroup::Clear => "Clear",
+                ConditionGroup::Clouds => "Clouds",
+                ConditionGroup::Atmosphere | ConditionGroup::Unknown => return None,
+            },
+        };
+
+        Some(main)
+    }
+}
+
+/// OpenWeather returns condition IDs as bare numbers, we map them onto a [`WeatherConditionId`] as part of the
+/// deserialization process.
+pub(crate) fn from_raw_condition_id<'de, D>(deserializer: D) -> Result<WeatherConditionId, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let code = u32::deserialize(deserializer)?;
+
+    Ok(WeatherConditionId::from_code(code))
+}
+
+/// Serializes a [`WeatherConditionId`] back to a bare number, the inverse of [`from_raw_condition_id`].
+pub(crate) fn to_raw_condition_id<S>(
+    id: &WeatherConditionId,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_u32(id.code())
+}
+
 /// Points on a 16-wind compass rose
-#[derive(Debug, Display)]
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq)]
 pub enum CompassPoint {
     North,
     NorthNorthEast,
@@ -148,9 +757,55 @@ pub enum CompassPoint {
     NorthNorthWest,
 }
 
+impl CompassPoint {
+    /// Conventional lowercase name of this compass point, e.g. "south-southwest"
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompassPoint::North => "north",
+            CompassPoint::NorthNorthEast => "north-northeast",
+            CompassPoint::NorthEast => "northeast",
+            CompassPoint::EastNorthEast => "east-northeast",
+            CompassPoint::East => "east",
+            CompassPoint::EastSouthEast => "east-southeast",
+            CompassPoint::SouthEast => "southeast",
+            CompassPoint::SouthSouthEast => "south-southeast",
+            CompassPoint::South => "south",
+            CompassPoint::SouthSouthWest => "south-southwest",
+            CompassPoint::SouthWest => "southwest",
+            CompassPoint::WestSouthWest => "west-southwest",
+            CompassPoint::West => "west",
+            CompassPoint::WestNorthWest => "west-northwest",
+            CompassPoint::NorthWest => "northwest",
+            CompassPoint::NorthNorthWest => "north-northwest",
+        }
+    }
+
+    /// Conventional abbreviation of this compass point, e.g. "SSW"
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            CompassPoint::North => "N",
+            CompassPoint::NorthNorthEast => "NNE",
+            CompassPoint::NorthEast => "NE",
+            CompassPoint::EastNorthEast => "ENE",
+            CompassPoint::East => "E",
+            CompassPoint::EastSouthEast => "ESE",
+            CompassPoint::SouthEast => "SE",
+            CompassPoint::SouthSouthEast => "SSE",
+            CompassPoint::South => "S",
+            CompassPoint::SouthSouthWest => "SSW",
+            CompassPoint::SouthWest => "SW",
+            CompassPoint::WestSouthWest => "WSW",
+            CompassPoint::West => "W",
+            CompassPoint::WestNorthWest => "WNW",
+            CompassPoint::NorthWest => "NW",
+            CompassPoint::NorthNorthWest => "NNW",
+        }
+    }
+}
+
 /// Meteorological convention for wind direction is measured in degrees clockwise from true North, and represents
 /// the direction _from which_ the wind is coming, thats what the OpenWeather API will respond with.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct WindDirection(f32);
 
 #[derive(Error, Debug)]
@@ -162,13 +817,18 @@ pub enum WindDirectionError {
 impl WindDirection {
     /// Validates the wind direction falls inside the range for compass direction in the meteorological convention.
     pub fn new_checked(deg: f32) -> Result<Self, WindDirectionError> {
-        if deg < 0.0 || deg >= 360.0 {
+        if !(0.0..360.0).contains(&deg) {
             return Err(WindDirectionError::InvalidDirection(deg));
         }
 
         Ok(WindDirection(deg))
     }
 
+    /// Returns the direction the wind is blowing from in degrees, in [0, 360)
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+
     /// Returns the compass point from which the wind is blowing
     pub fn compass_point(&self) -> CompassPoint {
         match self.0 {
@@ -200,94 +860,287 @@ impl WindDirection {
     }
 }
 
-#[derive(Deserialize, Debug)]
+/// A percentage, constrained to the range [0, 100]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Percentage(f32);
+
+#[derive(Error, Debug, PartialEq)]
+pub enum PercentageError {
+    #[error("provided percentage of `{0}` is outside the valid range [0, 100]")]
+    OutOfRange(f32),
+}
+
+impl Percentage {
+    /// Validates the percentage falls inside [0, 100].
+    pub fn new_checked(percent: f32) -> Result<Self, PercentageError> {
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(PercentageError::OutOfRange(percent));
+        }
+
+        Ok(Percentage(percent))
+    }
+
+    /// Returns the percentage as a number in [0, 100]
+    pub fn value(&self) -> f32 {
+        self.0
+    }
+}
+
+/// OpenWeather returns percentages as bare numbers, we validate them and wrap them in a [`Percentage`] as part of
+/// the deserialization process.
+pub(crate) fn from_raw_percentage<'de, D>(deserializer: D) -> Result<Percentage, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = f32::deserialize(deserializer)?;
+    let percentage = Percentage::new_checked(raw).map_err(serde::de::Error::custom)?;
+
+    Ok(percentage)
+}
+
+/// Serializes a [`Percentage`] back to a bare number, the inverse of [`from_raw_percentage`].
+pub(crate) fn to_raw_percentage<S>(
+    percentage: &Percentage,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(percentage.0)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct OWMain {
     /// Temperature. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
-    pub temp: f32,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub temp: Temperature,
 
     /// Temperature. This temperature parameter accounts for the human perception of weather. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
-    pub feels_like: f32,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub feels_like: Temperature,
 
     /// Atmospheric pressure on the sea level, hPa
-    pub pressure: f32,
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub pressure: Pressure,
 
     /// Humidity, %
-    pub humidity: f32,
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub humidity: Percentage,
 
     /// Minimum temperature at the moment. This is minimal currently observed temperature (within large megalopolises and urban areas). Please find more info here. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
-    pub temp_min: f32,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub temp_min: Temperature,
 
     /// Maximum temperature at the moment. This is maximal currently observed temperature (within large megalopolises and urban areas). Please find more info here. Unit Default: Kelvin, Metric: Celsius, Imperial: Fahrenheit
-    pub temp_max: f32,
+    #[serde(
+        deserialize_with = "from_raw_temperature",
+        serialize_with = "to_raw_temperature"
+    )]
+    pub temp_max: Temperature,
 
     /// Atmospheric pressure on the sea level, hPa
-    pub sea_level: f32,
+    #[serde(
+        deserialize_with = "from_raw_pressure",
+        serialize_with = "to_raw_pressure"
+    )]
+    pub sea_level: Pressure,
+
+    /// Atmospheric pressure on the ground level, hPa. Not reported in XML responses.
+    #[serde(
+        default,
+        deserialize_with = "from_optional_raw_pressure",
+        serialize_with = "to_optional_raw_pressure"
+    )]
+    pub grnd_level: Option<Pressure>,
+}
 
-    /// Atmospheric pressure on the ground level, hPa
-    pub grnd_level: f32,
+impl OWMain {
+    /// Labels the temperatures with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        for temp in [
+            &mut self.temp,
+            &mut self.feels_like,
+            &mut self.temp_min,
+            &mut self.temp_max,
+        ] {
+            *temp = Temperature::new(temp.value(), units);
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct OWWind {
     /// Wind speed. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
-    pub speed: f32,
+    #[serde(
+        deserialize_with = "from_raw_wind_speed",
+        serialize_with = "to_raw_wind_speed"
+    )]
+    pub speed: WindSpeed,
 
     /// Wind direction, degrees (meteorological)
-    #[serde(deserialize_with = "from_raw_wind_direction")]
+    #[serde(
+        deserialize_with = "from_raw_wind_direction",
+        serialize_with = "to_raw_wind_direction"
+    )]
     pub deg: WindDirection,
 
     /// Wind gust. Unit Default: meter/sec, Metric: meter/sec, Imperial: miles/hour
     /// The docs are not specific about this being optional but I've seen responses without it.
-    pub gust: Option<f32>,
+    #[serde(
+        default,
+        deserialize_with = "from_optional_raw_wind_speed",
+        serialize_with = "to_optional_raw_wind_speed"
+    )]
+    pub gust: Option<WindSpeed>,
 }
 
-/// OpenWeather returns sunrise and sunset times as seconds since UNIX epoch expressed in UTC, we convert
-/// them to timezone-aware [`chrono::DateTime`]s as part of the deserialization process.
-fn from_raw_wind_direction<'de, D>(deserializer: D) -> Result<WindDirection, D::Error>
+impl OWWind {
+    /// Returns the sustained wind speed on the Beaufort scale, 0 (calm) through 12 (hurricane force).
+    pub fn beaufort(&self) -> u8 {
+        self.speed.beaufort()
+    }
+
+    /// Returns the sustained wind speed in knots.
+    pub fn speed_knots(&self) -> f32 {
+        self.speed.to_knots()
+    }
+
+    /// Labels the speeds with the units they were requested in, without converting them
+    pub(crate) fn set_units(&mut self, units: WeatherUnits) {
+        self.speed = WindSpeed::new(self.speed.value(), units);
+        self.gust = self.gust.map(|gust| WindSpeed::new(gust.value(), units));
+    }
+}
+
+/// OpenWeather returns wind direction as a bare number of degrees, we validate it and wrap it in a
+/// [`WindDirection`] as part of the deserialization process.
+pub(crate) fn from_raw_wind_direction<'de, D>(deserializer: D) -> Result<WindDirection, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let raw = f32::deserialize(deserializer)?;
-    let wind_dir = WindDirection::new_checked(raw).map_err(|e| serde::de::Error::custom(e))?;
+    let wind_dir = WindDirection::new_checked(raw).map_err(serde::de::Error::custom)?;
 
     Ok(wind_dir)
 }
 
-#[derive(Deserialize, Debug)]
+/// Serializes a [`WindDirection`] back to a bare number of degrees, the inverse of [`from_raw_wind_direction`].
+pub(crate) fn to_raw_wind_direction<S>(
+    direction: &WindDirection,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_f32(direction.0)
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct OWClouds {
     /// Cloudiness %
-    pub all: f32,
+    #[serde(
+        deserialize_with = "from_raw_percentage",
+        serialize_with = "to_raw_percentage"
+    )]
+    pub all: Percentage,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
 pub struct OWRain {
     /// Precipitation, mm/h. Please note that only mm/h as units of measurement are available for this parameter
-    pub r#_1h: f32,
+    /// OpenWeather may omit the 1h total (reporting only a 3h total) so this is optional.
+    #[serde(
+        rename = "1h",
+        default,
+        deserialize_with = "from_optional_raw_precip_rate",
+        serialize_with = "to_optional_raw_precip_rate"
+    )]
+    pub one_hour: Option<PrecipRate>,
+
+    /// Precipitation over the last (or for forecasts, the next) 3 hours, mm
+    #[serde(rename = "3h")]
+    pub three_hours: Option<f32>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
 pub struct OWSnow {
     /// Precipitation, mm/h. Please note that only mm/h as units of measurement are available for this parameter
-    pub r#_1h: f32,
+    /// OpenWeather may omit the 1h total (reporting only a 3h total) so this is optional.
+    #[serde(
+        rename = "1h",
+        default,
+        deserialize_with = "from_optional_raw_precip_rate",
+        serialize_with = "to_optional_raw_precip_rate"
+    )]
+    pub one_hour: Option<PrecipRate>,
+
+    /// Precipitation over the last (or for forecasts, the next) 3 hours, mm
+    #[serde(rename = "3h")]
+    pub three_hours: Option<f32>,
+}
+
+/// Snow-to-liquid ratio used when nothing better is known, 10mm of snow for every 1mm of liquid water.
+pub const DEFAULT_SNOW_TO_LIQUID_RATIO: f32 = 10.0;
+
+impl OWSnow {
+    /// Estimated snow depth accumulated over the last hour in mm, given a snow-to-liquid `ratio`. OpenWeather reports
+    /// snow as liquid water equivalent so this is only an estimate, real ratios vary a lot with crystal type and wind.
+    /// Returns `None` if the response didn't include a 1h precipitation total.
+    pub fn estimated_snow_depth_mm(&self, ratio: f32) -> Option<f32> {
+        self.one_hour.map(|rate| rate.mm_per_hour() * ratio)
+    }
+
+    /// A rough temperature-adjusted snow-to-liquid ratio, wetter and denser snow near freezing and fluffier snow as it
+    /// gets colder. This is a rule of thumb for estimates, not a substitute for a real snowfall forecast.
+    pub fn typical_ratio_for_temp(temp_c: f32) -> f32 {
+        match temp_c {
+            t if t > 0.0 => 8.0,
+            t if t > -2.0 => DEFAULT_SNOW_TO_LIQUID_RATIO,
+            t if t > -7.0 => 15.0,
+            t if t > -12.0 => 20.0,
+            _ => 25.0,
+        }
+    }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
 pub struct OWSys {
     /// Country code (GB, JP etc.)
     pub country: String,
 
     /// Sunrise time, seconds since UNIX epoch, UTC
-    #[serde(deserialize_with = "from_unix_offset")]
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
     pub sunrise: DateTime<Utc>,
 
     /// Sunset time, seconds since UNIX epoch, UTC
-    #[serde(deserialize_with = "from_unix_offset")]
+    #[serde(
+        deserialize_with = "from_unix_offset",
+        serialize_with = "to_unix_offset"
+    )]
     pub sunset: DateTime<Utc>,
 }
 
 /// OpenWeather returns sunrise and sunset times as seconds since UNIX epoch expressed in UTC, we convert
 /// them to timezone-aware [`chrono::DateTime`]s as part of the deserialization process.
-fn from_unix_offset<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+pub(crate) fn from_unix_offset<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
@@ -299,31 +1152,50 @@ where
     Ok(date_time)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Serializes a time back to seconds since UNIX epoch, the inverse of [`from_unix_offset`].
+pub(crate) fn to_unix_offset<S>(date_time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_i64(date_time.timestamp())
+}
 
-    #[test]
-    fn geodetic_coords_checked() {
-        let bad_lat = GeodeticCoords::new_checked(180.0, 0.0);
-        assert_eq!(
-            bad_lat.unwrap_err(),
-            GeodeticCoordsError::LatitudeOutOfRange(180.0)
-        );
+/// Same as [`from_unix_offset`] for timestamps which OpenWeather sometimes omits or reports as zero (e.g. sunrise
+/// during polar night, or a day without a moonrise), use with `#[serde(default)]`.
+pub(crate) fn from_optional_unix_offset<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let secs_since_unix = u64::deserialize(deserializer)?;
+    if secs_since_unix == 0 {
+        return Ok(None);
+    }
 
-        let bad_lon = GeodeticCoords::new_checked(33.0, 190.0);
-        assert_eq!(
-            bad_lon.unwrap_err(),
-            GeodeticCoordsError::LongitudeOutOfRange(190.0)
-        );
+    let date_time = DateTime::from_timestamp(secs_since_unix as i64, 0)
+        .ok_or_else(|| serde::de::Error::custom("invalid timestamp"))?;
 
-        let valid_geo = GeodeticCoords::new_checked(33.0, -117.0);
-        assert!(valid_geo.is_ok())
-    }
+    Ok(Some(date_time))
+}
 
-    #[test]
-    fn parse_open_weather_response() {
-        let stringly = r#"
+/// Serializes an optional time back to seconds since UNIX epoch, writing `None` as zero the way OpenWeather does.
+pub(crate) fn to_optional_unix_offset<S>(
+    date_time: &Option<DateTime<Utc>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_i64(date_time.map_or(0, |date_time| date_time.timestamp()))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A real response from the current weather API, used as a fixture throughout the test suite
+    pub(crate) const ZOCCA_RESPONSE: &str = r#"
         {
         "coord": {
             "lon": 10.99,
@@ -372,7 +1244,293 @@ mod tests {
         }
         "#;
 
+    /// The parsed [`ZOCCA_RESPONSE`], for tests that tweak individual fields
+    pub(crate) fn zocca() -> OWCurrentWeatherResponse {
+        serde_json::from_str(ZOCCA_RESPONSE).unwrap()
+    }
+
+    #[test]
+    fn serialize_to_wire_format() {
+        let json = serde_json::to_value(zocca()).unwrap();
+
+        assert_eq!(json["timezone"], 3600);
+        assert_eq!(json["sys"]["sunrise"], 1763100641);
+        assert_eq!(json["wind"]["deg"], 202.0);
+        assert_eq!(json["main"]["humidity"], 95.0);
+        assert_eq!(json["clouds"]["all"], 78.0);
+
+        // And parses straight back into the same response
+        let reparsed: OWCurrentWeatherResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(reparsed, zocca());
+    }
+
+    #[test]
+    fn daytime() {
+        let mut response = zocca();
+        assert!(!response.is_daytime());
+
+        response.dt = response.sys.sunrise.timestamp() as u64;
+        assert!(response.is_daytime());
+
+        response.dt = response.sys.sunset.timestamp() as u64;
+        assert!(!response.is_daytime());
+    }
+
+    #[test]
+    fn sun_durations() {
+        let response = zocca();
+        assert_eq!(response.day_length(), Duration::seconds(34788));
+
+        let before_dawn = response.sys.sunrise - Duration::hours(1);
+        assert_eq!(
+            response.time_until_sunrise(before_dawn),
+            Some(Duration::hours(1))
+        );
+        assert_eq!(
+            response.time_until_sunset(before_dawn),
+            Some(Duration::seconds(34788 + 3600))
+        );
+
+        let evening = response.sys.sunset + Duration::minutes(5);
+        assert_eq!(response.time_until_sunrise(evening), None);
+        assert_eq!(response.time_until_sunset(evening), None);
+    }
+
+    #[test]
+    fn response_equality() {
+        let response = zocca();
+        assert_eq!(response.clone(), response);
+
+        let mut warmer = response.clone();
+        warmer.main.temp =
+            Temperature::new(warmer.main.temp.value() + 0.01, WeatherUnits::Standard);
+        assert_ne!(warmer, response);
+
+        assert_eq!(OWRain::default().one_hour, None);
+    }
+
+    #[test]
+    fn language_codes() {
+        assert_eq!(Language::Czech.to_string(), "cz");
+        assert_eq!(Language::BrazilianPortuguese.to_string(), "pt_br");
+        assert_eq!(
+            "ZH_TW".parse::<Language>().unwrap(),
+            Language::TraditionalChinese
+        );
+        assert!("klingon".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn most_severe_condition() {
+        let condition = |id, main: &str| OWWeather {
+            id: WeatherConditionId::from_code(id),
+            main: main.into(),
+            description: String::new(),
+            icon: String::new(),
+        };
+
+        let mut response = zocca();
+        response.weather = vec![
+            condition(803, "Clouds"),
+            condition(211, "Thunderstorm"),
+            condition(201, "Thunderstorm"),
+        ];
+        assert_eq!(
+            response.primary_condition().unwrap().id,
+            WeatherConditionId::BrokenClouds
+        );
+        assert_eq!(
+            response.most_severe_condition().unwrap().id,
+            WeatherConditionId::Thunderstorm
+        );
+
+        response.weather.clear();
+        assert!(response.most_severe_condition().is_none());
+    }
+
+    #[test]
+    fn condition_ids() {
+        let broken = WeatherConditionId::from_code(803);
+        assert_eq!(broken, WeatherConditionId::BrokenClouds);
+        assert_eq!(broken.code(), 803);
+        assert_eq!(broken.group(), ConditionGroup::Clouds);
+        assert_eq!(broken.description(), Some("broken clouds"));
+        assert_eq!(broken.main(), Some("Clouds"));
+        assert_eq!(WeatherConditionId::Mist.main(), Some("Mist"));
+        assert_eq!(WeatherConditionId::VolcanicAsh.main(), Some("Ash"));
+        assert_eq!(WeatherConditionId::SandDustWhirls.main(), Some("Dust"));
+        assert_eq!(WeatherConditionId::FreezingRain.main(), Some("Rain"));
+
+        let unknown = WeatherConditionId::from_code(999);
+        assert_eq!(unknown, WeatherConditionId::Unknown(999));
+        assert_eq!(unknown.code(), 999);
+        assert_eq!(unknown.group(), ConditionGroup::Unknown);
+        assert_eq!(unknown.description(), None);
+        assert_eq!(unknown.main(), None);
+
+        for code in 200..900 {
+            let condition = WeatherConditionId::from_code(code);
+            assert_eq!(condition.code(), code);
+            assert_eq!(
+                condition.main().is_some(),
+                condition.description().is_some()
+            );
+        }
+    }
+
+    #[test]
+    fn unit_symbols() {
+        assert_eq!(WeatherUnits::Standard.temperature_symbol(), "K");
+        assert_eq!(WeatherUnits::Metric.temperature_symbol(), "°C");
+        assert_eq!(WeatherUnits::Imperial.temperature_symbol(), "°F");
+
+        assert_eq!(WeatherUnits::Standard.wind_speed_symbol(), "m/s");
+        assert_eq!(WeatherUnits::Metric.wind_speed_symbol(), "m/s");
+        assert_eq!(WeatherUnits::Imperial.wind_speed_symbol(), "mph");
+    }
+
+    #[test]
+    fn geodetic_coords_checked() {
+        let bad_lat = GeodeticCoords::new_checked(180.0, 0.0);
+        assert_eq!(
+            bad_lat.unwrap_err(),
+            GeodeticCoordsError::LatitudeOutOfRange(180.0)
+        );
+
+        let bad_lon = GeodeticCoords::new_checked(33.0, 190.0);
+        assert_eq!(
+            bad_lon.unwrap_err(),
+            GeodeticCoordsError::LongitudeOutOfRange(190.0)
+        );
+
+        let valid_geo = GeodeticCoords::new_checked(33.0, -117.0);
+        assert!(valid_geo.is_ok())
+    }
+
+    #[test]
+    fn haversine_distance() {
+        let laguna = GeodeticCoords::new_checked(33.545, -117.771).unwrap();
+        let san_diego = GeodeticCoords::new_checked(32.716, -117.161).unwrap();
+
+        // Roughly 108km as the crow flies
+        let dist = laguna.distance_to(&san_diego);
+        assert!((dist - 108_300.0).abs() < 1_000.0, "{dist}");
+        assert_eq!(laguna.distance_to(&laguna), 0.0);
+    }
+
+    #[test]
+    fn parse_open_weather_response() {
+        let stringly = ZOCCA_RESPONSE;
+
         let res: Result<OWCurrentWeatherResponse, _> = serde_json::from_str(stringly);
         assert!(res.is_ok())
     }
+
+    #[test]
+    fn snow_depth_default_ratio() {
+        let snow: OWSnow = serde_json::from_str(r#"{"1h": 2.0}"#).unwrap();
+        let depth = snow.estimated_snow_depth_mm(DEFAULT_SNOW_TO_LIQUID_RATIO);
+        assert_eq!(depth, Some(20.0));
+
+        let no_hourly: OWSnow = serde_json::from_str(r#"{"3h": 4.0}"#).unwrap();
+        assert_eq!(
+            no_hourly.estimated_snow_depth_mm(DEFAULT_SNOW_TO_LIQUID_RATIO),
+            None
+        );
+    }
+
+    #[test]
+    fn snow_depth_cold_ratio() {
+        let ratio = OWSnow::typical_ratio_for_temp(-15.0);
+        assert!(ratio > DEFAULT_SNOW_TO_LIQUID_RATIO);
+        assert!(OWSnow::typical_ratio_for_temp(0.5) < DEFAULT_SNOW_TO_LIQUID_RATIO);
+
+        let snow: OWSnow = serde_json::from_str(r#"{"1h": 2.0}"#).unwrap();
+        assert_eq!(snow.estimated_snow_depth_mm(ratio), Some(50.0));
+    }
+
+    #[test]
+    fn wind_speed_knots() {
+        let mut wind = zocca().wind.unwrap();
+        wind.speed = WindSpeed::new(10.0, WeatherUnits::Metric);
+        assert!((wind.speed_knots() - 19.44).abs() < 0.01);
+
+        wind.speed = WindSpeed::new(10.0, WeatherUnits::Imperial);
+        assert!((wind.speed_knots() - 8.69).abs() < 0.01);
+    }
+
+    #[test]
+    fn parse_integer_numerics() {
+        /// Rewrites every number in the JSON as an integer
+        fn truncate_numbers(value: &mut serde_json::Value) {
+            match value {
+                serde_json::Value::Number(n) => *value = (n.as_f64().unwrap() as i64).into(),
+                serde_json::Value::Array(items) => items.iter_mut().for_each(truncate_numbers),
+                serde_json::Value::Object(map) => map.values_mut().for_each(truncate_numbers),
+                _ => {}
+            }
+        }
+
+        let mut json: serde_json::Value = serde_json::from_str(ZOCCA_RESPONSE).unwrap();
+        truncate_numbers(&mut json);
+        assert_eq!(json["wind"]["speed"], 2);
+
+        let res: OWCurrentWeatherResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(res.main.temp.value(), 281.0);
+        assert_eq!(res.wind().unwrap().speed.value(), 2.0);
+        assert_eq!(res.coord.lat, 44.0);
+    }
+
+    #[test]
+    fn parse_response_without_wind() {
+        let mut json: serde_json::Value = serde_json::from_str(ZOCCA_RESPONSE).unwrap();
+        json.as_object_mut().unwrap().remove("wind");
+
+        let res: OWCurrentWeatherResponse = serde_json::from_value(json).unwrap();
+        assert!(res.wind().is_none());
+    }
+
+    #[test]
+    fn percentage_deserialization() {
+        let clouds: OWClouds = serde_json::from_str(r#"{"all": 78}"#).unwrap();
+        assert_eq!(clouds.all.value(), 78.0);
+
+        let clear: OWClouds = serde_json::from_str(r#"{"all": 0}"#).unwrap();
+        assert_eq!(clear.all.value(), 0.0);
+
+        let overcast: OWClouds = serde_json::from_str(r#"{"all": 100.0}"#).unwrap();
+        assert_eq!(overcast.all.value(), 100.0);
+
+        let too_high: Result<OWClouds, _> = serde_json::from_str(r#"{"all": 100.5}"#);
+        assert!(too_high.is_err());
+
+        let negative: Result<OWClouds, _> = serde_json::from_str(r#"{"all": -1}"#);
+        assert!(negative.is_err());
+
+        assert_eq!(
+            Percentage::new_checked(101.0).unwrap_err(),
+            PercentageError::OutOfRange(101.0)
+        );
+    }
+
+    #[test]
+    fn parse_rain() {
+        let rain: OWRain = serde_json::from_str(r#"{"1h": 2.73}"#).unwrap();
+        assert_eq!(rain.one_hour, Some(PrecipRate::from_mm_per_hour(2.73)));
+    }
+
+    #[test]
+    fn beaufort_scale() {
+        let mut wind = zocca().wind.unwrap();
+        assert_eq!(wind.beaufort(), 2);
+
+        wind.speed = WindSpeed::new(0.2, WeatherUnits::Metric);
+        assert_eq!(wind.beaufort(), 0);
+
+        wind.speed = WindSpeed::new(40.0, WeatherUnits::Metric);
+        assert_eq!(wind.beaufort(), 12);
+
+        wind.speed = WindSpeed::new(40.0, WeatherUnits::Imperial);
+        assert_eq!(wind.beaufort(), 8);
+    }
 }
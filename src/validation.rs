@@ -0,0 +1,167 @@
+//! Consistency checks on parsed responses that serde can't express
+
+use crate::OWCurrentWeatherResponse;
+use chrono::{DateTime, Utc};
+use thiserror::Error;
+
+/// Lowest and highest sea level pressures ever recorded are roughly 870 and 1084 hPa, anything well outside is junk.
+const PLAUSIBLE_PRESSURE_HPA: std::ops::RangeInclusive<f32> = 850.0..=1100.0;
+
+/// The documented maximum visibility is 10km
+const MAX_VISIBILITY_M: f32 = 10_000.0;
+
+/// Signs that a parsed response is internally inconsistent. These are soft warnings, the response may still be
+/// usable, but they're worth logging when monitoring data quality.
+#[derive(Error, Debug, PartialEq)]
+pub enum ValidationWarning {
+    #[error("response contains no weather conditions")]
+    NoWeatherConditions,
+
+    #[error("minimum temperature `{temp_min}` is above the maximum temperature `{temp_max}`")]
+    TempMinAboveMax { temp_min: f32, temp_max: f32 },
+
+    #[error("temperature `{temp}` is outside the reported range [{temp_min}, {temp_max}]")]
+    TempOutsideMinMax {
+        temp: f32,
+        temp_min: f32,
+        temp_max: f32,
+    },
+
+    #[error("sea level pressure of `{0}` hPa is implausible")]
+    ImplausiblePressure(f32),
+
+    #[error("visibility of `{0}` m is outside the documented range [0, 10000]")]
+    VisibilityOutOfRange(f32),
+
+    #[error("wind speed of `{0}` is negative")]
+    NegativeWindSpeed(f32),
+
+    #[error("icon `{icon}` doesn't match the time of day, observed during daytime: {daytime}")]
+    IconDaytimeMismatch { icon: String, daytime: bool },
+
+    #[error("sunrise at `{sunrise}` is not before sunset at `{sunset}`")]
+    SunriseAfterSunset {
+        sunrise: DateTime<Utc>,
+        sunset: DateTime<Utc>,
+    },
+}
+
+impl OWCurrentWeatherResponse {
+    /// Checks the response for internal inconsistencies, returning every problem found rather than stopping at the
+    /// first. Callers are free to log or ignore the warnings.
+    pub fn validate(&self) -> Result<(), Vec<ValidationWarning>> {
+        let mut warnings = Vec::new();
+
+        if self.weather.is_empty() {
+            warnings.push(ValidationWarning::NoWeatherConditions);
+        }
+
+        let main = &self.main;
+        let (temp, temp_min, temp_max) = (
+            main.temp.value(),
+            main.temp_min.value(),
+            main.temp_max.value(),
+        );
+        if temp_min > temp_max {
+            warnings.push(ValidationWarning::TempMinAboveMax { temp_min, temp_max });
+        } else if !(temp_min..=temp_max).contains(&temp) {
+            warnings.push(ValidationWarning::TempOutsideMinMax {
+                temp,
+                temp_min,
+                temp_max,
+            });
+        }
+
+        if !PLAUSIBLE_PRESSURE_HPA.contains(&main.sea_level.hpa()) {
+            warnings.push(ValidationWarning::ImplausiblePressure(main.sea_level.hpa()));
+        }
+
+        if !(0.0..=MAX_VISIBILITY_M).contains(&self.visibility) {
+            warnings.push(ValidationWarning::VisibilityOutOfRange(self.visibility));
+        }
+
+        if let Some(wind) = self.wind()
+            && wind.speed.value() < 0.0
+        {
+            warnings.push(ValidationWarning::NegativeWindSpeed(wind.speed.value()));
+        }
+
+        if self.sys.sunrise >= self.sys.sunset {
+            warnings.push(ValidationWarning::SunriseAfterSunset {
+                sunrise: self.sys.sunrise,
+                sunset: self.sys.sunset,
+            });
+        }
+
+        let daytime = self.is_daytime();
+        for weather in &self.weather {
+            if weather
+                .is_day_icon()
+                .is_some_and(|day_icon| day_icon != daytime)
+            {
+                warnings.push(ValidationWarning::IconDaytimeMismatch {
+                    icon: weather.icon.clone(),
+                    daytime,
+                });
+            }
+        }
+
+        if warnings.is_empty() {
+            Ok(())
+        } else {
+            Err(warnings)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+    use crate::{Temperature, WeatherUnits};
+
+    #[test]
+    fn consistent_response() {
+        assert_eq!(zocca().validate(), Ok(()));
+    }
+
+    #[test]
+    fn temp_min_above_max() {
+        let mut response = zocca();
+        response.main.temp_min = Temperature::new(290.0, WeatherUnits::Standard);
+
+        assert_eq!(
+            response.validate(),
+            Err(vec![ValidationWarning::TempMinAboveMax {
+                temp_min: 290.0,
+                temp_max: 281.29
+            }])
+        );
+    }
+
+    #[test]
+    fn icon_daytime_mismatch() {
+        let mut response = zocca();
+        response.weather[0].icon = "04d".into();
+
+        assert_eq!(
+            response.validate(),
+            Err(vec![ValidationWarning::IconDaytimeMismatch {
+                icon: "04d".into(),
+                daytime: false
+            }])
+        );
+    }
+
+    #[test]
+    fn multiple_warnings() {
+        let mut response = zocca();
+        response.weather.clear();
+        response.visibility = 20_000.0;
+
+        let warnings = response.validate().unwrap_err();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.contains(&ValidationWarning::NoWeatherConditions));
+        assert!(warnings.contains(&ValidationWarning::VisibilityOutOfRange(20_000.0)));
+    }
+}
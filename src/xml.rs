@@ -0,0 +1,375 @@
+//! Parsing current weather responses requested with `mode=xml`, enabled with the `xml` feature
+
+use crate::{
+    GeodeticCoords, OWClouds, OWCurrentWeatherResponse, OWMain, OWRain, OWSnow, OWSys, OWWeather,
+    OWWind, OpenWeatherClient, OpenWxError, Percentage, PrecipRate, Pressure, ResponseMode,
+    Temperature, WeatherConditionId, WeatherUnits, WindDirection, WindSpeed,
+};
+use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use quick_xml::DeError;
+use serde::Deserialize;
+
+/// Root `<current>` element of an XML current weather response
+#[derive(Deserialize, Debug)]
+struct XmlCurrent {
+    city: XmlCity,
+    temperature: XmlTemperature,
+    feels_like: XmlTemperature,
+    humidity: XmlValue<f32>,
+    pressure: XmlValue<f32>,
+    wind: Option<XmlWind>,
+    clouds: XmlValue<f32>,
+    visibility: XmlValue<f32>,
+    precipitation: Option<XmlPrecipitation>,
+    #[serde(default)]
+    weather: Vec<XmlWeather>,
+    lastupdate: XmlValue<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlCity {
+    #[serde(rename = "@id")]
+    id: u32,
+    #[serde(rename = "@name")]
+    name: String,
+    coord: XmlCoord,
+    country: String,
+    timezone: i32,
+    sun: XmlSun,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlCoord {
+    #[serde(rename = "@lat")]
+    lat: f32,
+    #[serde(rename = "@lon")]
+    lon: f32,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlSun {
+    #[serde(rename = "@rise")]
+    rise: String,
+    #[serde(rename = "@set")]
+    set: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlTemperature {
+    #[serde(rename = "@value")]
+    value: f32,
+    #[serde(rename = "@min")]
+    min: Option<f32>,
+    #[serde(rename = "@max")]
+    max: Option<f32>,
+    /// `kelvin`, `celsius`, or `fahrenheit`, unlike JSON the XML says which units temperatures are in
+    #[serde(rename = "@unit")]
+    unit: String,
+}
+
+impl XmlTemperature {
+    fn units(&self) -> Result<WeatherUnits, DeError> {
+        match self.unit.as_str() {
+            "kelvin" => Ok(WeatherUnits::Standard),
+            "celsius" | "metric" => Ok(WeatherUnits::Metric),
+            "fahrenheit" | "imperial" => Ok(WeatherUnits::Imperial),
+            unit => Err(DeError::Custom(format!(
+                "unknown temperature unit `{unit}`"
+            ))),
+        }
+    }
+
+    fn temperature(&self, value: Option<f32>) -> Result<Temperature, DeError> {
+        let value = value.ok_or_else(|| DeError::Custom("missing temperature".into()))?;
+
+        Ok(Temperature::new(value, self.units()?))
+    }
+}
+
+/// Most elements carry their measurement in a `value` attribute alongside a unit or name we don't need
+#[derive(Deserialize, Debug)]
+struct XmlValue<T> {
+    #[serde(rename = "@value")]
+    value: T,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlWind {
+    speed: Option<XmlValue<f32>>,
+    gusts: Option<XmlOptionalValue>,
+    direction: Option<XmlOptionalValue>,
+}
+
+/// Elements like `<gusts/>` which are present but empty when there's nothing to report
+#[derive(Deserialize, Debug)]
+struct XmlOptionalValue {
+    #[serde(rename = "@value")]
+    value: Option<f32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlPrecipitation {
+    #[serde(rename = "@value")]
+    value: Option<f32>,
+    /// `rain`, `snow`, or `no`
+    #[serde(rename = "@mode")]
+    mode: String,
+    /// Period the value covers, `1h` or `3h`
+    #[serde(rename = "@unit")]
+    unit: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct XmlWeather {
+    #[serde(rename = "@number")]
+    number: u32,
+    #[serde(rename = "@value")]
+    value: String,
+    #[serde(rename = "@icon")]
+    icon: String,
+}
+
+/// Parse the body of a current weather response requested in [`ResponseMode::Xml`] into the same strongly-typed
+/// data as a JSON response.
+///
+/// The XML format carries a little less than JSON, so a few fields differ from what the same request in JSON would
+/// return:
+/// - `main.grnd_level` is `None`, ground level pressure isn't reported.
+/// - `main.sea_level` is the same as `main.pressure`, which is the sea level pressure.
+/// - `weather[].main` is empty for condition IDs outside of those documented, the XML doesn't report it.
+/// - The response, its temperatures, and its wind speeds are tagged with the units the XML reports, so there's no
+///   need for `with_units`.
+/// - `wind` is `None` if no direction was reported, as is the case in calm conditions.
+pub fn parse_current_weather_xml(
+    response_text: &str,
+) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+    let current: XmlCurrent = quick_xml::de::from_str(response_text)?;
+
+    Ok(current.try_into()?)
+}
+
+impl TryFrom<XmlCurrent> for OWCurrentWeatherResponse {
+    type Error = DeError;
+
+    fn try_from(current: XmlCurrent) -> Result<Self, Self::Error> {
+        let XmlCurrent {
+            city, temperature, ..
+        } = current;
+
+        let wind = current.wind.and_then(|wind| {
+            let speed = wind.speed?.value;
+            let deg = wind.direction?.value?;
+
+            Some((speed, deg, wind.gusts.and_then(|gusts| gusts.value)))
+        });
+        let wind = match wind {
+            Some((speed, deg, gust)) => {
+                // The XML labels wind speeds too, but only ever in the same system as the temperatures
+                let units = temperature.units()?;
+
+                Some(OWWind {
+                    speed: WindSpeed::new(speed, units),
+                    deg: WindDirection::new_checked(deg).map_err(custom)?,
+                    gust: gust.map(|gust| WindSpeed::new(gust, units)),
+                })
+            }
+            None => None,
+        };
+
+        let (mut rain, mut snow) = (None, None);
+        if let Some(XmlPrecipitation {
+            value: Some(value),
+            mode,
+            unit,
+        }) = current.precipitation
+        {
+            let (one_hour, three_hours) = match unit.as_deref() {
+                Some("3h") => (None, Some(value)),
+                _ => (Some(PrecipRate::from_mm_per_hour(value)), None),
+            };
+            match mode.as_str() {
+                "rain" => {
+                    rain = Some(OWRain {
+                        one_hour,
+                        three_hours,
+                    })
+                }
+                "snow" => {
+                    snow = Some(OWSnow {
+                        one_hour,
+                        three_hours,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        Ok(OWCurrentWeatherResponse {
+            coord: GeodeticCoords::new_checked(city.coord.lat, city.coord.lon).map_err(custom)?,
+            weather: current
+                .weather
+                .into_iter()
+                .map(|weather| {
+                    let id = WeatherConditionId::from_code(weather.number);
+
+                    OWWeather {
+                        id,
+                        main: id.main().unwrap_or_default().to_string(),
+                        description: weather.value,
+                        icon: weather.icon,
+                    }
+                })
+                .collect(),
+            main: OWMain {
+                temp: temperature.temperature(Some(temperature.value))?,
+                feels_like: current
+                    .feels_like
+                    .temperature(Some(current.feels_like.value))?,
+                pressure: Pressure::from_hpa(current.pressure.value),
+                humidity: Percentage::new_checked(current.humidity.value).map_err(custom)?,
+                temp_min: temperature.temperature(temperature.min)?,
+                temp_max: temperature.temperature(temperature.max)?,
+                sea_level: Pressure::from_hpa(current.pressure.value),
+                grnd_level: None,
+            },
+            visibility: current.visibility.value,
+            wind,
+            clouds: OWClouds {
+                all: Percentage::new_checked(current.clouds.value).map_err(custom)?,
+            },
+            rain,
+            snow,
+            dt: parse_utc(&current.lastupdate.value)?.timestamp() as u64,
+            sys: OWSys {
+                country: city.country,
+                sunrise: parse_utc(&city.sun.rise)?,
+                sunset: parse_utc(&city.sun.set)?,
+            },
+            timezone: FixedOffset::east_opt(city.timezone)
+                .ok_or_else(|| DeError::Custom("invalid timezone shift from UTC".into()))?,
+            id: city.id,
+            name: city.name,
+            units: temperature.units()?,
+        })
+    }
+}
+
+/// XML responses write times as ISO 8601 without an offset, in UTC
+fn parse_utc(time: &str) -> Result<DateTime<Utc>, DeError> {
+    NaiveDateTime::parse_from_str(time, "%Y-%m-%dT%H:%M:%S")
+        .map(|time| time.and_utc())
+        .map_err(custom)
+}
+
+fn custom(error: impl std::fmt::Display) -> DeError {
+    DeError::Custom(error.to_string())
+}
+
+impl OpenWeatherClient {
+    /// Request the current weather at a position as XML and parse it, see [`parse_current_weather_xml`] for how the
+    /// result differs from [`OpenWeatherClient::current_weather`]. This is a blocking HTTP request.
+    pub fn current_weather_xml(
+        &self,
+        coords: GeodeticCoords,
+    ) -> Result<OWCurrentWeatherResponse, OpenWxError> {
+        let response_text = self.raw_current_weather(coords, ResponseMode::Xml)?;
+
+        parse_current_weather_xml(&response_text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::tests::zocca;
+
+    /// [`crate::types::tests::ZOCCA_RESPONSE`] as OpenWeather writes it in XML
+    const ZOCCA_XML_RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <current>
+            <city id="3163858" name="Zocca">
+                <coord lon="10.99" lat="44.34"></coord>
+                <country>IT</country>
+                <timezone>3600</timezone>
+                <sun rise="2025-11-14T06:10:41" set="2025-11-14T15:50:29"></sun>
+            </city>
+            <temperature value="281.29" min="279.38" max="281.29" unit="kelvin"></temperature>
+            <feels_like value="279.63" unit="kelvin"></feels_like>
+            <humidity value="95" unit="%"></humidity>
+            <pressure value="1024" unit="hPa"></pressure>
+            <wind>
+                <speed value="2.69" unit="m/s" name="Light breeze"></speed>
+                <gusts value="3.51"></gusts>
+                <direction value="202" code="SSW" name="South-southwest"></direction>
+            </wind>
+            <clouds value="78" name="broken clouds"></clouds>
+            <visibility value="10000"></visibility>
+            <precipitation mode="no"></precipitation>
+            <weather number="803" value="broken clouds" icon="04n"></weather>
+            <lastupdate value="2025-11-13T23:45:22"></lastupdate>
+        </current>"#;
+
+    #[test]
+    fn xml_matches_json() {
+        let xml = parse_current_weather_xml(ZOCCA_XML_RESPONSE).unwrap();
+        let json = zocca();
+
+        // Serialized without a ground level pressure, and still read back as the same response
+        let text = serde_json::to_string(&xml).unwrap();
+        assert_eq!(crate::parse_current_weather(&text).unwrap(), xml);
+
+        assert_eq!(xml.coord, json.coord);
+        assert_eq!(xml.name, json.name);
+        assert_eq!(xml.id, json.id);
+        assert_eq!(xml.dt, json.dt);
+        assert_eq!(xml.timezone, json.timezone);
+        assert_eq!(xml.sys.country, json.sys.country);
+        assert_eq!(xml.sys.sunrise, json.sys.sunrise);
+        assert_eq!(xml.sys.sunset, json.sys.sunset);
+        assert_eq!(xml.main.temp, json.main.temp);
+        assert_eq!(xml.main.feels_like, json.main.feels_like);
+        assert_eq!(xml.main.temp_min, json.main.temp_min);
+        assert_eq!(xml.main.temp_max, json.main.temp_max);
+        assert_eq!(xml.main.pressure, json.main.pressure);
+        assert_eq!(xml.main.sea_level, json.main.sea_level);
+        assert_eq!(xml.main.grnd_level, None);
+        assert_eq!(xml.main.humidity, json.main.humidity);
+        assert_eq!(xml.clouds.all, json.clouds.all);
+        assert_eq!(xml.visibility, json.visibility);
+
+        let (xml_wind, json_wind) = (xml.wind.unwrap(), json.wind.unwrap());
+        assert_eq!(xml_wind.speed, json_wind.speed);
+        assert_eq!(xml_wind.gust, json_wind.gust);
+        assert_eq!(xml_wind.deg, json_wind.deg);
+
+        assert_eq!(xml.weather[0].id, json.weather[0].id);
+        assert_eq!(xml.weather[0].main, json.weather[0].main);
+        assert_eq!(xml.weather[0].description, json.weather[0].description);
+        assert_eq!(xml.weather[0].icon, json.weather[0].icon);
+        assert!(xml.rain.is_none() && xml.snow.is_none());
+    }
+
+    #[test]
+    fn xml_precipitation_and_calm() {
+        let rainy = ZOCCA_XML_RESPONSE
+            .replace(
+                r#"<precipitation mode="no">"#,
+                r#"<precipitation value="3.37" mode="rain" unit="1h">"#,
+            )
+            .replace(
+                r#"<direction value="202" code="SSW" name="South-southwest">"#,
+                "<direction>",
+            );
+        let rainy = parse_current_weather_xml(&rainy).unwrap();
+
+        assert_eq!(
+            rainy.rain.unwrap().one_hour,
+            Some(PrecipRate::from_mm_per_hour(3.37))
+        );
+        assert!(rainy.wind.is_none());
+
+        assert!(matches!(
+            parse_current_weather_xml("<current></current>"),
+            Err(OpenWxError::XmlParseError(_))
+        ));
+    }
+}